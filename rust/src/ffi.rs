@@ -38,21 +38,94 @@ unsafe fn ptr_to_str(ptr: *const c_char) -> Result<String, String> {
     }
 }
 
-/// Convert raw C string to a rust string wrapped by [Option].
+/// Maximum length in bytes of a user-supplied configuration string, excluding the null terminator.
 ///
-/// Returns an error if the pointer is non-null but the data is not valid UTF-8.
+/// Generous enough for hostnames, client names and IAM tokens, while bounding the read of a C string that
+/// is missing its null terminator.
+const MAX_CONFIG_STR_LEN: usize = 16 * 1024;
+
+/// Convert raw C string to a rust string, reading at most `max_len` bytes before the null terminator.
+///
+/// Returns an error if no null terminator is found within `max_len` bytes, or if the data is not valid UTF-8.
+/// Unlike [`ptr_to_str`], a string that is unterminated or longer than `max_len` is never read past `max_len + 1` bytes.
 ///
 /// # Safety
 ///
-/// * `ptr` must be able to be safely casted to a valid [`CStr`] via [`CStr::from_ptr`]. See the safety documentation of [`std::ffi::CStr::from_ptr`].
-unsafe fn ptr_to_opt_str(ptr: *const c_char) -> Result<Option<String>, String> {
+/// * `ptr` must be `null` or point to a buffer that is readable up to its null terminator or `max_len + 1` bytes, whichever comes first.
+unsafe fn ptr_to_str_bounded(ptr: *const c_char, max_len: usize) -> Result<String, String> {
+    if ptr.is_null() {
+        return Ok(String::new());
+    }
+    let len = (0..=max_len)
+        .find(|&i| unsafe { *ptr.add(i) } == 0)
+        .ok_or_else(|| {
+            format!("C string is longer than {max_len} bytes or is not null-terminated")
+        })?;
+    let bytes = unsafe { from_raw_parts(ptr as *const u8, len) };
+    std::str::from_utf8(bytes)
+        .map(|s| s.to_owned())
+        .map_err(|e| format!("Invalid UTF-8 in C string: {e}"))
+}
+
+/// Convert raw C string to a rust string wrapped by [Option], see [`ptr_to_str_bounded`].
+///
+/// # Safety
+///
+/// * See the safety documentation of [`ptr_to_str_bounded`].
+unsafe fn ptr_to_opt_str_bounded(
+    ptr: *const c_char,
+    max_len: usize,
+) -> Result<Option<String>, String> {
     if !ptr.is_null() {
-        unsafe { ptr_to_str(ptr) }.map(Some)
+        unsafe { ptr_to_str_bounded(ptr, max_len) }.map(Some)
     } else {
         Ok(None)
     }
 }
 
+/// Convert the cursor ID of a cluster scan, failing on invalid UTF-8 so that the scan is not silently restarted from "0".
+///
+/// # Safety
+///
+/// * `ptr` must not be `null`. See the safety documentation of [`CStr::from_ptr`].
+pub(crate) unsafe fn scan_cursor_id(ptr: *const c_char) -> Result<String, String> {
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map(|s| s.to_owned())
+        .map_err(|e| format!("Invalid UTF-8 in cursor ID: {e}"))
+}
+
+/// Retries of commands rejected with a `READONLY` error, see [`read_only_retry_config`].
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ReadOnlyRetryConfig {
+    /// Maximal number of retries, `0` disables retrying.
+    pub max_retries: u32,
+    /// Milliseconds before the first retry, doubled for every following retry.
+    pub delay_ms: u32,
+}
+
+/// A mirror of [`glide_core::client::PeriodicCheck`] adopted for FFI.
+///
+/// The discriminants must match the C# `PeriodicChecksMode` enum.
+#[repr(u32)]
+#[derive(Clone, Copy)]
+pub enum PeriodicChecksMode {
+    Enabled = 0,
+    Disabled = 1,
+    ManualInterval = 2,
+}
+
+/// Periodic topology checks settings, see [`periodic_check`].
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct PeriodicChecksConfig {
+    /// A [`PeriodicChecksMode`] discriminant, kept as an integer so that an unknown one is rejected, see [`periodic_check`].
+    pub mode: u32,
+    /// Seconds between checks, only used by [`PeriodicChecksMode::ManualInterval`].
+    pub interval_secs: u32,
+}
+
 /// A mirror of [`ConnectionRequest`] adopted for FFI.
 #[repr(C)]
 #[derive(Clone, Copy)]
@@ -60,12 +133,16 @@ pub struct CompressionConfig {
     pub min_compression_size: usize,
     pub has_compression_level: bool,
     pub compression_level: i32,
-    pub backend: CompressionBackend,
+    /// A [`CompressionBackend`] discriminant, kept as an integer so that an unknown one is rejected, see [`compression_config`].
+    pub backend: u32,
     pub enabled: bool,
     pub has_max_decompressed_size: bool,
     pub max_decompressed_size: u64,
 }
 
+/// A mirror of [`glide_core::compression::CompressionBackendType`] adopted for FFI.
+///
+/// The discriminants must match the C# `CompressionBackend` enum.
 #[repr(u32)]
 #[derive(Clone, Copy)]
 pub enum CompressionBackend {
@@ -105,6 +182,16 @@ pub struct ClientSideCacheConfig {
     pub server_assisted: bool,
 }
 
+/// Opt-in auto-pipelining of unrouted commands, handled by the FFI layer rather than glide-core.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct AutoPipelineConfig {
+    /// Flush once this many commands are queued.
+    pub max_batch_size: u32,
+    /// Flush once this many milliseconds elapsed since the first command of the window was queued.
+    pub flush_interval_ms: u32,
+}
+
 /// A mirror of [`ConnectionRequest`] adopted for FFI.
 #[repr(C)]
 #[derive(Clone, Copy)]
@@ -148,11 +235,40 @@ pub struct ConnectionConfig {
     pub node_discovery_mode: NodeDiscoveryMode,
     pub has_client_side_cache_config: bool,
     pub client_side_cache_config: ClientSideCacheConfig,
-    /*
-    TODO below
-    pub periodic_checks: Option<PeriodicCheck>,
-    pub inflight_requests_limit: Option<u32>
-    */
+    pub has_auto_pipeline_config: bool,
+    pub auto_pipeline_config: AutoPipelineConfig,
+    pub has_read_request_timeout: bool,
+    pub read_request_timeout: u32,
+    pub has_write_request_timeout: bool,
+    pub write_request_timeout: u32,
+    /// zero pointer is valid, means no key prefix is given (`None`), see [`apply_key_prefix`]
+    pub key_prefix: *const c_char,
+    pub has_subscribe_confirmation_timeout: bool,
+    pub subscribe_confirmation_timeout: u32,
+    /// Whether command replies keep their RESP string type, see [`ResponseValue::from_value_preserving_wire_types`].
+    pub preserve_wire_types: bool,
+    pub has_read_only_retry_config: bool,
+    pub read_only_retry_config: ReadOnlyRetryConfig,
+    pub has_inflight_requests_limit: bool,
+    pub inflight_requests_limit: u32,
+    pub has_periodic_checks_config: bool,
+    pub periodic_checks_config: PeriodicChecksConfig,
+    /// Replies larger than this fail their command, see [`max_response_bytes`].
+    pub has_max_response_bytes: bool,
+    pub max_response_bytes: u64,
+    /// The client certificate and private key for mutual TLS, see [`client_certificate`].
+    pub client_cert: *const u8,
+    pub client_cert_len: usize,
+    pub client_key: *const u8,
+    pub client_key_len: usize,
+    /// Whether Nagle's algorithm is disabled on the connections, the default `false` keeps it enabled.
+    pub tcp_nodelay: bool,
+    /// The library name sent with `CLIENT SETINFO`, a zero pointer keeps the compiled `GLIDE_NAME`.
+    pub client_lib_name: *const c_char,
+    /// The library version sent with `CLIENT SETINFO`, a zero pointer keeps glide-core's, see [`client_lib_version`].
+    pub client_lib_version: *const c_char,
+    /// Whether commands are checked by [`validate_command_arity`] before being sent, the default `false` leaves it to the server.
+    pub validate_command_arity: bool,
 }
 
 #[repr(C)]
@@ -177,7 +293,7 @@ unsafe fn convert_string_array(ptr: *const *const c_char, count: u32) -> Vec<Vec
         return Vec::new();
     }
 
-    let slice = unsafe { std::slice::from_raw_parts(ptr, count as usize) };
+    let slice = unsafe { from_raw_parts(ptr, count as usize) };
     slice
         .iter()
         .map(|&str_ptr| {
@@ -240,36 +356,47 @@ unsafe fn convert_pubsub_config(
 /// * `config_ptr` must not be `null`.
 /// * `config_ptr` must be a valid pointer to a [`ConnectionConfig`] struct.
 /// * Dereferenced [`ConnectionConfig`] struct and all nested structs must contain valid pointers.
-///   See the safety documentation of [`convert_node_addresses`], [`ptr_to_str`] and [`ptr_to_opt_str`].
+///   See the safety documentation of [`convert_node_addresses`] and [`ptr_to_str_bounded`].
 pub(crate) unsafe fn create_connection_request(
     config_ptr: *const ConnectionConfig,
 ) -> Result<ConnectionRequest, String> {
     let config = unsafe { *config_ptr };
+    let (client_cert, client_key) = unsafe { client_certificate(&config) }?;
     Ok(ConnectionRequest {
         read_from: if config.has_read_from {
             Some(match config.read_from.strategy {
                 ReadFromStrategy::Primary => coreReadFrom::Primary,
                 ReadFromStrategy::PreferReplica => coreReadFrom::PreferReplica,
-                ReadFromStrategy::AZAffinity => {
-                    coreReadFrom::AZAffinity(unsafe { ptr_to_str(config.read_from.az) }?)
-                }
+                ReadFromStrategy::AZAffinity => coreReadFrom::AZAffinity(unsafe {
+                    ptr_to_str_bounded(config.read_from.az, MAX_CONFIG_STR_LEN)
+                }?),
                 ReadFromStrategy::AZAffinityReplicasAndPrimary => {
                     coreReadFrom::AZAffinityReplicasAndPrimary(unsafe {
-                        ptr_to_str(config.read_from.az)
+                        ptr_to_str_bounded(config.read_from.az, MAX_CONFIG_STR_LEN)
                     }?)
                 }
             })
         } else {
             None
         },
-        client_name: unsafe { ptr_to_opt_str(config.client_name) }?,
-        lib_name: Some(env!("GLIDE_NAME").to_string()),
+        client_name: unsafe { ptr_to_opt_str_bounded(config.client_name, MAX_CONFIG_STR_LEN) }?,
+        lib_name: Some(
+            unsafe { ptr_to_opt_str_bounded(config.client_lib_name, MAX_CONFIG_STR_LEN) }?
+                .unwrap_or_else(|| env!("GLIDE_NAME").to_string()),
+        ),
         authentication_info: if config.has_authentication_info {
             let auth_info = config.authentication_info;
             let iam_config = if auth_info.has_iam_credentials {
                 Some(glide_core::client::IamAuthenticationConfig {
-                    cluster_name: unsafe { ptr_to_str(auth_info.iam_credentials.cluster_name) }?,
-                    region: unsafe { ptr_to_str(auth_info.iam_credentials.region) }?,
+                    cluster_name: unsafe {
+                        ptr_to_str_bounded(
+                            auth_info.iam_credentials.cluster_name,
+                            MAX_CONFIG_STR_LEN,
+                        )
+                    }?,
+                    region: unsafe {
+                        ptr_to_str_bounded(auth_info.iam_credentials.region, MAX_CONFIG_STR_LEN)
+                    }?,
                     service_type: match auth_info.iam_credentials.service_type {
                         ServiceType::ElastiCache => glide_core::iam::ServiceType::ElastiCache,
                         ServiceType::MemoryDB => glide_core::iam::ServiceType::MemoryDB,
@@ -288,8 +415,17 @@ pub(crate) unsafe fn create_connection_request(
             };
 
             Some(CoreAuthenticationInfo {
-                username: unsafe { ptr_to_opt_str(auth_info.username) }?,
-                password: unsafe { ptr_to_opt_str(auth_info.password) }?,
+                username: unsafe {
+                    ptr_to_opt_str_bounded(auth_info.username, MAX_CONFIG_STR_LEN)
+                }?,
+                password: if auth_info.has_password {
+                    Some(
+                        unsafe { ptr_to_opt_str_bounded(auth_info.password, MAX_CONFIG_STR_LEN) }?
+                            .unwrap_or_default(),
+                    )
+                } else {
+                    None
+                },
                 iam_config,
             })
         } else {
@@ -300,7 +436,10 @@ pub(crate) unsafe fn create_connection_request(
         tls_mode: config.has_tls.then_some(config.tls_mode),
         addresses: unsafe { convert_node_addresses(config.addresses, config.address_count) }?,
         cluster_mode_enabled: config.cluster_mode,
-        request_timeout: config.has_request_timeout.then_some(config.request_timeout),
+        request_timeout: (config.has_request_timeout
+            || config.has_read_request_timeout
+            || config.has_write_request_timeout)
+            .then(|| request_timeouts(config).longest()),
         connection_timeout: config
             .has_connection_timeout
             .then_some(config.connection_timeout),
@@ -317,29 +456,17 @@ pub(crate) unsafe fn create_connection_request(
                 config.root_certs_len,
             )
         },
+        client_cert,
+        client_key,
+        tcp_nodelay: config.tcp_nodelay,
         pubsub_reconciliation_interval_ms: config
             .has_pubsub_reconciliation_interval_ms
             .then_some(config.pubsub_reconciliation_interval_ms),
-        compression_config: config.has_compression_config.then_some(
-            glide_core::compression::CompressionConfig {
-                enabled: config.compression_config.enabled,
-                min_compression_size: config.compression_config.min_compression_size,
-                compression_level: config
-                    .compression_config
-                    .has_compression_level
-                    .then_some(config.compression_config.compression_level),
-                backend: match config.compression_config.backend {
-                    CompressionBackend::Zstd => {
-                        glide_core::compression::CompressionBackendType::Zstd
-                    }
-                    CompressionBackend::Lz4 => glide_core::compression::CompressionBackendType::Lz4,
-                },
-                max_decompressed_size: config
-                    .compression_config
-                    .has_max_decompressed_size
-                    .then_some(config.compression_config.max_decompressed_size as usize),
-            },
-        ),
+        inflight_requests_limit: config
+            .has_inflight_requests_limit
+            .then_some(config.inflight_requests_limit),
+        periodic_checks: periodic_check(&config)?,
+        compression_config: compression_config(&config)?,
         read_only: config.read_only,
 
         // Client-side cache configuration
@@ -347,7 +474,7 @@ pub(crate) unsafe fn create_connection_request(
             use redis::cache::EvictionPolicy as CoreEvictionPolicy;
             let csc = config.client_side_cache_config;
             Some(glide_core::client::ClientSideCache {
-                cache_id: unsafe { ptr_to_str(csc.cache_id) }?,
+                cache_id: unsafe { ptr_to_str_bounded(csc.cache_id, MAX_CONFIG_STR_LEN) }?,
                 max_cache_kb: csc.max_cache_kb,
                 entry_ttl_ms: csc.entry_ttl_ms,
                 eviction_policy: if csc.has_eviction_policy {
@@ -372,19 +499,220 @@ pub(crate) unsafe fn create_connection_request(
         },
 
         // Unimplemented configuration options.
-        client_cert: Vec::new(),
-        client_key: Vec::new(),
         client_cert_path: None,
         client_key_path: None,
         cert_reload: None,
-        tcp_nodelay: false,
-        periodic_checks: None,
-        inflight_requests_limit: None,
         address_resolver: None,
         client_circuit_breaker: None,
     })
 }
 
+/// Read the client certificate and private key for mutual TLS, both empty if the client doesn't authenticate itself.
+///
+/// # Safety
+/// * `client_cert` and `client_key` must be null or point to `client_cert_len` and `client_key_len` bytes, see [`convert_bytes_to_owned`].
+unsafe fn client_certificate(config: &ConnectionConfig) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let cert = unsafe { convert_bytes_to_owned(config.client_cert, config.client_cert_len) };
+    let key = unsafe { convert_bytes_to_owned(config.client_key, config.client_key_len) };
+    if cert.is_empty() != key.is_empty() {
+        return Err("Client certificate and private key must be given together".into());
+    }
+    Ok((cert, key))
+}
+
+/// Read the library version to report with `CLIENT SETINFO LIB-VER` from a [`ConnectionConfig`], `None` to keep glide-core's.
+///
+/// [`ConnectionRequest`] has no library version, so it is sent once the client is connected, and is not reapplied when
+/// glide-core reconnects. It is not sent by lazily connected clients, which have no connection yet.
+///
+/// # Safety
+/// * `client_lib_version` in the dereferenced struct must be `null` or a valid C string.
+pub(crate) unsafe fn client_lib_version(
+    config: &ConnectionConfig,
+) -> Result<Option<String>, String> {
+    unsafe { ptr_to_opt_str_bounded(config.client_lib_version, MAX_CONFIG_STR_LEN) }
+}
+
+/// The request timeout glide-core applies to the commands of a client created with `request`, in milliseconds.
+pub(crate) fn core_request_timeout(request: &ConnectionRequest) -> u32 {
+    request
+        .request_timeout
+        .unwrap_or(DEFAULT_REQUEST_TIMEOUT_MS)
+}
+
+/// Summarize the settings applied by a client created with `request` as a [`Value::Map`], for diagnostics.
+///
+/// Settings which were not configured are reported with the defaults glide-core applies to them. Secrets are never
+/// reported: the password, IAM credentials and certificates are only reported as set or not.
+pub(crate) fn effective_config(
+    request: &ConnectionRequest,
+    request_timeouts: RequestTimeouts,
+    max_response_bytes: Option<u64>,
+) -> Value {
+    let string = |value: &str| Value::BulkString(value.as_bytes().to_vec());
+    let opt_string = |value: &Option<String>| value.as_deref().map_or(Value::Nil, string);
+    let auth = request.authentication_info.as_ref();
+
+    let settings = [
+        (
+            "addresses",
+            Value::Array(
+                request
+                    .addresses
+                    .iter()
+                    .map(|address| string(&format!("{}:{}", address.host, address.port)))
+                    .collect(),
+            ),
+        ),
+        ("cluster_mode", Value::Boolean(request.cluster_mode_enabled)),
+        ("database_id", Value::Int(request.database_id)),
+        (
+            "protocol",
+            string(&format!(
+                "{:?}",
+                request.protocol.unwrap_or(redis::ProtocolVersion::RESP3)
+            )),
+        ),
+        (
+            "tls_mode",
+            string(&format!("{:?}", request.tls_mode.unwrap_or(TlsMode::NoTls))),
+        ),
+        (
+            "read_from",
+            string(&format!(
+                "{:?}",
+                request.read_from.clone().unwrap_or(coreReadFrom::Primary)
+            )),
+        ),
+        (
+            "request_timeout_ms",
+            Value::Int(request_timeouts.default.into()),
+        ),
+        (
+            "read_request_timeout_ms",
+            Value::Int(request_timeouts.read.into()),
+        ),
+        (
+            "write_request_timeout_ms",
+            Value::Int(request_timeouts.write.into()),
+        ),
+        (
+            "connection_timeout_ms",
+            Value::Int(
+                request
+                    .connection_timeout
+                    .unwrap_or(DEFAULT_CONNECTION_TIMEOUT_MS)
+                    .into(),
+            ),
+        ),
+        (
+            "inflight_requests_limit",
+            Value::Int(
+                request
+                    .inflight_requests_limit
+                    .unwrap_or(DEFAULT_INFLIGHT_REQUESTS_LIMIT)
+                    .into(),
+            ),
+        ),
+        ("lazy_connect", Value::Boolean(request.lazy_connect)),
+        ("client_name", opt_string(&request.client_name)),
+        ("lib_name", opt_string(&request.lib_name)),
+        (
+            "username",
+            opt_string(&auth.and_then(|auth| auth.username.clone())),
+        ),
+        (
+            "has_password",
+            Value::Boolean(auth.is_some_and(|auth| auth.password.is_some())),
+        ),
+        (
+            "has_iam_authentication",
+            Value::Boolean(auth.is_some_and(|auth| auth.iam_config.is_some())),
+        ),
+        (
+            "root_certificates",
+            Value::Int(i64::try_from(request.root_certs.len()).unwrap_or(i64::MAX)),
+        ),
+        (
+            "has_client_certificate",
+            Value::Boolean(!request.client_cert.is_empty()),
+        ),
+        (
+            "compression",
+            Value::Boolean(request.compression_config.is_some()),
+        ),
+        (
+            "client_side_cache",
+            Value::Boolean(request.client_side_cache.is_some()),
+        ),
+        (
+            "max_response_bytes",
+            max_response_bytes.map_or(Value::Nil, |max| {
+                Value::Int(i64::try_from(max).unwrap_or(i64::MAX))
+            }),
+        ),
+    ];
+    Value::Map(
+        settings
+            .into_iter()
+            .map(|(name, value)| (Value::SimpleString(name.into()), value))
+            .collect(),
+    )
+}
+
+/// Convert the compression settings, `None` disables compression.
+///
+/// Fails for an unknown [`CompressionBackend`], e.g. passed by a binding built against a newer version of this library.
+fn compression_config(
+    config: &ConnectionConfig,
+) -> Result<Option<glide_core::compression::CompressionConfig>, String> {
+    use glide_core::compression::CompressionBackendType;
+    if !config.has_compression_config {
+        return Ok(None);
+    }
+    let compression = config.compression_config;
+    let backend = match compression.backend {
+        backend if backend == CompressionBackend::Zstd as u32 => CompressionBackendType::Zstd,
+        backend if backend == CompressionBackend::Lz4 as u32 => CompressionBackendType::Lz4,
+        backend => return Err(format!("Unknown compression backend {backend}")),
+    };
+    Ok(Some(glide_core::compression::CompressionConfig {
+        enabled: compression.enabled,
+        min_compression_size: compression.min_compression_size,
+        compression_level: compression
+            .has_compression_level
+            .then_some(compression.compression_level),
+        backend,
+        max_decompressed_size: compression
+            .has_max_decompressed_size
+            .then_some(compression.max_decompressed_size as usize),
+    }))
+}
+
+/// Convert the periodic topology checks settings, `None` keeps glide-core's default checks.
+fn periodic_check(
+    config: &ConnectionConfig,
+) -> Result<Option<glide_core::client::PeriodicCheck>, String> {
+    use glide_core::client::PeriodicCheck;
+    if !config.has_periodic_checks_config {
+        return Ok(None);
+    }
+    let periodic_checks = config.periodic_checks_config;
+    Ok(Some(match periodic_checks.mode {
+        mode if mode == PeriodicChecksMode::Enabled as u32 => PeriodicCheck::Enabled,
+        mode if mode == PeriodicChecksMode::Disabled as u32 => PeriodicCheck::Disabled,
+        mode if mode == PeriodicChecksMode::ManualInterval as u32 => {
+            if periodic_checks.interval_secs == 0 {
+                return Err("Periodic checks interval must be positive".into());
+            }
+            PeriodicCheck::ManualInterval(std::time::Duration::from_secs(
+                periodic_checks.interval_secs.into(),
+            ))
+        }
+        mode => return Err(format!("Unknown periodic checks mode {mode}")),
+    }))
+}
+
 /// A mirror of [`NodeAddress`] adopted for FFI.
 #[repr(C)]
 pub struct Address {
@@ -399,16 +727,16 @@ pub struct Address {
 /// * `len` must not be greater than [`isize::MAX`]. See the safety documentation of [`std::slice::from_raw_parts`].
 /// * `data` must not be `null`.
 /// * `data` must point to `len` consecutive properly initialized [`Address`] structs.
-/// * Each [`Address`] dereferenced by `data` must contain a valid string pointer. See the safety documentation of [`ptr_to_str`].
+/// * Each [`Address`] dereferenced by `data` must contain a valid string pointer. See the safety documentation of [`ptr_to_str_bounded`].
 unsafe fn convert_node_addresses(
     data: *const *const Address,
     len: usize,
 ) -> Result<Vec<NodeAddress>, String> {
-    unsafe { std::slice::from_raw_parts(data as *mut Address, len) }
+    unsafe { from_raw_parts(data as *mut Address, len) }
         .iter()
         .map(|addr| {
             Ok(NodeAddress {
-                host: unsafe { ptr_to_str(addr.host) }?,
+                host: unsafe { ptr_to_str_bounded(addr.host, MAX_CONFIG_STR_LEN) }?,
                 port: addr.port,
             })
         })
@@ -438,6 +766,9 @@ pub enum ReadFromStrategy {
 pub struct AuthenticationInfo {
     pub username: *const c_char,
     pub password: *const c_char,
+    /// Whether a password is configured. Distinguishes an empty password, for servers configured with `requirepass ""`,
+    /// from no password. A null `password` is then treated as empty.
+    pub has_password: bool,
     pub has_iam_credentials: bool,
     pub iam_credentials: IamCredentials,
 }
@@ -500,7 +831,9 @@ pub struct RouteInfo {
     pub route_type: RouteType,
     pub slot_id: i32,
     /// zero pointer is valid, means no slot key is given (`None`)
-    pub slot_key: *const c_char,
+    pub slot_key: *const u8,
+    /// length of `slot_key` in bytes, since binary keys may contain NUL bytes
+    pub slot_key_len: usize,
     pub slot_type: SlotType,
     /// zero pointer is valid, means no hostname is given (`None`)
     pub hostname: *const c_char,
@@ -511,8 +844,10 @@ pub struct RouteInfo {
 ///
 /// # Safety
 /// * `route_ptr` could be `null`, but if it is not `null`, it must be a valid pointer to a [`RouteInfo`] struct.
-/// * `slot_key` and `hostname` in dereferenced [`RouteInfo`] struct must contain valid string pointers when corresponding `route_type` is set.
+/// * `hostname` in dereferenced [`RouteInfo`] struct must contain a valid string pointer when corresponding `route_type` is set.
 ///   See description of [`RouteInfo`] and the safety documentation of [`ptr_to_str`].
+/// * `slot_key` in dereferenced [`RouteInfo`] struct must point to `slot_key_len` bytes when corresponding `route_type` is set.
+///   See the safety documentation of [`from_raw_parts`].
 pub(crate) unsafe fn create_route(
     route_ptr: *const RouteInfo,
     cmd: Option<&Cmd>,
@@ -525,11 +860,11 @@ pub(crate) unsafe fn create_route(
         RouteType::Random => Ok(Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))),
         RouteType::AllNodes => Ok(Some(RoutingInfo::MultiNode((
             MultipleNodeRoutingInfo::AllNodes,
-            cmd.and_then(|c| ResponsePolicy::for_command(&c.command().unwrap())),
+            cmd.and_then(|c| ResponsePolicy::for_command(&c.command()?)),
         )))),
         RouteType::AllPrimaries => Ok(Some(RoutingInfo::MultiNode((
             MultipleNodeRoutingInfo::AllMasters,
-            cmd.and_then(|c| ResponsePolicy::for_command(&c.command().unwrap())),
+            cmd.and_then(|c| ResponsePolicy::for_command(&c.command()?)),
         )))),
         RouteType::SlotId => Ok(Some(RoutingInfo::SingleNode(
             SingleNodeRoutingInfo::SpecificNode(Route::new(
@@ -539,9 +874,11 @@ pub(crate) unsafe fn create_route(
         ))),
         RouteType::SlotKey => Ok(Some(RoutingInfo::SingleNode(
             SingleNodeRoutingInfo::SpecificNode(Route::new(
-                redis::cluster_topology::get_slot(
-                    unsafe { ptr_to_str(route.slot_key) }?.as_bytes(),
-                ),
+                redis::cluster_topology::get_slot(if route.slot_key.is_null() {
+                    &[]
+                } else {
+                    unsafe { from_raw_parts(route.slot_key, route.slot_key_len) }
+                }),
                 (&route.slot_type).into(),
             )),
         ))),
@@ -599,6 +936,17 @@ pub(crate) unsafe fn convert_byte_array_to_owned(
         .collect()
 }
 
+/// Copies a byte array to an owned vector, which is empty if `data` is null.
+///
+/// # Safety
+/// * `data` must be null or point to `len` consecutive properly initialized bytes, see [`std::slice::from_raw_parts`].
+pub(crate) unsafe fn convert_bytes_to_owned(data: *const u8, len: usize) -> Vec<u8> {
+    if data.is_null() || len == 0 {
+        return Vec::new();
+    }
+    unsafe { from_raw_parts(data, len) }.to_vec()
+}
+
 pub(crate) fn convert_vec_to_pointer<T>(mut vec: Vec<T>) -> (*const T, usize) {
     vec.shrink_to_fit();
     let vec_ptr = vec.as_ptr();
@@ -622,24 +970,47 @@ pub enum ValueType {
     BulkString = 8,
     OK = 9,
     Error = 10,
+    /// Only reported by [`ResponseValue::from_value_preserving_wire_types`], otherwise reported as [`ValueType::String`].
+    SimpleString = 11,
+    /// Only reported by [`ResponseValue::from_value_preserving_wire_types`], otherwise reported as [`ValueType::String`].
+    VerbatimString = 12,
+    /// A push frame returned inline by a command, stored like a [`ValueType::Array`] whose first element is the
+    /// [`PushKind`] as a [`ValueType::Int`], followed by the payload of the push.
+    Push = 13,
+    /// A RESP3 big number, stored as its decimal digits, with a leading `-` if negative.
+    BigNumber = 14,
+    /// A value with RESP3 attributes, stored like a [`ValueType::Array`] of exactly 2 elements: the value itself followed
+    /// by its attributes as a [`ValueType::Map`].
+    Attribute = 15,
 }
 
 /// Represents FFI-safe variant of [`Value`].
 /// * For [`Value::Nil`] and [`Value::Okay`], only [`ResponseValue::typ`] is stored.
 /// * Simple values such as [`Value::Int`], [`Value::Double`], and [`Value::Boolean`] are stored in [`ResponseValue::val`],
 ///   while corresponding [`ResponseValue::typ`] is set.
-/// * For complex values, such as [`Value::BulkString`], [`Value::VerbatimString`], [`Value::SimpleString`], only a pointer
-///   is stored in [`ResponseValue::val`], while corresponding [`ResponseValue::typ`] and [`ResponseValue::size`] are set.
+/// * A [`Value::Double`] is stored as its IEEE 754 bits. Infinities keep their sign, and every NaN, which RESP3 allows as
+///   `nan`, is stored as the canonical quiet NaN [`f64::NAN`], so the bits of a NaN never depend on its payload.
+/// * For complex values, such as [`Value::BulkString`], [`Value::VerbatimString`], [`Value::SimpleString`] and
+///   [`Value::BigNumber`], only a pointer is stored in [`ResponseValue::val`], while corresponding [`ResponseValue::typ`] and [`ResponseValue::size`] are set.
 /// * Way more complex types are stored by reference. For [`Value::Array`], [`Value::Set`] and [`Value::Map`], in
 ///   [`ResponseValue::val`] a pointer to an array of another [`ResponseValue`] is stored and [`ResponseValue::size`] contains
 ///   the array length (for a map - it is 2x map size).
+/// * A [`Value::Push`] is stored as an array of its kind, as a [`PushKind`] in a [`ValueType::Int`], followed by its data.
+/// * A [`Value::Attribute`] is stored as an array of its data followed by its attributes as a map, so that the data is
+///   always the first element.
+/// * A [`Value::Map`] is flattened to the entries `[key0, val0, key1, val1, ...]`, in the order of the map. Every key and
+///   value is a single [`ResponseValue`], so a key or value which is itself an array, set or map is stored by reference
+///   and never spliced into the flattened entries. The entry at an even index is therefore always a key, and the entry
+///   following it its value.
 #[repr(C)]
 #[derive(Default, Clone)]
 pub struct ResponseValue {
     pub typ: ValueType,
     pub val: i64,
-    /// For [`Value::BulkString`], [`Value::VerbatimString`], [`Value::SimpleString`] - size in bytes.
-    /// For Maps, sets and arrays - amount of values [`ResponseValue::val`] points to.
+    /// For [`Value::BulkString`], [`Value::VerbatimString`], [`Value::SimpleString`], [`Value::BigNumber`] - size in bytes.
+    /// For a [`ValueType::VerbatimString`], the bytes start with the 3 characters format and a colon, e.g. `txt:`, as on the wire.
+    /// For Maps, sets, arrays, pushes and attributes - amount of values [`ResponseValue::val`] points to. For maps it is
+    /// always even, for pushes it is at least 1, for attributes it is always 2.
     pub size: u32,
 }
 
@@ -665,6 +1036,19 @@ impl ResponseValue {
     /// Returns an error if any size component exceeds `u32::MAX`, preventing
     /// silent truncation across the FFI boundary.
     pub(crate) fn from_value(value: Value) -> Result<Self, String> {
+        Self::convert(value, false)
+    }
+
+    /// Build [`ResponseValue`] from a [`Value`], keeping the RESP type of every string, e.g. for a proxy re-serializing replies.
+    ///
+    /// Unlike [`ResponseValue::from_value`], a [`Value::SimpleString`] is stored as a [`ValueType::SimpleString`], and a
+    /// [`Value::VerbatimString`] as a [`ValueType::VerbatimString`] holding its format, see [`ResponseValue::size`].
+    pub(crate) fn from_value_preserving_wire_types(value: Value) -> Result<Self, String> {
+        Self::convert(value, true)
+    }
+
+    fn convert(value: Value, preserve_wire_types: bool) -> Result<Self, String> {
+        let convert = |value| Self::convert(value, preserve_wire_types);
         match value {
             Value::Nil => Ok(ResponseValue {
                 typ: ValueType::Null,
@@ -686,7 +1070,7 @@ impl ResponseValue {
             Value::Array(values) => {
                 let vec: Vec<ResponseValue> = values
                     .into_iter()
-                    .map(ResponseValue::from_value)
+                    .map(convert)
                     .collect::<Result<Vec<_>, _>>()?;
                 let (vec_ptr, size) = Self::convert_vec_to_ffi(vec, "Array")?;
                 Ok(ResponseValue {
@@ -698,7 +1082,7 @@ impl ResponseValue {
             Value::Set(values) => {
                 let vec: Vec<ResponseValue> = values
                     .into_iter()
-                    .map(ResponseValue::from_value)
+                    .map(convert)
                     .collect::<Result<Vec<_>, _>>()?;
                 let (vec_ptr, size) = Self::convert_vec_to_ffi(vec, "Set")?;
                 Ok(ResponseValue {
@@ -714,12 +1098,7 @@ impl ResponseValue {
             Value::Map(items) => {
                 let vec: Vec<ResponseValue> = items
                     .into_iter()
-                    .map(|(k, v)| {
-                        Ok::<_, String>(vec![
-                            ResponseValue::from_value(k)?,
-                            ResponseValue::from_value(v)?,
-                        ])
-                    })
+                    .map(|(k, v)| Ok::<_, String>(vec![convert(k)?, convert(v)?]))
                     .collect::<Result<Vec<_>, _>>()?
                     .into_iter()
                     .flatten()
@@ -733,7 +1112,7 @@ impl ResponseValue {
             }
             Value::Double(num) => Ok(ResponseValue {
                 typ: ValueType::Float,
-                val: num.to_bits() as i64,
+                val: if num.is_nan() { f64::NAN } else { num }.to_bits() as i64,
                 size: 0,
             }),
             Value::Boolean(boolean) => Ok(ResponseValue {
@@ -741,6 +1120,23 @@ impl ResponseValue {
                 val: if boolean { 1 } else { 0 },
                 size: 0,
             }),
+            Value::SimpleString(text) if preserve_wire_types => {
+                let (vec_ptr, size) = Self::convert_vec_to_ffi(text.into_bytes(), "String")?;
+                Ok(ResponseValue {
+                    typ: ValueType::SimpleString,
+                    val: vec_ptr as i64,
+                    size,
+                })
+            }
+            Value::VerbatimString { format, text } if preserve_wire_types => {
+                let text = format!("{format}:{text}");
+                let (vec_ptr, size) = Self::convert_vec_to_ffi(text.into_bytes(), "String")?;
+                Ok(ResponseValue {
+                    typ: ValueType::VerbatimString,
+                    val: vec_ptr as i64,
+                    size,
+                })
+            }
             Value::VerbatimString { format: _, text } | Value::SimpleString(text) => {
                 let (vec_ptr, size) = Self::convert_vec_to_ffi(text.into_bytes(), "String")?;
                 Ok(ResponseValue {
@@ -750,14 +1146,49 @@ impl ResponseValue {
                 })
             }
             Value::ServerError(err) => {
+                // A server error without details, e.g. a bare `-ERR`, is reported by its code.
+                let message = err.details().unwrap_or(err.code());
                 let (vec_ptr, size) =
-                    Self::convert_vec_to_ffi(err.details().unwrap().as_bytes().to_vec(), "Error")?;
+                    Self::convert_vec_to_ffi(message.as_bytes().to_vec(), "Error")?;
                 Ok(ResponseValue {
                     typ: ValueType::Error,
                     val: vec_ptr as i64,
                     size,
                 })
             }
+            Value::BigNumber(number) => {
+                let (vec_ptr, size) =
+                    Self::convert_vec_to_ffi(number.to_string().into_bytes(), "BigNumber")?;
+                Ok(ResponseValue {
+                    typ: ValueType::BigNumber,
+                    val: vec_ptr as i64,
+                    size,
+                })
+            }
+            Value::Attribute { data, attributes } => {
+                let vec = vec![convert(*data)?, convert(Value::Map(attributes))?];
+                let (vec_ptr, size) = Self::convert_vec_to_ffi(vec, "Attribute")?;
+                Ok(ResponseValue {
+                    typ: ValueType::Attribute,
+                    val: vec_ptr as i64,
+                    size,
+                })
+            }
+            Value::Push { kind, data } => {
+                let vec: Vec<ResponseValue> = std::iter::once(Ok(ResponseValue {
+                    typ: ValueType::Int,
+                    val: PushKind::from(&kind) as i64,
+                    size: 0,
+                }))
+                .chain(data.into_iter().map(convert))
+                .collect::<Result<Vec<_>, _>>()?;
+                let (vec_ptr, size) = Self::convert_vec_to_ffi(vec, "Push")?;
+                Ok(ResponseValue {
+                    typ: ValueType::Push,
+                    val: vec_ptr as i64,
+                    size,
+                })
+            }
             _ => Err("Unsupported Redis value type in FFI response serialization".into()),
         }
     }
@@ -765,12 +1196,16 @@ impl ResponseValue {
     /// Restore ownership and free all memory allocated by the current [`ResponseValue`] and referenced [`ResponseValue`] recursively.
     ///
     /// # Safety
-    /// * [`ResponseValue::val`] must not be `null` if [`ResponseValue::typ`] is [`ValueType::Array`] or [`ValueType::Set`] or [`ValueType::Map`] or [`ValueType::String`] or [`ValueType::BulkString`].
+    /// * [`ResponseValue::val`] must not be `null` if [`ResponseValue::typ`] is [`ValueType::Array`] or [`ValueType::Set`] or [`ValueType::Map`] or [`ValueType::Push`] or [`ValueType::Attribute`] or [`ValueType::String`] or [`ValueType::BulkString`].
     /// * [`ResponseValue::val`] must be able to be safely casted to a valid [`Vec<u8>`] (when [`ResponseValue::typ`] is [`ValueType::String`] or [`ValueType::BulkString`])
     ///   or [`Vec<ResponseValue>`] in other cases via [`Vec::from_raw_parts`]. See the safety documentation of [`Vec::from_raw_parts`].
     pub(crate) unsafe fn free_memory(&self) {
         match self.typ {
-            ValueType::Array | ValueType::Set | ValueType::Map => {
+            ValueType::Array
+            | ValueType::Set
+            | ValueType::Map
+            | ValueType::Push
+            | ValueType::Attribute => {
                 let vec = unsafe {
                     Vec::from_raw_parts(
                         self.val as *mut ResponseValue,
@@ -782,7 +1217,12 @@ impl ResponseValue {
                     unsafe { val.free_memory() };
                 }
             }
-            ValueType::String | ValueType::BulkString | ValueType::Error => {
+            ValueType::String
+            | ValueType::BulkString
+            | ValueType::Error
+            | ValueType::SimpleString
+            | ValueType::VerbatimString
+            | ValueType::BigNumber => {
                 let _ = unsafe {
                     Vec::from_raw_parts(self.val as *mut u8, self.size as usize, self.size as usize)
                 };
@@ -792,6 +1232,70 @@ impl ResponseValue {
     }
 }
 
+/// What `command_preferring_node` does when the preferred node can't serve a command.
+///
+/// The discriminants must match the C# `FFI.PreferredNodeFallback` enum.
+#[repr(u32)]
+#[derive(Clone, Copy)]
+pub enum PreferredNodeFallback {
+    /// Report an error.
+    Fail = 0,
+    /// Send the command to any replica serving its slot.
+    Replica = 1,
+    /// Send the command to the primary serving its slot.
+    Primary = 2,
+}
+
+/// The end of a list popped from or pushed to by `lmove` and `blmove`.
+///
+/// The discriminants must match the C# `FFI.ListDirection` enum.
+#[repr(u32)]
+#[derive(Clone, Copy)]
+pub enum ListDirection {
+    Left = 0,
+    Right = 1,
+}
+
+impl ListDirection {
+    pub(crate) fn as_arg(self) -> &'static str {
+        match self {
+            ListDirection::Left => "LEFT",
+            ListDirection::Right => "RIGHT",
+        }
+    }
+}
+
+/// The state of a command executed with `command_async`, as reported by `poll_command`.
+///
+/// The discriminants must match the C# `FFI.CommandPollStatus` enum.
+#[repr(u32)]
+#[derive(Clone, Copy)]
+pub enum CommandPollStatus {
+    /// The command has not completed yet.
+    Pending = 0,
+    /// The command succeeded, and its result was returned.
+    Completed = 1,
+    /// The command failed, and its error was returned.
+    Failed = 2,
+    /// The handle is unknown, or its result was already returned.
+    UnknownHandle = 3,
+}
+
+/// The class of operation a command is tagged with, selecting its request timeout.
+///
+/// The discriminants must match the C# `FFI.OperationClass` enum.
+#[repr(u32)]
+#[derive(Clone, Copy)]
+pub enum OperationClass {
+    /// Classified by the command itself, see [`RequestTimeouts::for_command`].
+    Unspecified = 0,
+    Read = 1,
+    Write = 2,
+    /// Opts the command out of request timeouts, including glide-core's, e.g. for admin commands such as `DEBUG RELOAD`, see
+    /// [`RequestTimeouts::for_command`].
+    Unbounded = 3,
+}
+
 #[repr(C)]
 #[derive(Clone, Copy)]
 pub struct CmdInfo {
@@ -799,6 +1303,40 @@ pub struct CmdInfo {
     pub args: *const *const u8,
     pub arg_count: usize,
     pub args_len: *const usize,
+    pub operation_class: OperationClass,
+    /// Whether string replies holding a number are reported as [`ValueType::Int`] or [`ValueType::Float`],
+    /// see [`decode_numeric_strings`].
+    pub decode_numbers: bool,
+    /// Whether the command's values are sent and its reply reported as is, without compression or decompression, e.g.
+    /// for already compressed data. Has no effect unless compression is enabled, see [`compression_config`].
+    /// Within a batch, only the compression of the command's values is skipped, the batch's replies are still decompressed.
+    pub skip_compression: bool,
+}
+
+/// Convert string replies holding a number, such as the replies of `INCRBYFLOAT` and `HINCRBYFLOAT`,
+/// to [`Value::Int`] or [`Value::Double`]. Elements of arrays are converted as well, other values are kept as is.
+///
+/// This must only be applied to commands known to reply with numbers, since arbitrary data may look like a number.
+pub(crate) fn decode_numeric_strings(value: Value) -> Value {
+    match value {
+        Value::BulkString(ref bytes) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(parse_number)
+            .unwrap_or(value),
+        Value::SimpleString(ref text) => parse_number(text).unwrap_or(value),
+        Value::Array(values) => {
+            Value::Array(values.into_iter().map(decode_numeric_strings).collect())
+        }
+        value => value,
+    }
+}
+
+/// Parse `text` as an integer, or else as a float.
+fn parse_number(text: &str) -> Option<Value> {
+    text.parse::<i64>()
+        .map(Value::Int)
+        .or_else(|_| text.parse::<f64>().map(Value::Double))
+        .ok()
 }
 
 #[repr(C)]
@@ -818,10 +1356,28 @@ pub struct BatchOptionsInfo {
     pub has_timeout: bool,
     pub timeout: u32,
     pub route_info: *const RouteInfo,
+    /// Whether a non-atomic batch is executed one command at a time, see `send_pipeline_sequential`.
+    pub sequential: bool,
+    /// Whether the batch consists of read commands only, see `read_only_route`.
+    pub is_read_only: bool,
+    /// Whether a read-only batch is rejected if it contains a write command, see [`check_read_only`].
+    pub validate_read_only: bool,
+    /// Whether a non-atomic batch is executed one command at a time and stopped at the first error, see `send_pipeline_sequential`.
+    pub stop_on_first_error: bool,
+    /// Whether a transaction is routed to the slot of its first keyed command, see [`first_key_route`].
+    pub route_by_first_key: bool,
 }
 
 /// Convert [`CmdInfo`] to a [`Cmd`].
 ///
+/// The arguments are borrowed from the caller and copied exactly once, into the command's own buffer. The command can't
+/// borrow them instead, since [`Cmd`] owns its data and is sent after the FFI call returned. In exchange, the caller's
+/// memory only needs to stay valid during this call, so large arguments can be passed from pinned managed memory
+/// rather than from an unmanaged copy.
+///
+/// If `validate_arity` is set, the arguments are checked with [`validate_command_arity`] first. The keys are prefixed with
+/// `key_prefix`, if given, see [`apply_key_prefix`].
+///
 /// # Safety
 /// * `cmd_ptr` must be able to be safely casted to a valid [`CmdInfo`]
 /// * `args` and `args_len` in a referred [`CmdInfo`] structure must not be `null`.
@@ -830,6 +1386,8 @@ pub struct BatchOptionsInfo {
 pub(crate) unsafe fn create_cmd(
     ptr: *const CmdInfo,
     compression_manager: Option<&std::sync::Arc<glide_core::compression::CompressionManager>>,
+    key_prefix: Option<&[u8]>,
+    validate_arity: bool,
 ) -> Result<Cmd, String> {
     let info = unsafe { *ptr };
     let arg_vec = unsafe { convert_byte_array_to_slices(info.args, info.arg_count, info.args_len) };
@@ -837,12 +1395,16 @@ pub(crate) unsafe fn create_cmd(
     let Some(mut cmd) = info.request_type.get_command() else {
         return Err("Couldn't fetch command type".into());
     };
+    if validate_arity {
+        validate_command_arity(info.request_type, &arg_vec)?;
+    }
 
     // Check if compression is enabled
-    let should_process_compression = compression_manager
-        .as_ref()
-        .map(|cm| cm.is_enabled())
-        .unwrap_or(false);
+    let should_process_compression = !info.skip_compression
+        && compression_manager
+            .as_ref()
+            .map(|cm| cm.is_enabled())
+            .unwrap_or(false);
 
     if should_process_compression {
         // Convert arg_vec to owned Vec<Vec<u8>> for compression processing
@@ -873,20 +1435,110 @@ pub(crate) unsafe fn create_cmd(
             return Err(format!("Compression failed: {}", err));
         }
 
+        // Prefix the keys found in the original arguments, which compression does not change.
+        if let Some(prefix) = key_prefix {
+            for index in key_prefix_indices(&cmd, &arg_vec)? {
+                owned_args[index].splice(0..0, prefix.iter().copied());
+            }
+        }
+
         // Use the compressed arguments
         for command_arg in &owned_args {
             cmd.arg(command_arg);
         }
     } else {
         // Use the original arguments
-        for command_arg in arg_vec {
-            cmd.arg(command_arg);
-        }
+        apply_key_prefix(&mut cmd, &arg_vec, key_prefix)?;
     }
 
     Ok(cmd)
 }
 
+/// Check the number of arguments of commands taking repeated groups of arguments, so that a malformed command fails
+/// with a clear error instead of the server's generic `wrong number of arguments`. Only applied if enabled with
+/// `validate_command_arity` of [`ConnectionConfig`].
+///
+/// `RequestType` has no arity metadata, so only the commands listed here are checked, whose syntax is fixed. Other
+/// commands, including custom commands, are left to the server.
+fn validate_command_arity(request_type: RequestType, args: &[&[u8]]) -> Result<(), String> {
+    match request_type {
+        RequestType::Del
+        | RequestType::Unlink
+        | RequestType::Exists
+        | RequestType::MGet
+        | RequestType::Touch
+            if args.is_empty() =>
+        {
+            let name = match request_type {
+                RequestType::Del => "DEL",
+                RequestType::Unlink => "UNLINK",
+                RequestType::Exists => "EXISTS",
+                RequestType::MGet => "MGET",
+                _ => "TOUCH",
+            };
+            Err(format!("{name} requires at least one key"))
+        }
+        RequestType::MSet | RequestType::MSetNX if args.is_empty() || args.len() % 2 != 0 => {
+            let name = if matches!(request_type, RequestType::MSet) {
+                "MSET"
+            } else {
+                "MSETNX"
+            };
+            Err(format!(
+                "{name} requires an even number of arguments, as key-value pairs"
+            ))
+        }
+        RequestType::HSet | RequestType::HMSet if args.len() < 3 || args.len() % 2 == 0 => {
+            let name = if matches!(request_type, RequestType::HSet) {
+                "HSET"
+            } else {
+                "HMSET"
+            };
+            Err(format!(
+                "{name} requires a key followed by field-value pairs"
+            ))
+        }
+        RequestType::GeoAdd => {
+            // GEOADD key [NX | XX] [CH] longitude latitude member [longitude latitude member ...]
+            let options = args
+                .iter()
+                .skip(1)
+                .take(2)
+                .take_while(|arg| {
+                    [b"NX".as_slice(), b"XX", b"CH"]
+                        .iter()
+                        .any(|option| arg.eq_ignore_ascii_case(option))
+                })
+                .count();
+            let members = args.len().saturating_sub(1 + options);
+            if args.is_empty() || members == 0 || members % 3 != 0 {
+                Err("GEOADD requires a key followed by longitude-latitude-member triples".into())
+            } else {
+                Ok(())
+            }
+        }
+        RequestType::ZAdd => {
+            // ZADD key [NX | XX] [GT | LT] [CH] [INCR] score member [score member ...]
+            let options = args
+                .iter()
+                .skip(1)
+                .take_while(|arg| {
+                    [b"NX".as_slice(), b"XX", b"GT", b"LT", b"CH", b"INCR"]
+                        .iter()
+                        .any(|option| arg.eq_ignore_ascii_case(option))
+                })
+                .count();
+            let members = args.len().saturating_sub(1 + options);
+            if args.is_empty() || members == 0 || members % 2 != 0 {
+                Err("ZADD requires a key followed by score-member pairs".into())
+            } else {
+                Ok(())
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Resolves the actual RequestType for a CustomCommand by parsing the command name from the first argument.
 fn resolve_custom_command_type(args: &[Vec<u8>]) -> Result<RequestType, String> {
     if args.is_empty() {
@@ -911,14 +1563,22 @@ fn resolve_custom_command_type(args: &[Vec<u8>]) -> Result<RequestType, String>
 pub(crate) unsafe fn create_pipeline(
     ptr: *const BatchInfo,
     compression_manager: Option<&std::sync::Arc<glide_core::compression::CompressionManager>>,
+    key_prefix: Option<&[u8]>,
+    validate_arity: bool,
 ) -> Result<Pipeline, String> {
     let info = unsafe { *ptr };
     let cmd_pointers = unsafe { from_raw_parts(info.cmds, info.cmd_count) };
     let mut pipeline = Pipeline::with_capacity(info.cmd_count);
     for (i, cmd_ptr) in cmd_pointers.iter().enumerate() {
-        match unsafe { create_cmd(*cmd_ptr, compression_manager) } {
+        match unsafe { create_cmd(*cmd_ptr, compression_manager, key_prefix, validate_arity) } {
             Ok(cmd) => pipeline.add_command(cmd),
-            Err(err) => return Err(format!("Coudln't create {i:?}'th command: {err:?}")),
+            Err(err) => {
+                let info = unsafe { **cmd_ptr };
+                return Err(format!(
+                    "Couldn't create the batch command at index {i} ({:?} with {} arguments): {err}",
+                    info.request_type, info.arg_count
+                ));
+            }
         };
     }
     if info.is_atomic {
@@ -928,42 +1588,945 @@ pub(crate) unsafe fn create_pipeline(
     Ok(pipeline)
 }
 
-/// Convert [`BatchOptionsInfo`] to a tuple of corresponding values.
+/// Return an error naming the first command of `pipeline` which is not a read command.
+pub(crate) fn check_read_only(pipeline: &Pipeline) -> Result<(), String> {
+    for (i, cmd) in pipeline.cmd_iter().enumerate() {
+        let name = cmd.command().unwrap_or_default();
+        if !redis::cluster_routing::is_readonly_cmd(&name) {
+            return Err(format!(
+                "Read-only batch contains the write command {} at index {i}",
+                String::from_utf8_lossy(&name)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The route of a read-only transaction: the slot of its first keyed command, served by a replica if the client's
+/// `ReadFrom` strategy allows it. Returns `None` if no command has a key, in which case the default routing is kept.
+pub(crate) fn read_only_route(pipeline: &Pipeline) -> Option<RoutingInfo> {
+    first_key_route(pipeline, SlotAddr::ReplicaOptional)
+}
+
+/// Route a transaction to the node of `slot_addr` serving the slot of its first keyed command, regardless of the keys of
+/// the following commands, e.g. when they share a hash tag the client can't see. Returns `None` if no command has a key
+/// which maps to a single slot.
+pub(crate) fn first_key_route(pipeline: &Pipeline, slot_addr: SlotAddr) -> Option<RoutingInfo> {
+    pipeline
+        .cmd_iter()
+        .find_map(|cmd| match RoutingInfo::for_routable(cmd) {
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) => {
+                Some(route)
+            }
+            _ => None,
+        })
+        .map(|route| {
+            RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                route.slot(),
+                slot_addr,
+            )))
+        })
+}
+
+/// Request timeout used by glide-core when none is configured, in milliseconds.
+const DEFAULT_REQUEST_TIMEOUT_MS: u32 = 250;
+
+/// Connection timeout used by glide-core when none is configured, in milliseconds.
+const DEFAULT_CONNECTION_TIMEOUT_MS: u32 = 2000;
+
+/// Limit of in-flight requests used by glide-core when none is configured.
+const DEFAULT_INFLIGHT_REQUESTS_LIMIT: u32 = 1000;
+
+/// Timeout of the commands tagged with [`OperationClass::Unbounded`], in milliseconds: about 49 days, longer than any
+/// command a server would run, while keeping glide-core's timer arithmetic in range.
+const UNBOUNDED_TIMEOUT_MS: u32 = u32::MAX;
+
+/// Request timeouts per [`OperationClass`], in milliseconds.
 ///
-/// # Safety
-/// * `ptr` could be `null`, but if it is not `null`, it must be a valid pointer to a [`BatchOptionsInfo`] struct.
-/// * `route_info` in dereferenced [`BatchOptionsInfo`] struct must contain a [`RouteInfo`] pointer.
-///   See description of [`RouteInfo`] and the safety documentation of [`create_route`].
-pub(crate) unsafe fn get_pipeline_options(
-    ptr: *const BatchOptionsInfo,
-) -> Result<(Option<RoutingInfo>, Option<u32>, PipelineRetryStrategy), String> {
-    if ptr.is_null() {
-        return Ok((None, None, PipelineRetryStrategy::new(false, false)));
+/// glide-core's own request timeout is the longest of them, see [`longest`](Self::longest), so commands with a shorter
+/// timeout can be sent with `send_command` and cut off earlier, see
+/// [`send_command_with_timeout`](crate::send_command_with_timeout). Batches use the default timeout.
+#[derive(Clone, Copy)]
+pub(crate) struct RequestTimeouts {
+    pub default: u32,
+    pub read: u32,
+    pub write: u32,
+}
+
+impl RequestTimeouts {
+    /// The timeout of `cmd`, tagged with `class`.
+    ///
+    /// A command tagged with [`OperationClass::Unspecified`] is classified by its name: read-only commands, as listed by
+    /// [`redis::cluster_routing::is_readonly_cmd`], use the read timeout and the other commands the write timeout. Both
+    /// default to the global request timeout, so the classification has no effect unless they are configured.
+    ///
+    /// [`OperationClass::Unbounded`] disables the safety net of a timeout for the command, which may then wait for a reply
+    /// as long as the server takes, e.g. for long-running admin commands. It replaces glide-core's request timeout as well,
+    /// whether or not read and write timeouts are configured.
+    pub(crate) fn for_command(&self, class: OperationClass, cmd: &Cmd) -> u32 {
+        match class {
+            OperationClass::Unspecified => {
+                let name = cmd.command().unwrap_or_default();
+                if redis::cluster_routing::is_readonly_cmd(&name) {
+                    self.read
+                } else {
+                    self.write
+                }
+            }
+            OperationClass::Read => self.read,
+            OperationClass::Write => self.write,
+            OperationClass::Unbounded => UNBOUNDED_TIMEOUT_MS,
+        }
     }
-    let info = unsafe { *ptr };
-    let timeout = if info.has_timeout {
-        Some(info.timeout)
-    } else {
-        None
+
+    /// The longest of the timeouts, used as glide-core's request timeout.
+    pub(crate) fn longest(&self) -> u32 {
+        self.default.max(self.read).max(self.write)
+    }
+}
+
+/// Whether `cmd` blocks on the server until data is available or its own timeout expires.
+///
+/// glide-core derives the request timeout of these commands from their arguments rather than applying its request timeout,
+/// so they are always sent as they are, see [`send_command_with_timeout`](crate::send_command_with_timeout).
+pub(crate) fn is_blocking_cmd(cmd: &Cmd) -> bool {
+    let Some(name) = cmd.command() else {
+        return false;
     };
-    let route = unsafe { create_route(info.route_info, None) }?;
+    match name.to_ascii_uppercase().as_slice() {
+        b"BLPOP" | b"BRPOP" | b"BLMOVE" | b"BLMPOP" | b"BRPOPLPUSH" | b"BZPOPMIN" | b"BZPOPMAX"
+        | b"BZMPOP" | b"WAIT" | b"WAITAOF" => true,
+        b"XREAD" | b"XREADGROUP" => cmd.args_iter().any(
+            |arg| matches!(arg, redis::Arg::Simple(arg) if arg.eq_ignore_ascii_case(b"BLOCK")),
+        ),
+        _ => false,
+    }
+}
 
-    Ok((
-        route,
-        timeout,
-        PipelineRetryStrategy::new(info.retry_server_error, info.retry_connection_error),
-    ))
+/// Read the retries of commands rejected with a `READONLY` error from a [`ConnectionConfig`], `None` if they are disabled.
+///
+/// A node returns `READONLY` for a write while it is a replica, e.g. after a failover demoted the primary the client still
+/// routes the write to, until the client learned about the promoted primary.
+pub(crate) fn read_only_retry_config(config: &ConnectionConfig) -> Option<ReadOnlyRetryConfig> {
+    (config.has_read_only_retry_config && config.read_only_retry_config.max_retries > 0)
+        .then_some(config.read_only_retry_config)
 }
 
-/// FFI-safe version of [`redis::PushKind`] for C# interop.
-/// This enum maps to the `PushKind` enum in `sources/Valkey.Glide/Internals/FFI.structs.cs`.
+/// Read the maximal reply size from a [`ConnectionConfig`], `None` if replies are not limited.
 ///
-/// The `#[repr(u32)]` attribute ensures a stable memory layout compatible with C# marshaling.
-/// Each variant corresponds to a specific Redis/Valkey PubSub notification type.
-#[repr(u32)]
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum PushKind {
-    /// Disconnection notification sent from the library when connection is closed.
+/// glide-core reads a reply entirely before handing it over, so the limit can't prevent it from allocating a reply; it
+/// keeps an oversized reply, e.g. sent by a misbehaving server, from being copied and passed on to the application.
+pub(crate) fn max_response_bytes(config: &ConnectionConfig) -> Option<u64> {
+    config
+        .has_max_response_bytes
+        .then_some(config.max_response_bytes)
+}
+
+/// Check that a reply is at most `limit` bytes, counting the bytes of all its strings.
+pub(crate) fn check_response_size(value: &Value, limit: u64) -> Result<(), String> {
+    let size = response_size(value);
+    if size > limit {
+        return Err(format!(
+            "Response of {size} bytes exceeds the maximal response size of {limit} bytes"
+        ));
+    }
+    Ok(())
+}
+
+fn response_size(value: &Value) -> u64 {
+    let sum = |values: &[Value]| {
+        values.iter().fold(0u64, |size, value| {
+            size.saturating_add(response_size(value))
+        })
+    };
+    let sum_pairs = |pairs: &[(Value, Value)]| {
+        pairs.iter().fold(0u64, |size, (key, value)| {
+            size.saturating_add(response_size(key))
+                .saturating_add(response_size(value))
+        })
+    };
+    match value {
+        Value::BulkString(bytes) => bytes.len() as u64,
+        Value::SimpleString(text) | Value::VerbatimString { text, .. } => text.len() as u64,
+        Value::Array(values) | Value::Set(values) | Value::Push { data: values, .. } => sum(values),
+        Value::Map(pairs) => sum_pairs(pairs),
+        Value::Attribute { data, attributes } => {
+            response_size(data).saturating_add(sum_pairs(attributes))
+        }
+        _ => 0,
+    }
+}
+
+/// Read the per-[`OperationClass`] request timeouts from a [`ConnectionConfig`].
+///
+/// A class without its own timeout uses the global one.
+pub(crate) fn request_timeouts(config: &ConnectionConfig) -> RequestTimeouts {
+    let default = if config.has_request_timeout {
+        config.request_timeout
+    } else {
+        DEFAULT_REQUEST_TIMEOUT_MS
+    };
+    RequestTimeouts {
+        default,
+        read: if config.has_read_request_timeout {
+            config.read_request_timeout
+        } else {
+            default
+        },
+        write: if config.has_write_request_timeout {
+            config.write_request_timeout
+        } else {
+            default
+        },
+    }
+}
+
+/// Get how long [`create_client`](crate::create_client) waits for the server to confirm the configured subscriptions,
+/// `None` to not wait.
+pub(crate) fn subscribe_confirmation_timeout(
+    config: &ConnectionConfig,
+) -> Option<std::time::Duration> {
+    config
+        .has_subscribe_confirmation_timeout
+        .then(|| std::time::Duration::from_millis(config.subscribe_confirmation_timeout.into()))
+}
+
+/// Read the auto-pipelining settings from a [`ConnectionConfig`].
+///
+/// Returns `None` if auto-pipelining is disabled, or an error if the window size or interval is zero.
+///
+/// # Safety
+/// * `config_ptr` must not be `null` and must be a valid pointer to a [`ConnectionConfig`] struct.
+pub(crate) unsafe fn get_auto_pipeline_config(
+    config_ptr: *const ConnectionConfig,
+) -> Result<Option<AutoPipelineConfig>, String> {
+    let config = unsafe { *config_ptr };
+    if !config.has_auto_pipeline_config {
+        return Ok(None);
+    }
+    let auto_pipeline = config.auto_pipeline_config;
+    if auto_pipeline.max_batch_size == 0 {
+        return Err("Auto-pipeline max batch size must be positive".into());
+    }
+    if auto_pipeline.flush_interval_ms == 0 {
+        return Err("Auto-pipeline flush interval must be positive".into());
+    }
+    Ok(Some(auto_pipeline))
+}
+
+/// Read the key prefix from a [`ConnectionConfig`], `None` if it is not set or empty.
+///
+/// # Safety
+/// * `config_ptr` must be a valid pointer to a [`ConnectionConfig`] struct.
+/// * `key_prefix` in the dereferenced struct must be `null` or a valid C string.
+pub(crate) unsafe fn get_key_prefix(
+    config_ptr: *const ConnectionConfig,
+) -> Result<Option<Vec<u8>>, String> {
+    let config = unsafe { *config_ptr };
+    let key_prefix = unsafe { ptr_to_opt_str_bounded(config.key_prefix, MAX_CONFIG_STR_LEN) }?;
+    Ok(key_prefix
+        .filter(|prefix| !prefix.is_empty())
+        .map(String::into_bytes))
+}
+
+/// Where the keys of a command are among its arguments, counting the command name as argument 0.
+enum KeyPositions {
+    NoKeys,
+    /// A single key at this index, e.g. 1 for `GET key`, or 2 for `OBJECT ENCODING key`.
+    At(usize),
+    /// Keys at indices 1 and 2, e.g. `RENAME key newkey`.
+    FirstTwo,
+    /// Every argument, e.g. `DEL key [key ...]`.
+    All,
+    /// Every argument but the last one, e.g. the timeout of `BLPOP key [key ...] timeout`.
+    AllButLast,
+    /// Every other argument, e.g. `MSET key value [key value ...]`.
+    Interleaved,
+    /// `numkeys` at this index, followed by that many keys, e.g. 2 for `EVAL script numkeys key [key ...]`.
+    NumKeys(usize),
+    /// A destination key, then `numkeys` and that many keys, e.g. `ZUNIONSTORE destination numkeys key [key ...]`.
+    DestinationAndNumKeys,
+    /// A key at index 1, and the destination of the `STORE` option, e.g. `SORT key [LIMIT offset count] STORE destination`.
+    Sort,
+}
+
+/// Get the key positions of a command, `None` if they are unknown.
+fn key_positions(command: &[u8]) -> Option<KeyPositions> {
+    let command = command.to_ascii_uppercase();
+    Some(match command.as_slice() {
+        b"PING" | b"ECHO" | b"INFO" | b"TIME" | b"DBSIZE" | b"FLUSHALL" | b"FLUSHDB"
+        | b"SELECT" | b"AUTH" | b"HELLO" | b"CLIENT" | b"CONFIG" | b"COMMAND" | b"LASTSAVE"
+        | b"ROLE" | b"PUBLISH" | b"SPUBLISH" | b"SCRIPT" | b"FUNCTION" | b"LOLWUT" | b"MULTI"
+        | b"EXEC" | b"DISCARD" | b"UNWATCH" | b"RANDOMKEY" => KeyPositions::NoKeys,
+        b"GET" | b"SET" | b"SETEX" | b"PSETEX" | b"SETNX" | b"GETSET" | b"GETDEL" | b"GETEX"
+        | b"APPEND" | b"STRLEN" | b"INCR" | b"INCRBY" | b"INCRBYFLOAT" | b"DECR" | b"DECRBY"
+        | b"SETRANGE" | b"GETRANGE" | b"EXPIRE" | b"PEXPIRE" | b"EXPIREAT" | b"PEXPIREAT"
+        | b"EXPIRETIME" | b"PEXPIRETIME" | b"TTL" | b"PTTL" | b"PERSIST" | b"TYPE" | b"DUMP"
+        | b"RESTORE" | b"HSET" | b"HGET" | b"HMSET" | b"HMGET" | b"HDEL" | b"HEXISTS"
+        | b"HGETALL" | b"HKEYS" | b"HVALS" | b"HLEN" | b"HINCRBY" | b"HINCRBYFLOAT" | b"HSETNX"
+        | b"HSTRLEN" | b"HRANDFIELD" | b"HSCAN" | b"LPUSH" | b"RPUSH" | b"LPUSHX" | b"RPUSHX"
+        | b"LPOP" | b"RPOP" | b"LLEN" | b"LRANGE" | b"LINDEX" | b"LSET" | b"LREM" | b"LTRIM"
+        | b"LINSERT" | b"LPOS" | b"SADD" | b"SREM" | b"SMEMBERS" | b"SISMEMBER" | b"SMISMEMBER"
+        | b"SCARD" | b"SPOP" | b"SRANDMEMBER" | b"SSCAN" | b"ZADD" | b"ZREM" | b"ZRANGE"
+        | b"ZSCORE" | b"ZMSCORE" | b"ZCARD" | b"ZCOUNT" | b"ZINCRBY" | b"ZRANK" | b"ZREVRANK"
+        | b"ZRANGEBYSCORE" | b"ZREVRANGE" | b"ZREVRANGEBYSCORE" | b"ZRANGEBYLEX"
+        | b"ZREVRANGEBYLEX" | b"ZLEXCOUNT" | b"ZREMRANGEBYSCORE" | b"ZREMRANGEBYRANK"
+        | b"ZREMRANGEBYLEX" | b"ZPOPMIN" | b"ZPOPMAX" | b"ZRANDMEMBER" | b"ZSCAN" | b"XADD"
+        | b"XLEN" | b"XRANGE" | b"XREVRANGE" | b"XDEL" | b"XTRIM" | b"XACK" | b"XCLAIM"
+        | b"XAUTOCLAIM" | b"XPENDING" | b"XSETID" | b"GEOADD" | b"GEODIST" | b"GEOHASH"
+        | b"GEOPOS" | b"GEOSEARCH" | b"PFADD" | b"SETBIT" | b"GETBIT" | b"BITCOUNT" | b"BITPOS"
+        | b"BITFIELD" | b"BITFIELD_RO" => KeyPositions::At(1),
+        b"OBJECT" | b"XGROUP" | b"XINFO" | b"MEMORY" => KeyPositions::At(2),
+        b"RENAME" | b"RENAMENX" | b"COPY" | b"LMOVE" | b"BLMOVE" | b"SMOVE" | b"RPOPLPUSH"
+        | b"BRPOPLPUSH" | b"GEOSEARCHSTORE" | b"ZRANGESTORE" => KeyPositions::FirstTwo,
+        b"DEL" | b"UNLINK" | b"EXISTS" | b"TOUCH" | b"MGET" | b"WATCH" | b"SDIFF" | b"SINTER"
+        | b"SUNION" | b"SDIFFSTORE" | b"SINTERSTORE" | b"SUNIONSTORE" | b"PFCOUNT" | b"PFMERGE" => {
+            KeyPositions::All
+        }
+        b"BLPOP" | b"BRPOP" | b"BZPOPMIN" | b"BZPOPMAX" => KeyPositions::AllButLast,
+        b"MSET" | b"MSETNX" => KeyPositions::Interleaved,
+        b"ZUNION" | b"ZINTER" | b"ZDIFF" | b"ZINTERCARD" | b"SINTERCARD" | b"LMPOP" | b"ZMPOP" => {
+            KeyPositions::NumKeys(1)
+        }
+        b"EVAL" | b"EVALSHA" | b"EVAL_RO" | b"EVALSHA_RO" | b"FCALL" | b"FCALL_RO" | b"BLMPOP"
+        | b"BZMPOP" => KeyPositions::NumKeys(2),
+        b"ZUNIONSTORE" | b"ZINTERSTORE" | b"ZDIFFSTORE" => KeyPositions::DestinationAndNumKeys,
+        b"SORT" | b"SORT_RO" => KeyPositions::Sort,
+        _ => return None,
+    })
+}
+
+/// Get the indices of the key arguments of a command, `None` if they can't be determined.
+fn key_indices(args: &[&[u8]]) -> Option<Vec<usize>> {
+    let arg_count = args.len();
+    let num_keys_at = |index: usize| -> Option<std::ops::Range<usize>> {
+        let num_keys: usize = std::str::from_utf8(args.get(index)?).ok()?.parse().ok()?;
+        Some(index + 1..(index + 1 + num_keys).min(arg_count))
+    };
+    Some(match key_positions(args.first()?)? {
+        KeyPositions::NoKeys => Vec::new(),
+        KeyPositions::At(index) => (index < arg_count).then_some(index).into_iter().collect(),
+        KeyPositions::FirstTwo => (1..arg_count.min(3)).collect(),
+        KeyPositions::All => (1..arg_count).collect(),
+        KeyPositions::AllButLast => (1..arg_count.saturating_sub(1)).collect(),
+        KeyPositions::Interleaved => (1..arg_count).step_by(2).collect(),
+        KeyPositions::NumKeys(index) => num_keys_at(index)?.collect(),
+        KeyPositions::DestinationAndNumKeys => std::iter::once(1).chain(num_keys_at(2)?).collect(),
+        KeyPositions::Sort => (1 < arg_count)
+            .then_some(1)
+            .into_iter()
+            .chain(sort_option_values(args, b"STORE"))
+            .collect(),
+    })
+}
+
+/// Get the indices of the values of the `option` options of a `SORT key [options ...]` command, e.g. of the destination
+/// of `STORE`, or of the patterns of `GET`, which may be repeated.
+fn sort_option_values(args: &[&[u8]], option: &[u8]) -> Vec<usize> {
+    let mut values = Vec::new();
+    let mut index = 2;
+    while index < args.len() {
+        let keyword = args[index];
+        let value_count = if keyword.eq_ignore_ascii_case(b"LIMIT") {
+            2
+        } else if [&b"BY"[..], b"GET", b"STORE"]
+            .iter()
+            .any(|with_value| keyword.eq_ignore_ascii_case(with_value))
+        {
+            1
+        } else {
+            0
+        };
+        if keyword.eq_ignore_ascii_case(option) && index + 1 < args.len() {
+            values.push(index + 1);
+        }
+        index += 1 + value_count;
+    }
+    values
+}
+
+/// Check that the keys of a multi-key command such as `RENAME key newkey` map to the same slot, as required in cluster mode.
+///
+/// The server rejects such a command with a `CROSSSLOT` error otherwise, after a round trip. The keys are found like in
+/// [`apply_key_prefix`], so the check must run on the prefixed command. Commands with unknown keys are accepted.
+pub(crate) fn check_same_slot(cmd: &Cmd) -> Result<(), String> {
+    let args: Vec<&[u8]> = cmd
+        .args_iter()
+        .filter_map(|arg| match arg {
+            redis::Arg::Simple(bytes) => Some(bytes),
+            redis::Arg::Cursor => None,
+        })
+        .collect();
+    let Some(key_indices) = key_indices(&args) else {
+        return Ok(());
+    };
+    let mut keys = key_indices.into_iter().map(|index| args[index]);
+    let Some(first) = keys.next() else {
+        return Ok(());
+    };
+    let slot = redis::cluster_topology::get_slot(first);
+    match keys.find(|key| redis::cluster_topology::get_slot(key) != slot) {
+        None => Ok(()),
+        Some(key) => Err(format!(
+            "The keys of {} must map to the same slot in cluster mode, but `{}` maps to slot {slot} and `{}` to slot {}. \
+             Use a hash tag, e.g. `{{tag}}:a` and `{{tag}}:b`, to keep keys in the same slot",
+            String::from_utf8_lossy(args[0]).to_ascii_uppercase(),
+            String::from_utf8_lossy(first),
+            String::from_utf8_lossy(key),
+            redis::cluster_topology::get_slot(key),
+        )),
+    }
+}
+
+/// Names of the commands [`apply_key_prefix`] already warned about, so that a command sent in a loop logs one warning.
+static UNPREFIXED_COMMANDS: std::sync::Mutex<std::collections::BTreeSet<Vec<u8>>> =
+    std::sync::Mutex::new(std::collections::BTreeSet::new());
+
+/// Append `args` to `cmd`, which holds the command name, prepending `key_prefix`, the `key_prefix` of
+/// [`ConnectionConfig`], to the keys among them, see [`key_prefix_indices`].
+///
+/// The arguments are appended to the command as it is, so that any state it already carries is kept.
+pub(crate) fn apply_key_prefix(
+    cmd: &mut Cmd,
+    args: &[&[u8]],
+    key_prefix: Option<&[u8]>,
+) -> Result<(), String> {
+    let Some(prefix) = key_prefix else {
+        for arg in args {
+            cmd.arg(*arg);
+        }
+        return Ok(());
+    };
+
+    // The key indices are in increasing order, so they are walked along with the arguments.
+    let mut key_indices = key_prefix_indices(cmd, args)?.into_iter().peekable();
+    for (index, arg) in args.iter().enumerate() {
+        if key_indices.next_if_eq(&index).is_some() {
+            cmd.arg([prefix, *arg].concat());
+        } else {
+            cmd.arg(*arg);
+        }
+    }
+    Ok(())
+}
+
+/// Get the indices, in increasing order, of the arguments among `args` which [`apply_key_prefix`] prefixes when they are
+/// appended to `cmd`, which holds the command name.
+///
+/// The keys are found with a table of the key positions of common commands, since glide-core does not expose the
+/// key specifications of the server. Commands whose keys can't be determined statically, e.g. `XREAD`, or which are
+/// missing from the table, are sent without prefixing, and a warning is logged once per command name. The `BY` and
+/// `GET` patterns of `SORT` name keys as well, and are prefixed, except for `GET #` which stands for the element itself.
+/// `KEYS` is rejected, since its reply can't be restricted to the prefixed keys the way the scans are, see
+/// [`strip_key_prefix`].
+pub(crate) fn key_prefix_indices(cmd: &Cmd, args: &[&[u8]]) -> Result<Vec<usize>, String> {
+    let all_args: Vec<&[u8]> = cmd
+        .args_iter()
+        .filter_map(|arg| match arg {
+            redis::Arg::Simple(bytes) => Some(bytes),
+            redis::Arg::Cursor => None,
+        })
+        .chain(args.iter().copied())
+        .collect();
+    let offset = all_args.len() - args.len();
+    let name = all_args
+        .first()
+        .copied()
+        .unwrap_or_default()
+        .to_ascii_uppercase();
+    if name == b"KEYS" {
+        return Err(
+            "KEYS can't be used with a key prefix, use SCAN, which only returns the prefixed keys"
+                .into(),
+        );
+    }
+    let Some(mut key_indices) = key_indices(&all_args) else {
+        let first_warning = UNPREFIXED_COMMANDS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(name.clone());
+        if first_warning {
+            logger_core::log(
+                logger_core::Level::Warn,
+                "key_prefix",
+                format!(
+                    "Keys of {} can't be determined, sending it without the key prefix",
+                    String::from_utf8_lossy(&name)
+                ),
+            );
+        }
+        return Ok(Vec::new());
+    };
+    if matches!(name.as_slice(), b"SORT" | b"SORT_RO") {
+        key_indices.extend(sort_option_values(&all_args, b"BY"));
+        key_indices.extend(
+            sort_option_values(&all_args, b"GET")
+                .into_iter()
+                .filter(|&index| all_args[index] != b"#"),
+        );
+        key_indices.sort_unstable();
+    }
+    Ok(key_indices
+        .into_iter()
+        .filter_map(|index| index.checked_sub(offset))
+        .collect())
+}
+
+/// Remove `prefix`, the `key_prefix` of [`ConnectionConfig`], from the keys of a scan, so that they are returned as the
+/// application named them. The scans only match prefixed keys, see [`prefixed_scan_pattern`].
+pub(crate) fn strip_key_prefix(keys: &mut [Value], prefix: &[u8]) {
+    for key in keys {
+        if let Value::BulkString(bytes) = key
+            && bytes.starts_with(prefix)
+        {
+            bytes.drain(..prefix.len());
+        }
+    }
+}
+
+/// The `MATCH` pattern of a scan restricted to the keys starting with `prefix`, the `key_prefix` of [`ConnectionConfig`]:
+/// `prefix`, with its glob-style special characters escaped, followed by `pattern`, or by `*` to match every key.
+pub(crate) fn prefixed_scan_pattern(prefix: &[u8], pattern: Option<&[u8]>) -> Vec<u8> {
+    let mut prefixed = Vec::with_capacity(prefix.len() + pattern.map_or(1, <[u8]>::len));
+    for &byte in prefix {
+        if matches!(byte, b'*' | b'?' | b'[' | b']' | b'\\') {
+            prefixed.push(b'\\');
+        }
+        prefixed.push(byte);
+    }
+    prefixed.extend_from_slice(pattern.unwrap_or(b"*"));
+    prefixed
+}
+
+/// Convert [`BatchOptionsInfo`] to a tuple of corresponding values.
+///
+/// # Safety
+/// * `ptr` could be `null`, but if it is not `null`, it must be a valid pointer to a [`BatchOptionsInfo`] struct.
+/// * `route_info` in dereferenced [`BatchOptionsInfo`] struct must contain a [`RouteInfo`] pointer.
+///   See description of [`RouteInfo`] and the safety documentation of [`create_route`].
+pub(crate) unsafe fn get_pipeline_options(
+    ptr: *const BatchOptionsInfo,
+) -> Result<(Option<RoutingInfo>, Option<u32>, PipelineRetryStrategy), String> {
+    if ptr.is_null() {
+        return Ok((None, None, PipelineRetryStrategy::new(false, false)));
+    }
+    let info = unsafe { *ptr };
+    let timeout = if info.has_timeout {
+        Some(info.timeout)
+    } else {
+        None
+    };
+    let route = unsafe { create_route(info.route_info, None) }?;
+
+    Ok((
+        route,
+        timeout,
+        PipelineRetryStrategy::new(info.retry_server_error, info.retry_connection_error),
+    ))
+}
+
+/// Build a `FAILOVER [TO host port [FORCE]] [ABORT] [TIMEOUT milliseconds]` command.
+///
+/// Returns an error if:
+/// * only one of `host` and `port` is given, since `TO` requires both;
+/// * `abort` is combined with a target, `force` or a timeout;
+/// * `force` is given without both a target and a timeout, as the server requires.
+pub(crate) fn create_failover_cmd(
+    host: Option<String>,
+    port: Option<u16>,
+    force: bool,
+    abort: bool,
+    timeout_ms: Option<u32>,
+) -> Result<Cmd, String> {
+    let target = match (host, port) {
+        (Some(host), Some(port)) => Some((host, port)),
+        (None, None) => None,
+        _ => return Err("FAILOVER TO requires both a host and a port".into()),
+    };
+    if abort && (target.is_some() || force || timeout_ms.is_some()) {
+        return Err("FAILOVER ABORT cannot be combined with TO, FORCE or TIMEOUT".into());
+    }
+    if force && (target.is_none() || timeout_ms.is_none()) {
+        return Err("FAILOVER FORCE requires both TO and TIMEOUT".into());
+    }
+
+    let mut cmd = redis::cmd("FAILOVER");
+    if let Some((host, port)) = target {
+        cmd.arg("TO").arg(host).arg(port);
+        if force {
+            cmd.arg("FORCE");
+        }
+    }
+    if abort {
+        cmd.arg("ABORT");
+    }
+    if let Some(timeout) = timeout_ms {
+        cmd.arg("TIMEOUT").arg(timeout);
+    }
+    Ok(cmd)
+}
+
+/// Build a `WAITAOF numlocal numreplicas timeout` command.
+///
+/// Returns an error if `num_local` is not `0` or `1`, since a node has a single local AOF.
+pub(crate) fn create_wait_aof_cmd(
+    num_local: u32,
+    num_replicas: u32,
+    timeout_ms: u64,
+) -> Result<Cmd, String> {
+    if num_local > 1 {
+        return Err(format!("WAITAOF numlocal must be 0 or 1, got {num_local}"));
+    }
+    let mut cmd = redis::cmd("WAITAOF");
+    cmd.arg(num_local).arg(num_replicas).arg(timeout_ms);
+    Ok(cmd)
+}
+
+/// Convert a `WAITAOF` reply into a map of `local` and `replicas` acknowledgement counts.
+///
+/// An unknown command error is replaced with one stating that the server is too old to support `WAITAOF`.
+pub(crate) fn parse_wait_aof_response(
+    result: redis::RedisResult<Value>,
+) -> redis::RedisResult<Value> {
+    let value = result.map_err(|err| {
+        if err.kind() == redis::ErrorKind::ResponseError
+            && err.to_string().to_lowercase().contains("unknown command")
+        {
+            redis::RedisError::from((
+                redis::ErrorKind::ResponseError,
+                "WAITAOF is not supported by the server",
+                "WAITAOF requires Valkey or Redis 7.2 or later".to_string(),
+            ))
+        } else {
+            err
+        }
+    })?;
+
+    match value {
+        Value::Array(values) if values.len() == 2 => {
+            let mut values = values.into_iter();
+            let local = values.next().unwrap_or(Value::Nil);
+            let replicas = values.next().unwrap_or(Value::Nil);
+            Ok(Value::Map(vec![
+                (Value::SimpleString("local".into()), local),
+                (Value::SimpleString("replicas".into()), replicas),
+            ]))
+        }
+        other => Err(redis::RedisError::from((
+            redis::ErrorKind::TypeError,
+            "Unexpected WAITAOF response",
+            format!("{other:?}"),
+        ))),
+    }
+}
+
+/// Extract `cluster_current_epoch` from a `CLUSTER INFO` reply as a [`Value::Int`].
+pub(crate) fn parse_cluster_epoch(result: redis::RedisResult<Value>) -> redis::RedisResult<Value> {
+    let info = match result? {
+        Value::BulkString(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Value::VerbatimString { format: _, text } | Value::SimpleString(text) => text,
+        other => {
+            return Err(redis::RedisError::from((
+                redis::ErrorKind::TypeError,
+                "Unexpected CLUSTER INFO response",
+                format!("{other:?}"),
+            )));
+        }
+    };
+
+    info.lines()
+        .find_map(|line| line.trim().strip_prefix("cluster_current_epoch:"))
+        .and_then(|epoch| epoch.parse::<i64>().ok())
+        .map(Value::Int)
+        .ok_or_else(|| {
+            redis::RedisError::from((
+                redis::ErrorKind::TypeError,
+                "CLUSTER INFO response has no valid cluster_current_epoch",
+            ))
+        })
+}
+
+/// Normalize a `CLIENT TRACKINGINFO` reply to a [`Value::Map`] of its fields.
+///
+/// RESP3 replies are maps with the flags as a set, RESP2 replies are flat arrays of alternating field names and values
+/// with the flags as an array. Both are reported as a map with the flags as an array.
+pub(crate) fn parse_tracking_info(result: redis::RedisResult<Value>) -> redis::RedisResult<Value> {
+    let pairs = match result? {
+        Value::Map(pairs) => pairs,
+        Value::Array(values) if values.len() % 2 == 0 => {
+            let mut values = values.into_iter();
+            std::iter::from_fn(|| Some((values.next()?, values.next()?))).collect()
+        }
+        other => {
+            return Err(redis::RedisError::from((
+                redis::ErrorKind::TypeError,
+                "Unexpected CLIENT TRACKINGINFO response",
+                format!("{other:?}"),
+            )));
+        }
+    };
+
+    Ok(Value::Map(
+        pairs
+            .into_iter()
+            .map(|(field, value)| match value {
+                Value::Set(flags) => (field, Value::Array(flags)),
+                value => (field, value),
+            })
+            .collect(),
+    ))
+}
+
+/// Fields of `XINFO STREAM` replies missing from older servers, reported as `nil` by [`parse_xinfo_stream`].
+const XINFO_STREAM_FIELDS: &[&str] = &[
+    "max-deleted-entry-id",
+    "entries-added",
+    "recorded-first-entry-id",
+];
+
+/// Fields of consumer group replies missing from older servers, reported as `nil` by [`parse_xinfo_groups`].
+const XINFO_GROUP_FIELDS: &[&str] = &["entries-read", "lag"];
+
+/// Fields of the consumers of `XINFO STREAM FULL` replies missing from older servers, reported as `nil`.
+const XINFO_CONSUMER_FIELDS: &[&str] = &["active-time"];
+
+/// Normalize an `XINFO` map reply to a [`Value::Map`], adding each of `fields` the server didn't report as `nil`.
+///
+/// RESP3 replies are maps, RESP2 replies are flat arrays of alternating field names and values. The value of each field
+/// is passed through `nested` with the field name, to normalize nested replies.
+fn normalize_xinfo_map(
+    value: Value,
+    fields: &[&str],
+    nested: fn(&[u8], Value) -> redis::RedisResult<Value>,
+) -> redis::RedisResult<Value> {
+    let pairs: Vec<(Value, Value)> = match value {
+        Value::Map(pairs) => pairs,
+        Value::Array(values) if values.len() % 2 == 0 => {
+            let mut values = values.into_iter();
+            std::iter::from_fn(|| Some((values.next()?, values.next()?))).collect()
+        }
+        other => {
+            return Err(redis::RedisError::from((
+                redis::ErrorKind::TypeError,
+                "Unexpected XINFO response",
+                format!("{other:?}"),
+            )));
+        }
+    };
+    let field_name = |field: &Value| match field {
+        Value::BulkString(name) => Some(name.clone()),
+        Value::SimpleString(name) => Some(name.as_bytes().to_vec()),
+        _ => None,
+    };
+
+    let mut pairs = pairs
+        .into_iter()
+        .map(|(field, value)| match field_name(&field) {
+            Some(name) => Ok((field, nested(&name, value)?)),
+            None => Ok((field, value)),
+        })
+        .collect::<redis::RedisResult<Vec<_>>>()?;
+    for field in fields {
+        if !pairs
+            .iter()
+            .any(|(name, _)| field_name(name).as_deref() == Some(field.as_bytes()))
+        {
+            pairs.push((Value::BulkString(field.as_bytes().to_vec()), Value::Nil));
+        }
+    }
+    Ok(Value::Map(pairs))
+}
+
+/// Normalize a consumer group of an `XINFO GROUPS` or `XINFO STREAM FULL` reply, see [`normalize_xinfo_map`].
+fn normalize_xinfo_group(value: Value) -> redis::RedisResult<Value> {
+    normalize_xinfo_map(value, XINFO_GROUP_FIELDS, |field, value| {
+        match (field, value) {
+            // The consumers of `XINFO STREAM FULL`, `XINFO GROUPS` only reports their number.
+            (b"consumers", Value::Array(consumers)) => consumers
+                .into_iter()
+                .map(|consumer| {
+                    normalize_xinfo_map(consumer, XINFO_CONSUMER_FIELDS, |_, value| Ok(value))
+                })
+                .collect::<redis::RedisResult<_>>()
+                .map(Value::Array),
+            (_, value) => Ok(value),
+        }
+    })
+}
+
+/// Normalize an `XINFO STREAM` reply, with or without `FULL`, to a [`Value::Map`] of its fields.
+///
+/// Regardless of the protocol and server version, the reply is a map with the fields added in Valkey 7.0 set to `nil` if
+/// missing. With `FULL`, `groups` is an array of maps shaped as in [`parse_xinfo_groups`], with their `consumers` as maps
+/// including `active-time`.
+pub(crate) fn parse_xinfo_stream(result: redis::RedisResult<Value>) -> redis::RedisResult<Value> {
+    normalize_xinfo_map(result?, XINFO_STREAM_FIELDS, |field, value| {
+        match (field, value) {
+            // The groups of `XINFO STREAM FULL`, without `FULL` only their number is reported.
+            (b"groups", Value::Array(groups)) => groups
+                .into_iter()
+                .map(normalize_xinfo_group)
+                .collect::<redis::RedisResult<_>>()
+                .map(Value::Array),
+            (_, value) => Ok(value),
+        }
+    })
+}
+
+/// Normalize an `XINFO GROUPS` reply to an array of [`Value::Map`]s, one per consumer group.
+///
+/// Regardless of the protocol and server version, each group is a map with `entries-read` and `lag` set to `nil` if
+/// missing.
+pub(crate) fn parse_xinfo_groups(result: redis::RedisResult<Value>) -> redis::RedisResult<Value> {
+    match result? {
+        Value::Array(groups) => groups
+            .into_iter()
+            .map(normalize_xinfo_group)
+            .collect::<redis::RedisResult<_>>()
+            .map(Value::Array),
+        other => Err(redis::RedisError::from((
+            redis::ErrorKind::TypeError,
+            "Unexpected XINFO GROUPS response",
+            format!("{other:?}"),
+        ))),
+    }
+}
+
+/// Build one `CLIENT SETINFO <attribute> <value>` command per attribute/value pair.
+///
+/// Returns an error if an attribute name is empty or a string is not valid UTF-8.
+///
+/// # Safety
+/// * `attributes` and `values` must point to `count` consecutive C string pointers.
+/// * Each string pointer must be able to be safely casted to a valid [`CStr`] via [`CStr::from_ptr`].
+pub(crate) unsafe fn create_client_setinfo_cmds(
+    attributes: *const *const c_char,
+    values: *const *const c_char,
+    count: usize,
+) -> Result<Vec<Cmd>, String> {
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    if attributes.is_null() || values.is_null() {
+        return Err("CLIENT SETINFO attributes and values must not be null".into());
+    }
+    let attributes = unsafe { from_raw_parts(attributes, count) };
+    let values = unsafe { from_raw_parts(values, count) };
+
+    attributes
+        .iter()
+        .zip(values)
+        .map(|(&attribute, &value)| {
+            let attribute = unsafe { ptr_to_str(attribute) }?;
+            if attribute.is_empty() {
+                return Err("CLIENT SETINFO attribute name must not be empty".to_string());
+            }
+            let value = unsafe { ptr_to_str(value) }?;
+            let mut cmd = redis::cmd("CLIENT");
+            cmd.arg("SETINFO").arg(attribute).arg(value);
+            Ok(cmd)
+        })
+        .collect()
+}
+
+/// A node as listed by `CLUSTER NODES`.
+pub(crate) struct ClusterNode {
+    pub id: String,
+    /// The announced hostname if any, otherwise the IP, matching the endpoints glide-core connects to.
+    pub host: String,
+    pub port: u16,
+    pub flags: Vec<String>,
+    /// Id of the node's primary, `None` for primaries.
+    pub primary_id: Option<String>,
+    /// Inclusive slot ranges served by the node. Empty for replicas.
+    pub slots: Vec<(u16, u16)>,
+}
+
+impl ClusterNode {
+    pub(crate) fn is_primary(&self) -> bool {
+        self.has_flag("master")
+    }
+
+    /// Whether the cluster flags the node as failed or possibly failed.
+    pub(crate) fn is_failed(&self) -> bool {
+        self.has_flag("fail") || self.has_flag("fail?")
+    }
+
+    /// Whether the node serves `slot`.
+    pub(crate) fn serves_slot(&self, slot: u16) -> bool {
+        self.slots
+            .iter()
+            .any(|&(start, end)| (start..=end).contains(&slot))
+    }
+
+    fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
+}
+
+/// Parse a `CLUSTER NODES` reply.
+///
+/// Nodes without an address or still in handshake are skipped, since commands cannot be routed to them.
+pub(crate) fn parse_cluster_nodes(nodes: &str) -> Vec<ClusterNode> {
+    nodes
+        .lines()
+        .filter_map(|line| {
+            // Format: <id> <ip:port@cport[,hostname]> <flags> <primary> <ping-sent> <pong-recv> <config-epoch> <link-state> <slot>...
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (id, address, flags, primary_id) = (
+                fields.first()?,
+                fields.get(1)?,
+                fields.get(2)?,
+                fields.get(3)?,
+            );
+            // The slots follow the link state, a line without it is truncated.
+            fields.get(7)?;
+            let flags: Vec<String> = flags.split(',').map(str::to_string).collect();
+            if flags
+                .iter()
+                .any(|flag| matches!(flag.as_str(), "noaddr" | "handshake"))
+            {
+                return None;
+            }
+            let (endpoint, hostname) = match address.split_once(',') {
+                Some((endpoint, hostname)) => (endpoint, Some(hostname)),
+                None => (*address, None),
+            };
+            let endpoint = endpoint.split('@').next()?;
+            let (ip, port) = endpoint.rsplit_once(':')?;
+            let port = port.parse().ok()?;
+            let host = hostname
+                .filter(|hostname| !hostname.is_empty())
+                .unwrap_or(ip);
+            // Slots are either a single slot or a `start-end` range. Importing and migrating slots (`[...]`) are skipped.
+            let slots = fields[8..]
+                .iter()
+                .filter_map(|slot| match slot.split_once('-') {
+                    Some((start, end)) => Some((start.parse().ok()?, end.parse().ok()?)),
+                    None => slot.parse().ok().map(|slot| (slot, slot)),
+                })
+                .collect();
+            Some(ClusterNode {
+                id: id.to_string(),
+                host: host.to_string(),
+                port,
+                flags,
+                primary_id: (*primary_id != "-").then(|| primary_id.to_string()),
+                slots,
+            })
+        })
+        .collect()
+}
+
+/// FFI-safe version of [`redis::PushKind`] for C# interop.
+/// This enum maps to the `PushKind` enum in `sources/Valkey.Glide/Internals/FFI.structs.cs`.
+///
+/// The `#[repr(u32)]` attribute ensures a stable memory layout compatible with C# marshaling.
+/// Each variant corresponds to a specific Redis/Valkey PubSub notification type.
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PushKind {
+    /// Disconnection notification sent from the library when connection is closed.
     Disconnection = 0,
     /// Other/unknown push notification type.
     Other = 1,
@@ -1020,6 +2583,9 @@ impl From<&redis::PushKind> for PushKind {
 /// * `channel_len` - Length of the channel name in bytes (unsigned, cannot be negative)
 /// * `pattern_ptr` - Pointer to the raw pattern bytes (null if no pattern)
 /// * `pattern_len` - Length of the pattern in bytes (unsigned, 0 if no pattern)
+/// * `sequence` - Number of the message among those of this callback, starting at 1 and incremented by 1 for every
+///   message delivered to or dropped for it, including across reconnections, so that a gap means a message was lost.
+///   0 for subscription confirmations, which are not numbered
 pub type PubSubCallback = unsafe extern "C" fn(
     push_kind: PushKind,
     message_ptr: *const u8,
@@ -1028,4 +2594,497 @@ pub type PubSubCallback = unsafe extern "C" fn(
     channel_len: u64,
     pattern_ptr: *const u8,
     pattern_len: u64,
+    sequence: u64,
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The arguments of `cmd`, including the command name.
+    fn args(cmd: &Cmd) -> Vec<Vec<u8>> {
+        cmd.args_iter()
+            .filter_map(|arg| match arg {
+                redis::Arg::Simple(bytes) => Some(bytes.to_vec()),
+                redis::Arg::Cursor => None,
+            })
+            .collect()
+    }
+
+    fn prefixed(cmd: &mut Cmd) -> Vec<Vec<u8>> {
+        let values = args(cmd);
+        let mut prefixed = redis::cmd(std::str::from_utf8(&values[0]).unwrap());
+        let rest: Vec<&[u8]> = values[1..].iter().map(Vec::as_slice).collect();
+        apply_key_prefix(&mut prefixed, &rest, Some(&b"app:"[..])).unwrap();
+        args(&prefixed)
+    }
+
+    #[test]
+    fn key_prefix_applies_to_sort_destination_and_patterns() {
+        assert_eq!(
+            prefixed(
+                redis::cmd("SORT")
+                    .arg("list")
+                    .arg("LIMIT")
+                    .arg(0)
+                    .arg(10)
+                    .arg("BY")
+                    .arg("weight_*")
+                    .arg("GET")
+                    .arg("#")
+                    .arg("GET")
+                    .arg("data_*->name")
+                    .arg("STORE")
+                    .arg("sorted")
+            ),
+            [
+                &b"SORT"[..],
+                b"app:list",
+                b"LIMIT",
+                b"0",
+                b"10",
+                b"BY",
+                b"app:weight_*",
+                b"GET",
+                b"#",
+                b"GET",
+                b"app:data_*->name",
+                b"STORE",
+                b"app:sorted"
+            ]
+        );
+    }
+
+    #[test]
+    fn key_prefix_rejects_keys() {
+        let mut cmd = redis::cmd("KEYS");
+        assert!(apply_key_prefix(&mut cmd, &[b"*".as_slice()], Some(&b"app:"[..])).is_err());
+        assert!(apply_key_prefix(&mut redis::cmd("KEYS"), &[b"*".as_slice()], None).is_ok());
+    }
+
+    #[test]
+    fn key_prefix_is_stripped_from_scanned_keys() {
+        let mut keys = vec![
+            Value::BulkString(b"app:a".to_vec()),
+            Value::BulkString(b"other".to_vec()),
+        ];
+        strip_key_prefix(&mut keys, b"app:");
+        assert_eq!(
+            keys,
+            [
+                Value::BulkString(b"a".to_vec()),
+                Value::BulkString(b"other".to_vec())
+            ]
+        );
+    }
+
+    #[test]
+    fn key_prefix_applies_to_xinfo_stream_and_groups() {
+        assert_eq!(
+            prefixed(redis::cmd("XINFO").arg("STREAM").arg("events").arg("FULL")),
+            [&b"XINFO"[..], b"STREAM", b"app:events", b"FULL"]
+        );
+        assert_eq!(
+            prefixed(redis::cmd("XINFO").arg("GROUPS").arg("events")),
+            [&b"XINFO"[..], b"GROUPS", b"app:events"]
+        );
+    }
+
+    #[test]
+    fn key_prefix_applies_to_script_keys_only() {
+        assert_eq!(
+            prefixed(
+                redis::cmd("EVALSHA")
+                    .arg("sha")
+                    .arg(2)
+                    .arg("a")
+                    .arg("b")
+                    .arg("c")
+            ),
+            [&b"EVALSHA"[..], b"sha", b"2", b"app:a", b"app:b", b"c"]
+        );
+        assert_eq!(
+            prefixed(redis::cmd("FCALL").arg("fn").arg(0).arg("a")),
+            [&b"FCALL"[..], b"fn", b"0", b"a"]
+        );
+    }
+
+    #[test]
+    fn key_prefix_applies_to_destination_and_numkeys() {
+        assert_eq!(
+            prefixed(
+                redis::cmd("ZUNIONSTORE")
+                    .arg("dst")
+                    .arg(2)
+                    .arg("a")
+                    .arg("b")
+                    .arg("WEIGHTS")
+                    .arg(1)
+                    .arg(2)
+            ),
+            [
+                &b"ZUNIONSTORE"[..],
+                b"app:dst",
+                b"2",
+                b"app:a",
+                b"app:b",
+                b"WEIGHTS",
+                b"1",
+                b"2"
+            ]
+        );
+    }
+
+    #[test]
+    fn key_prefix_skips_unknown_commands() {
+        assert_eq!(
+            prefixed(redis::cmd("XREAD").arg("STREAMS").arg("events").arg("0")),
+            [&b"XREAD"[..], b"STREAMS", b"events", b"0"]
+        );
+    }
+
+    fn failover(
+        host: Option<&str>,
+        port: Option<u16>,
+        force: bool,
+        abort: bool,
+        timeout_ms: Option<u32>,
+    ) -> Result<Vec<Vec<u8>>, String> {
+        create_failover_cmd(host.map(Into::into), port, force, abort, timeout_ms)
+            .map(|cmd| args(&cmd))
+    }
+
+    #[test]
+    fn untagged_commands_use_the_timeout_of_their_class() {
+        let timeouts = RequestTimeouts {
+            default: 250,
+            read: 100,
+            write: 1000,
+        };
+        let timeout = |class, cmd: &Cmd| timeouts.for_command(class, cmd);
+
+        assert_eq!(
+            timeout(OperationClass::Unspecified, &redis::cmd("GET")),
+            100
+        );
+        assert_eq!(
+            timeout(OperationClass::Unspecified, &redis::cmd("SET")),
+            1000
+        );
+        assert_eq!(timeout(OperationClass::Write, &redis::cmd("GET")), 1000);
+        assert_eq!(timeout(OperationClass::Read, &redis::cmd("SET")), 100);
+        assert_eq!(
+            timeout(OperationClass::Unbounded, &redis::cmd("DEBUG")),
+            UNBOUNDED_TIMEOUT_MS
+        );
+        assert_eq!(timeouts.longest(), 1000);
+    }
+
+    #[test]
+    fn blocking_commands_are_detected() {
+        assert!(is_blocking_cmd(redis::cmd("BLPOP").arg("key").arg(0)));
+        assert!(is_blocking_cmd(redis::cmd("wait").arg(1).arg(100)));
+        assert!(is_blocking_cmd(
+            redis::cmd("XREAD")
+                .arg("block")
+                .arg(100)
+                .arg("STREAMS")
+                .arg("key")
+                .arg("$")
+        ));
+        assert!(!is_blocking_cmd(
+            redis::cmd("XREAD").arg("STREAMS").arg("key").arg("0")
+        ));
+        assert!(!is_blocking_cmd(redis::cmd("LPOP").arg("key")));
+    }
+
+    #[test]
+    fn failover_builds_valid_combinations() {
+        assert_eq!(
+            failover(None, None, false, false, None),
+            Ok(vec![b"FAILOVER".to_vec()])
+        );
+        assert_eq!(
+            failover(Some("replica"), Some(6380), true, false, Some(500)),
+            Ok(vec![
+                b"FAILOVER".to_vec(),
+                b"TO".to_vec(),
+                b"replica".to_vec(),
+                b"6380".to_vec(),
+                b"FORCE".to_vec(),
+                b"TIMEOUT".to_vec(),
+                b"500".to_vec(),
+            ])
+        );
+        assert_eq!(
+            failover(None, None, false, true, None),
+            Ok(vec![b"FAILOVER".to_vec(), b"ABORT".to_vec()])
+        );
+    }
+
+    #[test]
+    fn failover_requires_both_host_and_port() {
+        let err = Err("FAILOVER TO requires both a host and a port".to_string());
+        assert_eq!(failover(Some("replica"), None, false, false, None), err);
+        assert_eq!(failover(None, Some(6380), false, false, None), err);
+    }
+
+    #[test]
+    fn failover_abort_cannot_be_combined() {
+        let err = Err("FAILOVER ABORT cannot be combined with TO, FORCE or TIMEOUT".to_string());
+        assert_eq!(
+            failover(Some("replica"), Some(6380), false, true, None),
+            err
+        );
+        assert_eq!(failover(None, None, true, true, None), err);
+        assert_eq!(failover(None, None, false, true, Some(500)), err);
+    }
+
+    #[test]
+    fn failover_force_requires_target_and_timeout() {
+        let err = Err("FAILOVER FORCE requires both TO and TIMEOUT".to_string());
+        assert_eq!(failover(None, None, true, false, Some(500)), err);
+        assert_eq!(
+            failover(Some("replica"), Some(6380), true, false, None),
+            err
+        );
+    }
+
+    fn slot_key_route(slot_key: &[u8]) -> Option<RoutingInfo> {
+        let route = RouteInfo {
+            route_type: RouteType::SlotKey,
+            slot_id: 0,
+            slot_key: slot_key.as_ptr(),
+            slot_key_len: slot_key.len(),
+            slot_type: SlotType::Primary,
+            hostname: std::ptr::null(),
+            port: 0,
+        };
+        unsafe { create_route(&route, None) }.expect("slot keys are binary")
+    }
+
+    #[test]
+    fn slot_key_route_accepts_invalid_utf8() {
+        let slot = |key: &[u8]| match slot_key_route(key) {
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) => {
+                route.slot()
+            }
+            _ => panic!("a slot key routes to a specific node"),
+        };
+        assert_eq!(slot(b"\xff\xfe{user}"), slot(b"user"));
+        assert_eq!(
+            slot(b"\xc3\x28"),
+            redis::cluster_topology::get_slot(b"\xc3\x28")
+        );
+    }
+
+    #[test]
+    fn by_address_route_rejects_invalid_utf8_hostname() {
+        let hostname = c"\xff\xfehost";
+        let route = RouteInfo {
+            route_type: RouteType::ByAddress,
+            slot_id: 0,
+            slot_key: std::ptr::null(),
+            slot_key_len: 0,
+            slot_type: SlotType::Primary,
+            hostname: hostname.as_ptr(),
+            port: 6379,
+        };
+        let err = unsafe { create_route(&route, None) }.expect_err("hostname is not UTF-8");
+        assert!(err.starts_with("Invalid UTF-8 in C string"), "{err}");
+    }
+
+    #[test]
+    fn client_name_rejects_invalid_utf8() {
+        let name = c"\xffclient";
+        let err = unsafe { ptr_to_opt_str_bounded(name.as_ptr(), MAX_CONFIG_STR_LEN) }
+            .expect_err("client name is not UTF-8");
+        assert!(err.starts_with("Invalid UTF-8 in C string"), "{err}");
+        assert_eq!(
+            unsafe { ptr_to_opt_str_bounded(c"client".as_ptr(), MAX_CONFIG_STR_LEN) },
+            Ok(Some("client".to_string()))
+        );
+    }
+
+    #[test]
+    fn scan_cursor_id_rejects_invalid_utf8() {
+        let err = unsafe { scan_cursor_id(c"\xff".as_ptr()) }.expect_err("cursor is not UTF-8");
+        assert!(err.starts_with("Invalid UTF-8 in cursor ID"), "{err}");
+        assert_eq!(
+            unsafe { scan_cursor_id(c"0".as_ptr()) },
+            Ok("0".to_string())
+        );
+    }
+
+    /// The elements of an array-like [`ResponseValue`].
+    fn elements(value: &ResponseValue) -> &[ResponseValue] {
+        unsafe { from_raw_parts(value.val as *const ResponseValue, value.size as usize) }
+    }
+
+    /// The bytes of a string-like [`ResponseValue`].
+    fn bytes(value: &ResponseValue) -> &[u8] {
+        unsafe { from_raw_parts(value.val as *const u8, value.size as usize) }
+    }
+
+    /// Converts and frees a push, so that `cargo miri test` checks the ownership of its nested allocations.
+    #[test]
+    fn push_round_trips_through_from_value_and_free_memory() {
+        let push = Value::Push {
+            kind: redis::PushKind::Message,
+            data: vec![
+                Value::BulkString(b"channel".to_vec()),
+                Value::Array(vec![Value::Int(1), Value::BulkString(b"payload".to_vec())]),
+            ],
+        };
+
+        let response = ResponseValue::from_value(push).unwrap();
+        assert!(matches!(response.typ, ValueType::Push));
+        let [kind, channel, payload] = elements(&response) else {
+            panic!("a push is stored as its kind followed by its data");
+        };
+        assert!(matches!(kind.typ, ValueType::Int));
+        assert_eq!(kind.val, PushKind::Message as i64);
+        assert_eq!(bytes(channel), b"channel");
+        let [one, text] = elements(payload) else {
+            panic!("nested arrays are stored by reference");
+        };
+        assert_eq!(one.val, 1);
+        assert_eq!(bytes(text), b"payload");
+
+        unsafe { response.free_memory() };
+    }
+
+    /// An empty push still holds its kind, so its array is never empty.
+    #[test]
+    fn empty_push_round_trips_through_from_value_and_free_memory() {
+        let push = Value::Push {
+            kind: redis::PushKind::Invalidate,
+            data: vec![],
+        };
+
+        let response = ResponseValue::from_value(push).unwrap();
+        let [kind] = elements(&response) else {
+            panic!("a push is stored as its kind followed by its data");
+        };
+        assert_eq!(kind.val, PushKind::Invalidate as i64);
+
+        unsafe { response.free_memory() };
+    }
+
+    fn server_error(detail: Option<&str>) -> ResponseValue {
+        ResponseValue::from_value(Value::ServerError(redis::ServerError::ExtensionError {
+            code: "CUSTOMERR".into(),
+            detail: detail.map(Into::into),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn server_error_is_reported_by_its_details() {
+        let response = server_error(Some("something went wrong"));
+        assert!(matches!(response.typ, ValueType::Error));
+        assert_eq!(bytes(&response), b"something went wrong");
+        unsafe { response.free_memory() };
+    }
+
+    #[test]
+    fn server_error_without_details_is_reported_by_its_code() {
+        let response = server_error(None);
+        assert!(matches!(response.typ, ValueType::Error));
+        assert_eq!(bytes(&response), b"CUSTOMERR");
+        unsafe { response.free_memory() };
+    }
+
+    #[test]
+    fn prefixed_scan_pattern_escapes_the_prefix() {
+        assert_eq!(prefixed_scan_pattern(b"app:", None), b"app:*");
+        assert_eq!(
+            prefixed_scan_pattern(b"app:", Some(b"user:*")),
+            b"app:user:*"
+        );
+        assert_eq!(
+            prefixed_scan_pattern(b"a*b?[c]\\:", Some(b"*")),
+            b"a\\*b\\?\\[c\\]\\\\:*"
+        );
+    }
+
+    fn arity(request_type: RequestType, args: &[&str]) -> Result<(), String> {
+        let args: Vec<&[u8]> = args.iter().map(|arg| arg.as_bytes()).collect();
+        validate_command_arity(request_type, &args)
+    }
+
+    #[test]
+    fn arity_accepts_well_formed_commands() {
+        assert!(arity(RequestType::MSet, &["k1", "v1", "k2", "v2"]).is_ok());
+        assert!(arity(RequestType::MSetNX, &["k1", "v1"]).is_ok());
+        assert!(arity(RequestType::HSet, &["hash", "f1", "v1", "f2", "v2"]).is_ok());
+        assert!(arity(RequestType::HMSet, &["hash", "f1", "v1"]).is_ok());
+        assert!(arity(RequestType::GeoAdd, &["geo", "13.4", "52.5", "berlin"]).is_ok());
+        assert!(
+            arity(
+                RequestType::GeoAdd,
+                &["geo", "nx", "CH", "13.4", "52.5", "berlin"]
+            )
+            .is_ok()
+        );
+        assert!(arity(RequestType::ZAdd, &["zset", "1", "a", "2", "b"]).is_ok());
+        assert!(arity(RequestType::ZAdd, &["zset", "XX", "GT", "CH", "1", "a"]).is_ok());
+        assert!(arity(RequestType::Del, &["k1", "k2"]).is_ok());
+        assert!(arity(RequestType::MGet, &["k1"]).is_ok());
+    }
+
+    #[test]
+    fn arity_rejects_malformed_commands() {
+        assert!(arity(RequestType::MSet, &[]).is_err());
+        assert!(arity(RequestType::MSet, &["k1", "v1", "k2"]).is_err());
+        assert!(arity(RequestType::MSetNX, &["k1"]).is_err());
+        assert!(arity(RequestType::HSet, &["hash"]).is_err());
+        assert!(arity(RequestType::HSet, &["hash", "f1", "v1", "f2"]).is_err());
+        assert!(arity(RequestType::GeoAdd, &["geo", "NX"]).is_err());
+        assert!(arity(RequestType::GeoAdd, &["geo", "13.4", "52.5"]).is_err());
+        assert!(arity(RequestType::ZAdd, &["zset", "CH"]).is_err());
+        assert!(arity(RequestType::ZAdd, &["zset", "1", "a", "2"]).is_err());
+        assert!(arity(RequestType::Del, &[]).is_err());
+        assert!(arity(RequestType::Exists, &[]).is_err());
+    }
+
+    #[test]
+    fn arity_leaves_other_commands_to_the_server() {
+        assert!(arity(RequestType::CustomCommand, &["MSET", "k1"]).is_ok());
+        assert!(arity(RequestType::Get, &[]).is_ok());
+    }
+
+    #[test]
+    fn create_cmd_validates_arity_only_when_enabled() {
+        let values = [b"k1".as_slice(), b"v1", b"k2"];
+        let pointers: Vec<*const u8> = values.iter().map(|arg| arg.as_ptr()).collect();
+        let lengths: Vec<usize> = values.iter().map(|arg| arg.len()).collect();
+        let info = CmdInfo {
+            request_type: RequestType::MSet,
+            args: pointers.as_ptr(),
+            arg_count: values.len(),
+            args_len: lengths.as_ptr(),
+            operation_class: OperationClass::Unspecified,
+            decode_numbers: false,
+            skip_compression: false,
+        };
+
+        let cmd = unsafe { create_cmd(&info, None, None, false) }.expect("validation is disabled");
+        assert_eq!(args(&cmd), [&b"MSET"[..], b"k1", b"v1", b"k2"]);
+        assert!(unsafe { create_cmd(&info, None, None, true) }.is_err());
+        let cmd = unsafe { create_cmd(&info, None, Some(&b"app:"[..]), false) }.unwrap();
+        assert_eq!(args(&cmd), [&b"MSET"[..], b"app:k1", b"v1", b"app:k2"]);
+    }
+
+    #[test]
+    fn arity_error_names_the_command() {
+        assert_eq!(
+            arity(RequestType::MSetNX, &["k1"]),
+            Err("MSETNX requires an even number of arguments, as key-value pairs".into())
+        );
+        assert_eq!(
+            arity(RequestType::Unlink, &[]),
+            Err("UNLINK requires at least one key".into())
+        );
+    }
+}
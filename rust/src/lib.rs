@@ -2,9 +2,17 @@
 
 mod ffi;
 use ffi::{
-    BatchInfo, BatchOptionsInfo, CmdInfo, ConnectionConfig, PubSubCallback, PushKind,
-    ResponseValue, RouteInfo, create_cmd, create_connection_request, create_pipeline, create_route,
-    get_pipeline_options,
+    AutoPipelineConfig, BatchInfo, BatchOptionsInfo, ClusterNode, CmdInfo, CommandPollStatus,
+    ConnectionConfig, ListDirection, PreferredNodeFallback, PubSubCallback, PushKind,
+    ReadOnlyRetryConfig, RequestTimeouts, ResponseValue, RouteInfo, apply_key_prefix,
+    check_read_only, check_response_size, check_same_slot, client_lib_version,
+    core_request_timeout, create_client_setinfo_cmds, create_cmd, create_connection_request,
+    create_failover_cmd, create_pipeline, create_route, create_wait_aof_cmd,
+    decode_numeric_strings, effective_config, first_key_route, get_auto_pipeline_config,
+    get_key_prefix, get_pipeline_options, is_blocking_cmd, max_response_bytes, parse_cluster_epoch,
+    parse_cluster_nodes, parse_tracking_info, parse_wait_aof_response, parse_xinfo_groups,
+    parse_xinfo_stream, prefixed_scan_pattern, read_only_retry_config, read_only_route,
+    request_timeouts, scan_cursor_id, strip_key_prefix, subscribe_confirmation_timeout,
 };
 use glide_core::{
     GlideOpenTelemetry, GlideOpenTelemetryConfigBuilder, GlideOpenTelemetrySignalsExporter,
@@ -13,7 +21,7 @@ use glide_core::{
     errors::{RequestErrorType, error_message, error_type},
     request_type::RequestType,
 };
-use redis::cluster_routing::Routable;
+use redis::cluster_routing::{MultipleNodeRoutingInfo, ResponsePolicy, Routable, RoutingInfo};
 use std::{
     ffi::{CStr, CString, c_char, c_void},
     slice::from_raw_parts,
@@ -59,6 +67,160 @@ pub struct Client {
     core: Arc<CommandExecutionCore>,
     pubsub_shutdown: std::sync::Mutex<Option<tokio::sync::oneshot::Sender<()>>>,
     pubsub_task: std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>,
+    /// Received PubSub messages, `None` unless the client has subscriptions but no PubSub callback, see [`get_pubsub_message`].
+    pubsub_queue: Option<std::sync::Mutex<PushReceiver>>,
+    /// Queue of the auto-pipelining flush task, `None` unless enabled in [`ConnectionConfig`](ffi::ConnectionConfig).
+    auto_pipeline: Option<tokio::sync::mpsc::UnboundedSender<AutoPipelineMessage>>,
+    /// Per-operation-class request timeouts.
+    request_timeouts: RequestTimeouts,
+    /// Command tasks that have not completed yet.
+    inflight: Arc<InflightTasks>,
+    /// Result slots of commands executed with [`command_async`].
+    command_handles: Arc<CommandHandles>,
+    /// Whether the client was created in cluster mode.
+    cluster_mode: bool,
+    /// The configured username, see [`update_connection_credentials`].
+    username: Option<String>,
+    /// The prefix prepended to key arguments, `None` unless configured in [`ConnectionConfig`](ffi::ConnectionConfig).
+    key_prefix: Option<Vec<u8>>,
+    /// Whether commands are checked by `validate_command_arity` before being sent.
+    validate_command_arity: bool,
+    /// The currently selected database, see [`get_database`].
+    database_id: Arc<std::sync::atomic::AtomicI64>,
+    /// Callbacks of subscriptions made with [`subscribe_with_callback`].
+    subscription_callbacks: Arc<SubscriptionCallbacks>,
+    /// Counters of the client's delivered and dropped PubSub messages.
+    pubsub_counters: Arc<PubSubCounters>,
+    /// The settings applied when the client was created, see [`get_effective_config`].
+    effective_config: redis::Value,
+}
+
+/// Callbacks of subscriptions made with [`subscribe_with_callback`], by subscription kind and channel or pattern.
+type SubscriptionCallbacks = std::sync::RwLock<
+    std::collections::HashMap<(redis::PubSubSubscriptionKind, Vec<u8>), PubSubCallback>,
+>;
+
+impl Client {
+    /// Build a command with [`create_cmd`], validating its arity, compressing its values and prefixing its keys as configured.
+    ///
+    /// # Safety
+    /// * `cmd_ptr` must be able to be safely casted to a valid [`CmdInfo`]. See the safety documentation of [`create_cmd`].
+    #[allow(rustdoc::private_intra_doc_links)]
+    unsafe fn create_cmd(&self, cmd_ptr: *const CmdInfo) -> Result<redis::Cmd, String> {
+        unsafe {
+            create_cmd(
+                cmd_ptr,
+                self.core.client.compression_manager().as_ref(),
+                self.key_prefix.as_deref(),
+                self.validate_command_arity,
+            )
+        }
+    }
+
+    /// Prefix `key` as configured, for the exports taking keys directly rather than a command built with [`create_cmd`].
+    #[allow(rustdoc::private_intra_doc_links)]
+    fn prefixed_key(&self, key: &[u8]) -> Vec<u8> {
+        match &self.key_prefix {
+            Some(prefix) => [prefix, key].concat(),
+            None => key.to_vec(),
+        }
+    }
+
+    /// Spawn a task on the client's runtime, tracking it as in-flight until it completes.
+    ///
+    /// Every task which may call the client's callbacks should be spawned this way, so that [`close_client_with_timeout`] can wait for it.
+    fn spawn_tracked<F>(&self, task: F)
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let guard = self.inflight.track();
+        self.runtime.spawn(async move {
+            task.await;
+            drop(guard);
+        });
+    }
+}
+
+/// Counts the in-flight tasks of a client.
+#[derive(Default)]
+struct InflightTasks {
+    count: std::sync::atomic::AtomicUsize,
+    idle: tokio::sync::Notify,
+    /// Commands sent by [`command`] and not completed yet, keyed by callback index, see [`list_blocking_commands`].
+    commands: std::sync::Mutex<std::collections::HashMap<usize, InflightCommand>>,
+}
+
+/// A command tracked by [`InflightTasks::track_command`].
+struct InflightCommand {
+    name: Vec<u8>,
+    started: std::time::Instant,
+}
+
+impl InflightTasks {
+    /// Track a task until the returned guard is dropped.
+    fn track(self: &Arc<Self>) -> InflightTaskGuard {
+        self.count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        InflightTaskGuard(self.clone())
+    }
+
+    /// Track a command until the returned guard is dropped, see [`list_blocking_commands`].
+    fn track_command(
+        self: &Arc<Self>,
+        callback_index: usize,
+        cmd: &redis::Cmd,
+    ) -> InflightCommandGuard {
+        let command = InflightCommand {
+            name: cmd.command().unwrap_or_default(),
+            started: std::time::Instant::now(),
+        };
+        self.lock_commands().insert(callback_index, command);
+        InflightCommandGuard(self.clone(), callback_index)
+    }
+
+    fn lock_commands(
+        &self,
+    ) -> std::sync::MutexGuard<'_, std::collections::HashMap<usize, InflightCommand>> {
+        self.commands
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Wait until no task is in flight.
+    async fn wait_idle(&self) {
+        loop {
+            // Created before checking the count, so a notification sent in between is not missed.
+            let notified = self.idle.notified();
+            if self.count.load(std::sync::atomic::Ordering::SeqCst) == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Marks a task tracked by [`InflightTasks`] as completed when dropped, including when the task panics.
+struct InflightTaskGuard(Arc<InflightTasks>);
+
+impl Drop for InflightTaskGuard {
+    fn drop(&mut self) {
+        if self
+            .0
+            .count
+            .fetch_sub(1, std::sync::atomic::Ordering::SeqCst)
+            == 1
+        {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+/// Stops tracking a command tracked by [`InflightTasks::track_command`] when dropped, including when the task panics.
+struct InflightCommandGuard(Arc<InflightTasks>, usize);
+
+impl Drop for InflightCommandGuard {
+    fn drop(&mut self) {
+        self.0.lock_commands().remove(&self.1);
+    }
 }
 
 /// Success callback that is called when a command succeeds.
@@ -97,6 +259,77 @@ pub type FailureCallback = unsafe extern "C-unwind" fn(
     error_type: RequestErrorType,
 ) -> ();
 
+/// Callback receiving the duration of a command, see [`set_command_timing_callback`].
+///
+/// # Arguments
+/// * `index` is the callback index of the command.
+/// * `elapsed_ns` is the time in nanoseconds from sending the command until its reply or error was received.
+///
+/// # Safety
+/// * The callback must return ASAP, see [`SuccessCallback`].
+pub type CommandTimingCallback = unsafe extern "C-unwind" fn(index: usize, elapsed_ns: u64) -> ();
+
+/// Callback for errors that are not tied to a request, see [`set_global_error_callback`].
+///
+/// # Arguments
+/// * `error_type` is the type of the error.
+/// * `error_message` is an UTF-8 string describing the error, managed by Rust and freed when the callback returns.
+///
+/// # Safety
+/// * The callback must copy the message in a sync manner and return ASAP, see [`FailureCallback`].
+pub type GlobalErrorCallback =
+    unsafe extern "C-unwind" fn(error_type: RequestErrorType, error_message: *const c_char) -> ();
+
+/// The callback registered with [`set_global_error_callback`].
+static GLOBAL_ERROR_CALLBACK: std::sync::RwLock<Option<GlobalErrorCallback>> =
+    std::sync::RwLock::new(None);
+
+/// Register a callback for errors that are not tied to a request, replacing any previously registered one.
+/// Pass `null` to unregister it.
+///
+/// The callback is shared by all clients of the process, and is called for:
+/// * disconnections observed by PubSub tasks, after which glide-core reconnects in the background, with [`RequestErrorType::Disconnect`];
+/// * PubSub messages dropped because of an invalid structure, with [`RequestErrorType::Unspecified`].
+///
+/// These errors are logged whether or not a callback is registered. Other background errors of glide-core, e.g. failed
+/// reconnection attempts, are only logged by glide-core itself.
+///
+/// # Safety
+/// * `callback` must be `null` or a valid function pointer, which remains valid until it is replaced or unregistered.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn set_global_error_callback(callback: Option<GlobalErrorCallback>) {
+    *GLOBAL_ERROR_CALLBACK
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = callback;
+}
+
+/// Report an error which is not tied to a request to the callback registered with [`set_global_error_callback`], if any.
+fn report_global_error(error_message: String, error_type: RequestErrorType) {
+    let callback = *GLOBAL_ERROR_CALLBACK
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let Some(callback) = callback else {
+        return;
+    };
+    let Ok(error_message) = CString::new(error_message) else {
+        return;
+    };
+    unsafe { callback(error_type, error_message.as_ptr()) };
+}
+
+/// Callback that receives one batch of a collection fetched incrementally, see [`safe_smembers`], [`safe_hgetall`] and
+/// [`scan_all_keys`].
+///
+/// # Arguments
+/// * `index` is the callback index of the fetch the batch belongs to.
+/// * `batch` is the batch of elements, an array for sets and keys and a map for hashes.
+///
+/// # Safety
+/// * The callback must copy the pointer in a sync manner and return ASAP, see [`SuccessCallback`].
+/// * The callee is responsible to free memory by calling [`free_response`] with the given pointer once only.
+pub type FetchBatchCallback =
+    unsafe extern "C-unwind" fn(index: usize, batch: *const ResponseValue) -> ();
+
 /// Callback for resolving server addresses before connection.
 ///
 /// Invoked synchronously during connection setup to translate a configured (host, port) pair
@@ -176,6 +409,68 @@ struct CommandExecutionCore {
     client: GlideClient,
     success_callback: SuccessCallback,
     failure_callback: FailureCallback,
+    /// Set with [`set_command_timing_callback`], commands are only timed while it is set.
+    timing_callback: std::sync::RwLock<Option<CommandTimingCallback>>,
+    /// Set with [`set_fault_injection`].
+    #[cfg(feature = "fault-injection")]
+    fault_injection: std::sync::Mutex<Option<FaultInjection>>,
+    /// Whether command replies keep their RESP string type, see [`ResponseValue::from_value_preserving_wire_types`].
+    preserve_wire_types: bool,
+    /// Retries of commands rejected with a `READONLY` error, see [`send_command_retrying_read_only`].
+    read_only_retry: Option<ReadOnlyRetryConfig>,
+    /// Replies larger than this fail their command, see [`max_response_bytes`].
+    max_response_bytes: Option<u64>,
+    /// The request timeout glide-core applies to commands, in milliseconds, see [`send_command_with_timeout`].
+    request_timeout_ms: u32,
+    /// The global request timeout, in milliseconds, see [`CommandExecutionCore::batch_timeout`].
+    default_request_timeout_ms: u32,
+}
+
+impl CommandExecutionCore {
+    /// Start timing a command if a timing callback is set, see [`set_command_timing_callback`].
+    fn start_timing(&self) -> Option<(CommandTimingCallback, std::time::Instant)> {
+        let callback = *self
+            .timing_callback
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        callback.map(|callback| (callback, std::time::Instant::now()))
+    }
+
+    /// Convert a command reply, keeping the RESP type of its strings if the client was configured to.
+    ///
+    /// Fails if the reply is larger than the client's maximal response size.
+    fn response_value(&self, value: redis::Value) -> Result<ResponseValue, String> {
+        if let Some(limit) = self.max_response_bytes {
+            check_response_size(&value, limit)?;
+        }
+        if self.preserve_wire_types {
+            ResponseValue::from_value_preserving_wire_types(value)
+        } else {
+            ResponseValue::from_value(value)
+        }
+    }
+
+    /// The timeout of a batch, the global request timeout unless `timeout` is given.
+    ///
+    /// glide-core's request timeout is the longest of the per-class timeouts, see [`RequestTimeouts`], which a batch
+    /// mixing reads and writes must not inherit.
+    fn batch_timeout(&self, timeout: Option<u32>) -> Option<u32> {
+        timeout.or(Some(self.default_request_timeout_ms))
+    }
+}
+
+/// Report the duration of a command started with [`CommandExecutionCore::start_timing`].
+///
+/// # Safety
+/// * The timing callback must be valid, see [`CommandTimingCallback`].
+unsafe fn report_timing(
+    timing: Option<(CommandTimingCallback, std::time::Instant)>,
+    callback_index: usize,
+) {
+    if let Some((callback, start)) = timing {
+        let elapsed_ns = u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        unsafe { callback(callback_index, elapsed_ns) };
+    }
 }
 
 /// # Safety
@@ -205,6 +500,7 @@ struct PanicGuard {
 impl Drop for PanicGuard {
     fn drop(&mut self) {
         if self.panicked {
+            CALLBACK_PANICS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
             unsafe {
                 report_error(
                     self.failure_callback,
@@ -255,6 +551,54 @@ pub unsafe extern "C-unwind" fn create_client(
         }
     };
 
+    let auto_pipeline_config = match unsafe { get_auto_pipeline_config(config) } {
+        Ok(auto_pipeline_config) => auto_pipeline_config,
+        Err(err) => {
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(failure_callback, 0, err, RequestErrorType::Unspecified);
+            }
+            return;
+        }
+    };
+
+    let key_prefix = match unsafe { get_key_prefix(config) } {
+        Ok(key_prefix) => key_prefix,
+        Err(err) => {
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(failure_callback, 0, err, RequestErrorType::Unspecified);
+            }
+            return;
+        }
+    };
+
+    let lib_version = match unsafe { client_lib_version(&*config) } {
+        Ok(lib_version) => lib_version.filter(|_| !request.lazy_connect),
+        Err(err) => {
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(failure_callback, 0, err, RequestErrorType::Unspecified);
+            }
+            return;
+        }
+    };
+
+    let request_timeouts = unsafe { request_timeouts(&*config) };
+    let preserve_wire_types = unsafe { (*config).preserve_wire_types };
+    let validate_command_arity = unsafe { (*config).validate_command_arity };
+    let read_only_retry = read_only_retry_config(unsafe { &*config });
+    let max_response_bytes = max_response_bytes(unsafe { &*config });
+    let confirmation_timeout = subscribe_confirmation_timeout(unsafe { &*config });
+    let cluster_mode = request.cluster_mode_enabled;
+    let username = request
+        .authentication_info
+        .as_ref()
+        .and_then(|auth| auth.username.clone());
+    let database_id = request.database_id;
+    let effective_config = effective_config(&request, request_timeouts, max_response_bytes);
+    let request_timeout_ms = core_request_timeout(&request);
+
     // Set address resolver if provided
     if let Some(cb) = address_resolver {
         request.address_resolver = Some(std::sync::Arc::new(FFIAddressResolver { callback: cb }));
@@ -272,24 +616,76 @@ pub unsafe extern "C-unwind" fn create_client(
     // Set up push notification channel if PubSub subscriptions are configured
     // The callback is optional - users can use queue-based message retrieval instead
     let is_subscriber = request.pubsub_subscriptions.is_some();
-
-    let (push_tx, mut push_rx) = tokio::sync::mpsc::unbounded_channel();
+    // Lazily connected clients only subscribe once connected, so there is nothing to wait for yet.
+    let awaited_subscriptions = confirmation_timeout
+        .filter(|_| !request.lazy_connect)
+        .zip(request.pubsub_subscriptions.clone());
+
+    let (push_tx, push_rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut push_rx = PushReceiver {
+        buffered: std::collections::VecDeque::new(),
+        receiver: push_rx,
+    };
     let tx = if is_subscriber { Some(push_tx) } else { None };
 
     let res = runtime.block_on(GlideClient::new(request, tx));
     match res {
         Ok(client) => {
+            if let Some((timeout, subscriptions)) = awaited_subscriptions {
+                let confirmed = runtime.block_on(await_subscription_confirmations(
+                    &mut push_rx,
+                    subscriptions,
+                    timeout,
+                ));
+                if let Err(err) = confirmed {
+                    unsafe { report_error(failure_callback, 0, err, RequestErrorType::Timeout) };
+                    panic_guard.panicked = false;
+                    return;
+                }
+            }
+
+            // Servers older than 7.2 don't support `CLIENT SETINFO`, so failing to set the version doesn't fail the client.
+            if let Some(lib_version) = lib_version {
+                let mut cmd = redis::cmd("CLIENT");
+                cmd.arg("SETINFO").arg("LIB-VER").arg(lib_version);
+                let route = RoutingInfo::MultiNode((
+                    MultipleNodeRoutingInfo::AllNodes,
+                    Some(ResponsePolicy::AllSucceeded),
+                ));
+                if let Err(err) =
+                    runtime.block_on(client.clone().send_command(&mut cmd, Some(route)))
+                {
+                    logger_core::log(
+                        logger_core::Level::Warn,
+                        "client",
+                        format!("Failed to set the library version: {}", error_message(&err)),
+                    );
+                }
+            }
+
+            let subscription_callbacks = Arc::new(SubscriptionCallbacks::default());
+            let pubsub_counters = Arc::new(PubSubCounters::default());
             let core = Arc::new(CommandExecutionCore {
                 success_callback,
                 failure_callback,
                 client,
+                timing_callback: std::sync::RwLock::new(None),
+                #[cfg(feature = "fault-injection")]
+                fault_injection: std::sync::Mutex::new(None),
+                preserve_wire_types,
+                read_only_retry,
+                max_response_bytes,
+                request_timeout_ms,
+                default_request_timeout_ms: request_timeouts.default,
             });
 
             // Set up graceful shutdown coordination for PubSub task
-            // Only spawn the callback task if a callback is provided
-            let (pubsub_shutdown, pubsub_task) =
+            // Only spawn the callback task if a callback is provided, otherwise messages are queued for `get_pubsub_message`
+            let (pubsub_shutdown, pubsub_task, pubsub_queue) =
                 if let (true, Some(callback)) = (is_subscriber, pubsub_callback) {
                     let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+                    let subscription_callbacks = subscription_callbacks.clone();
+                    let pubsub_counters = pubsub_counters.clone();
 
                     let task_handle = runtime.spawn(async move {
                         logger_core::log(logger_core::Level::Info, "pubsub", "PubSub task started");
@@ -298,7 +694,12 @@ pub unsafe extern "C-unwind" fn create_client(
                             tokio::select! {
                                 Some(push_msg) = push_rx.recv() => {
                                     unsafe {
-                                        process_push_notification(push_msg, callback);
+                                        process_push_notification(
+                                            push_msg,
+                                            callback,
+                                            &subscription_callbacks,
+                                            &pubsub_counters,
+                                        );
                                     }
                                 }
                                 _ = &mut shutdown_rx => {
@@ -322,16 +723,46 @@ pub unsafe extern "C-unwind" fn create_client(
                     (
                         std::sync::Mutex::new(Some(shutdown_tx)),
                         std::sync::Mutex::new(Some(task_handle)),
+                        None,
                     )
                 } else {
-                    (std::sync::Mutex::new(None), std::sync::Mutex::new(None))
+                    (
+                        std::sync::Mutex::new(None),
+                        std::sync::Mutex::new(None),
+                        is_subscriber.then(|| std::sync::Mutex::new(push_rx)),
+                    )
                 };
 
+            let inflight = Arc::new(InflightTasks::default());
+            let auto_pipeline = auto_pipeline_config.map(|auto_pipeline_config| {
+                let (queue_tx, queue_rx) = tokio::sync::mpsc::unbounded_channel();
+                runtime.spawn(run_auto_pipeline(
+                    core.clone(),
+                    queue_rx,
+                    auto_pipeline_config,
+                    inflight.clone(),
+                ));
+                queue_tx
+            });
+
             let client_adapter = Arc::new(Client {
                 runtime,
                 core,
                 pubsub_shutdown,
                 pubsub_task,
+                pubsub_queue,
+                auto_pipeline,
+                request_timeouts,
+                inflight,
+                command_handles: Arc::new(CommandHandles::default()),
+                cluster_mode,
+                username,
+                key_prefix,
+                validate_command_arity,
+                database_id: Arc::new(std::sync::atomic::AtomicI64::new(database_id)),
+                subscription_callbacks,
+                pubsub_counters,
+                effective_config,
             });
             let client_ptr = Arc::into_raw(client_adapter.clone());
 
@@ -353,33 +784,200 @@ pub unsafe extern "C-unwind" fn create_client(
     drop(panic_guard);
 }
 
-/// Processes a push notification message and calls the provided callback function.
+/// Receiver of the push notifications of a client.
 ///
-/// This function extracts the message data from the PushInfo and invokes the C# callback
-/// with the appropriate parameters using scoped lifetime management to prevent memory leaks.
-///
-/// # Parameters
-/// - `push_msg`: The push notification message to process.
-/// - `pubsub_callback`: The callback function to invoke with the processed notification.
+/// Notifications received while [`create_client`] waits for subscription confirmations are buffered, and returned first.
+struct PushReceiver {
+    buffered: std::collections::VecDeque<redis::PushInfo>,
+    receiver: tokio::sync::mpsc::UnboundedReceiver<redis::PushInfo>,
+}
+
+impl PushReceiver {
+    async fn recv(&mut self) -> Option<redis::PushInfo> {
+        match self.buffered.pop_front() {
+            Some(push_msg) => Some(push_msg),
+            None => self.receiver.recv().await,
+        }
+    }
+
+    fn try_recv(&mut self) -> Option<redis::PushInfo> {
+        self.buffered
+            .pop_front()
+            .or_else(|| self.receiver.try_recv().ok())
+    }
+}
+
+/// Wait until the server confirmed every configured subscription, buffering the notifications received meanwhile.
 ///
-/// # Safety
-/// This function is unsafe because it:
-/// - Calls an FFI function (`pubsub_callback`) that may have undefined behavior
-/// - Assumes push_msg.data contains valid BulkString values
+/// This is used when `subscribe_confirmation_timeout` is set in [`ConnectionConfig`], so that subscribing to an
+/// unresponsive server (e.g. during a failover) fails client creation instead of silently never delivering messages.
 ///
-/// The caller must ensure:
-/// - `pubsub_callback` is a valid function pointer to a properly implemented callback
-/// - The callback copies data synchronously before returning
+/// # Returns
+/// * An error naming the subscriptions which were not confirmed within `timeout`.
+async fn await_subscription_confirmations(
+    push_rx: &mut PushReceiver,
+    subscriptions: std::collections::HashMap<
+        redis::PubSubSubscriptionKind,
+        std::collections::HashSet<Vec<u8>>,
+    >,
+    timeout: std::time::Duration,
+) -> Result<(), String> {
+    use redis::PubSubSubscriptionKind;
+
+    let mut pending: std::collections::HashSet<(PubSubSubscriptionKind, Vec<u8>)> = subscriptions
+        .into_iter()
+        .flat_map(|(kind, channels)| {
+            channels
+                .into_iter()
+                .map(move |channel| (kind.clone(), channel))
+        })
+        .collect();
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    while !pending.is_empty() {
+        match tokio::time::timeout_at(deadline, push_rx.receiver.recv()).await {
+            Ok(Some(push_msg)) => {
+                let kind = match push_msg.kind {
+                    redis::PushKind::Subscribe => Some(PubSubSubscriptionKind::Exact),
+                    redis::PushKind::PSubscribe => Some(PubSubSubscriptionKind::Pattern),
+                    redis::PushKind::SSubscribe => Some(PubSubSubscriptionKind::Sharded),
+                    _ => None,
+                };
+                if let (Some(kind), Some(redis::Value::BulkString(channel))) =
+                    (kind, push_msg.data.first())
+                {
+                    pending.remove(&(kind, channel.to_vec()));
+                }
+                push_rx.buffered.push_back(push_msg);
+            }
+            Ok(None) | Err(_) => break,
+        }
+    }
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let mut unconfirmed: Vec<String> = pending
+        .iter()
+        .map(|(_, channel)| String::from_utf8_lossy(channel).into_owned())
+        .collect();
+    unconfirmed.sort();
+    Err(format!(
+        "The server did not confirm the subscriptions to {} within {}ms",
+        unconfirmed.join(", "),
+        timeout.as_millis()
+    ))
+}
+
+/// Key of the sequence numbers of the PubSub callback passed to [`create_client`], or of the queue of a client created
+/// without one. Function pointers are never null, so this doesn't collide with the key of a subscription callback.
+const DEFAULT_SEQUENCE_KEY: usize = 0;
+
+/// Counters of the PubSub messages received by a client.
+#[derive(Default)]
+struct PubSubCounters {
+    /// The sequence number of the last message numbered for each callback, keyed by the callback's address or
+    /// [`DEFAULT_SEQUENCE_KEY`], see [`PubSubMessage::sequence`].
+    sequences: std::sync::Mutex<std::collections::HashMap<usize, u64>>,
+    /// Number of messages dropped since the last reset, see [`get_pubsub_dropped_count`].
+    dropped: std::sync::atomic::AtomicU64,
+}
+
+impl PubSubCounters {
+    /// Consume the next sequence number of the callback with the key `key`.
+    fn next_sequence(&self, key: usize) -> u64 {
+        let mut sequences = self
+            .sequences
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let sequence = sequences.entry(key).or_default();
+        *sequence += 1;
+        *sequence
+    }
+
+    /// Forget the sequence numbers of the callback with the key `key`, once it has no subscription left.
+    fn forget_sequence(&self, key: usize) {
+        self.sequences
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&key);
+    }
+}
+
+/// A PubSub message or subscription confirmation, as received from the server.
+struct PubSubMessage {
+    kind: PushKind,
+    pattern: Option<Vec<u8>>,
+    channel: Vec<u8>,
+    message: Vec<u8>,
+    /// Number of the message among those of the callback it is dispatched to, starting at 1 and incremented by 1 for every
+    /// message delivered to or dropped for the callback, including across reconnections, so that a gap means a message
+    /// was lost. Subscription confirmations are not numbered and have the sequence number 0.
+    sequence: u64,
+}
+
+impl PubSubMessage {
+    /// The name of the message kind, as used in [`PubSubMessage::into_value`].
+    fn kind_name(&self) -> &'static str {
+        match self.kind {
+            PushKind::Message => "message",
+            PushKind::PMessage => "pmessage",
+            PushKind::SMessage => "smessage",
+            PushKind::Subscribe => "subscribe",
+            PushKind::PSubscribe => "psubscribe",
+            PushKind::SSubscribe => "ssubscribe",
+            PushKind::Unsubscribe => "unsubscribe",
+            PushKind::PUnsubscribe => "punsubscribe",
+            PushKind::SUnsubscribe => "sunsubscribe",
+            PushKind::Disconnection | PushKind::Invalidate | PushKind::Other => "other",
+        }
+    }
+
+    /// Convert to the structured representation returned by [`get_pubsub_message`] and [`drain_pubsub_messages`]:
+    /// a map with `kind` (e.g. `"pmessage"`), `pattern` (nil unless the kind is pattern-based), `channel`, `message` and
+    /// `sequence`, see [`PubSubMessage::sequence`]. For subscription confirmations, `message` holds the number of
+    /// subscriptions.
+    fn into_value(self) -> redis::Value {
+        use redis::Value;
+
+        let kind = Value::SimpleString(self.kind_name().to_string());
+        Value::Map(vec![
+            (Value::SimpleString("kind".to_string()), kind),
+            (
+                Value::SimpleString("pattern".to_string()),
+                self.pattern
+                    .map_or(Value::Nil, |pattern| Value::BulkString(pattern.into())),
+            ),
+            (
+                Value::SimpleString("channel".to_string()),
+                Value::BulkString(self.channel.into()),
+            ),
+            (
+                Value::SimpleString("message".to_string()),
+                Value::BulkString(self.message.into()),
+            ),
+            (
+                Value::SimpleString("sequence".to_string()),
+                Value::Int(i64::try_from(self.sequence).unwrap_or(i64::MAX)),
+            ),
+        ])
+    }
+}
+
+/// Extracts the message data from a push notification. A message is numbered with the next sequence number of the
+/// callback it is dispatched to in `counters`, whose key `sequence_key` returns for the message's subscription.
 ///
-/// # Memory Safety
-/// This implementation uses scoped lifetime management instead of `std::mem::forget()`.
-/// `Vec<u8>` instances are kept alive during callback execution and automatically cleaned up
-/// when the function exits, preventing memory leaks.
-unsafe fn process_push_notification(push_msg: redis::PushInfo, pubsub_callback: PubSubCallback) {
+/// Returns `None` for disconnection notifications and for messages with an invalid structure, which are logged and
+/// counted. An invalid message still consumes a sequence number if its subscription can be told, so that it shows as a gap.
+fn parse_push_notification(
+    push_msg: redis::PushInfo,
+    counters: &PubSubCounters,
+    sequence_key: impl Fn(&(redis::PubSubSubscriptionKind, Vec<u8>)) -> usize,
+) -> Option<PubSubMessage> {
     use redis::Value;
 
     // Convert all values to Vec<u8>, handling both BulkString and Int types
-    let strings: Vec<Vec<u8>> = push_msg
+    let mut strings: Vec<Vec<u8>> = push_msg
         .data
         .into_iter()
         .map(|value| match value {
@@ -397,129 +995,489 @@ unsafe fn process_push_notification(push_msg: redis::PushInfo, pubsub_callback:
         })
         .collect();
 
-    // Store the kind to avoid move issues
-    let push_kind = push_msg.kind.clone();
+    let push_kind = push_msg.kind;
+    let len = strings.len();
+    let mut take = |index: usize| std::mem::take(&mut strings[index]);
 
     // Validate message structure based on PushKind and convert to FFI kind
     // The FFI PushKind enum is defined in ffi.rs and matches the C# PushKind enum in FFI.structs.cs
-    let (pattern, channel, message, kind) = match (push_kind.clone(), strings.len()) {
+    let (pattern, channel, message, kind) = match (push_kind, len) {
         (redis::PushKind::Message, 2) => {
             // Regular message: [channel, message]
-            (None, &strings[0], &strings[1], PushKind::Message)
+            (None, take(0), take(1), PushKind::Message)
         }
         (redis::PushKind::PMessage, 3) => {
             // Pattern message: [pattern, channel, message]
-            (
-                Some(&strings[0]),
-                &strings[1],
-                &strings[2],
-                PushKind::PMessage,
-            )
+            (Some(take(0)), take(1), take(2), PushKind::PMessage)
         }
         (redis::PushKind::SMessage, 2) => {
             // Sharded message: [channel, message]
-            (None, &strings[0], &strings[1], PushKind::SMessage)
+            (None, take(0), take(1), PushKind::SMessage)
         }
         (redis::PushKind::Subscribe, 2) => {
             // Subscribe confirmation: [channel, count]
-            (None, &strings[0], &strings[1], PushKind::Subscribe)
+            (None, take(0), take(1), PushKind::Subscribe)
         }
         (redis::PushKind::PSubscribe, 3) => {
             // Pattern subscribe confirmation: [pattern, channel, count]
-            (
-                Some(&strings[0]),
-                &strings[1],
-                &strings[2],
-                PushKind::PSubscribe,
-            )
+            (Some(take(0)), take(1), take(2), PushKind::PSubscribe)
         }
         (redis::PushKind::SSubscribe, 2) => {
             // Sharded subscribe confirmation: [channel, count]
-            (None, &strings[0], &strings[1], PushKind::SSubscribe)
+            (None, take(0), take(1), PushKind::SSubscribe)
         }
         (redis::PushKind::Unsubscribe, 2) => {
             // Unsubscribe confirmation: [channel, count]
-            (None, &strings[0], &strings[1], PushKind::Unsubscribe)
+            (None, take(0), take(1), PushKind::Unsubscribe)
         }
         (redis::PushKind::PUnsubscribe, 3) => {
             // Pattern unsubscribe confirmation: [pattern, channel, count]
-            (
-                Some(&strings[0]),
-                &strings[1],
-                &strings[2],
-                PushKind::PUnsubscribe,
-            )
+            (Some(take(0)), take(1), take(2), PushKind::PUnsubscribe)
         }
         (redis::PushKind::SUnsubscribe, 2) => {
             // Sharded unsubscribe confirmation: [channel, count]
-            (None, &strings[0], &strings[1], PushKind::SUnsubscribe)
+            (None, take(0), take(1), PushKind::SUnsubscribe)
         }
         (redis::PushKind::Disconnection, _) => {
+            PUBSUB_DISCONNECTIONS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
             logger_core::log(
                 logger_core::Level::Info,
                 "pubsub",
                 "PubSub disconnection received",
             );
-            return;
+            report_global_error(
+                "Disconnected from the server, reconnecting".into(),
+                RequestErrorType::Disconnect,
+            );
+            return None;
         }
         (kind, len) => {
-            logger_core::log(
-                logger_core::Level::Error,
-                "pubsub",
-                format!(
-                    "Invalid PubSub message structure: kind={:?}, len={}",
-                    kind, len
-                ),
+            PUBSUB_INVALID_MESSAGES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            counters
+                .dropped
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            let message_kind = match kind {
+                redis::PushKind::Message => Some(PushKind::Message),
+                redis::PushKind::PMessage => Some(PushKind::PMessage),
+                redis::PushKind::SMessage => Some(PushKind::SMessage),
+                _ => None,
+            };
+            // The first element of a message is its channel, or its pattern for a pattern message.
+            let first = strings.first().map_or(&[][..], Vec::as_slice);
+            if let Some(subscription) =
+                message_kind.and_then(|kind| subscription_of(kind, Some(first), first))
+            {
+                counters.next_sequence(sequence_key(&subscription));
+            }
+            let error_message = format!(
+                "Invalid PubSub message structure: kind={:?}, len={}",
+                kind, len
             );
-            return;
+            logger_core::log(logger_core::Level::Error, "pubsub", &error_message);
+            report_global_error(error_message, RequestErrorType::Unspecified);
+            return None;
         }
     };
 
-    // Prepare pointers while keeping strings alive
-    let pattern_ptr = pattern.map(|p| p.as_ptr()).unwrap_or(std::ptr::null());
-    let pattern_len = pattern.map(|p| p.len() as u64).unwrap_or(0);
-    let channel_ptr = channel.as_ptr();
-    let channel_len = channel.len() as u64;
-    let message_ptr = message.as_ptr();
-    let message_len = message.len() as u64;
-
-    // Call callback while strings are still alive
-    unsafe {
-        pubsub_callback(
-            kind,
-            message_ptr,
-            message_len,
-            channel_ptr,
-            channel_len,
-            pattern_ptr,
-            pattern_len,
-        );
-    }
-
-    // Vec<u8> instances are automatically cleaned up here
-    // No memory leak, no use-after-free
+    let sequence = match kind {
+        PushKind::Message | PushKind::PMessage | PushKind::SMessage => {
+            subscription_of(kind, pattern.as_deref(), &channel).map_or(0, |subscription| {
+                counters.next_sequence(sequence_key(&subscription))
+            })
+        }
+        _ => 0,
+    };
+    Some(PubSubMessage {
+        kind,
+        pattern,
+        channel,
+        message,
+        sequence,
+    })
 }
 
-/// Closes the given client, deallocating it from the heap.
-/// This function should only be called once per pointer created by [`create_client`].
-/// After calling this function the `client_ptr` is not in a valid state.
+/// Get the next PubSub message received by a client created without a PubSub callback.
 ///
-/// Implements graceful shutdown coordination for PubSub tasks with timeout.
+/// The message is structured as described in [`PubSubMessage::into_value`]. Disconnection notifications and messages
+/// with an invalid structure are skipped.
 ///
-/// # Safety
+/// # Returns
+/// * A pointer to the message, which must be freed with [`free_response`], or null if no message is waiting, or if the
+///   client has no subscriptions or was created with a PubSub callback.
 ///
-/// * `client_ptr` must not be `null`.
-/// * `client_ptr` must be able to be safely casted to a valid [`Arc<Client>`] via [`Arc::from_raw`]. See the safety documentation of [`Arc::from_raw`].
+/// # Safety
+/// * `client_ptr` must not be `null` and must be obtained from [`create_client`], and not yet closed.
 #[unsafe(no_mangle)]
-pub extern "C" fn close_client(client_ptr: *const c_void) {
+pub unsafe extern "C" fn get_pubsub_message(client_ptr: *const c_void) -> *mut ResponseValue {
     assert!(!client_ptr.is_null());
-
-    // Get a reference to the client to access shutdown coordination
     let client = unsafe { &*(client_ptr as *const Client) };
-
-    // Take ownership of shutdown sender and signal graceful shutdown
-    if let Ok(mut guard) = client.pubsub_shutdown.lock()
-        && let Some(shutdown_tx) = guard.take()
+    let Some(queue) = &client.pubsub_queue else {
+        return std::ptr::null_mut();
+    };
+    let mut queue = queue
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    while let Some(push_msg) = queue.try_recv() {
+        if let Some(message) =
+            parse_push_notification(push_msg, &client.pubsub_counters, |_| DEFAULT_SEQUENCE_KEY)
+        {
+            return match ResponseValue::from_value(message.into_value()) {
+                Ok(response) => Box::into_raw(Box::new(response)),
+                Err(_) => std::ptr::null_mut(),
+            };
+        }
+    }
+    std::ptr::null_mut()
+}
+
+/// Get up to `max_count` PubSub messages received by a client created without a PubSub callback, in the order they were
+/// received. See [`get_pubsub_message`].
+///
+/// # Returns
+/// * A pointer to an array of messages, which must be freed with [`free_response`]. The array is empty if no message
+///   is waiting, or if the client has no subscriptions or was created with a PubSub callback. Null if the conversion failed.
+///
+/// # Safety
+/// * `client_ptr` must not be `null` and must be obtained from [`create_client`], and not yet closed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn drain_pubsub_messages(
+    client_ptr: *const c_void,
+    max_count: u32,
+) -> *mut ResponseValue {
+    assert!(!client_ptr.is_null());
+    let client = unsafe { &*(client_ptr as *const Client) };
+    let mut messages = Vec::new();
+    if let Some(queue) = &client.pubsub_queue {
+        let mut queue = queue
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        while messages.len() < max_count as usize {
+            let Some(push_msg) = queue.try_recv() else {
+                break;
+            };
+            if let Some(message) =
+                parse_push_notification(push_msg, &client.pubsub_counters, |_| DEFAULT_SEQUENCE_KEY)
+            {
+                messages.push(message.into_value());
+            }
+        }
+    }
+    match ResponseValue::from_value(redis::Value::Array(messages)) {
+        Ok(response) => Box::into_raw(Box::new(response)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Get the number of PubSub messages a client dropped since it was created or the count was last reset.
+///
+/// The PubSub channel is unbounded, so messages are currently only dropped because of an invalid structure, which is
+/// also reported to the callback registered with [`set_global_error_callback`], if any. Messages dropped because of overflow will be counted as well once a
+/// bounded channel is added. Unlike `pubsub_invalid_messages` of [`get_internal_counters`], the count is per client.
+///
+/// # Arguments
+/// * `reset` - Whether to reset the count to 0 after reading it.
+///
+/// # Safety
+/// * `client_ptr` must not be `null` and must be obtained from [`create_client`], and not yet closed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_pubsub_dropped_count(client_ptr: *const c_void, reset: bool) -> u64 {
+    use std::sync::atomic::Ordering;
+
+    assert!(!client_ptr.is_null());
+    let client = unsafe { &*(client_ptr as *const Client) };
+    let dropped = &client.pubsub_counters.dropped;
+    if reset {
+        dropped.swap(0, Ordering::Relaxed)
+    } else {
+        dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Processes a push notification message and calls the provided callback function.
+///
+/// This function extracts the message data from the PushInfo and invokes the C# callback
+/// with the appropriate parameters using scoped lifetime management to prevent memory leaks.
+///
+/// # Parameters
+/// - `push_msg`: The push notification message to process.
+/// - `pubsub_callback`: The callback function to invoke with the processed notification, unless the subscription it
+///   belongs to has its own callback in `subscription_callbacks`.
+/// - `subscription_callbacks`: The callbacks of subscriptions made with [`subscribe_with_callback`]. The callback of a
+///   subscription is removed once its unsubscribe confirmation was dispatched.
+/// - `pubsub_counters`: The client's counters of delivered and dropped messages, see [`PubSubMessage::sequence`]. The
+///   sequence numbers of `pubsub_callback` are those of [`DEFAULT_SEQUENCE_KEY`], and a subscription callback has its own.
+///
+/// # Safety
+/// This function is unsafe because it:
+/// - Calls an FFI function (`pubsub_callback`) that may have undefined behavior
+/// - Assumes push_msg.data contains valid BulkString values
+///
+/// The caller must ensure:
+/// - `pubsub_callback` is a valid function pointer to a properly implemented callback
+/// - The callback copies data synchronously before returning
+///
+/// # Memory Safety
+/// This implementation uses scoped lifetime management instead of `std::mem::forget()`.
+/// `Vec<u8>` instances are kept alive during callback execution and automatically cleaned up
+/// when the function exits, preventing memory leaks.
+unsafe fn process_push_notification(
+    push_msg: redis::PushInfo,
+    pubsub_callback: PubSubCallback,
+    subscription_callbacks: &SubscriptionCallbacks,
+    pubsub_counters: &PubSubCounters,
+) {
+    let subscription_callback = |subscription: &(redis::PubSubSubscriptionKind, Vec<u8>)| {
+        subscription_callbacks
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(subscription)
+            .copied()
+    };
+    let Some(PubSubMessage {
+        kind,
+        pattern,
+        channel,
+        message,
+        sequence,
+    }) = parse_push_notification(push_msg, pubsub_counters, |subscription| {
+        subscription_callback(subscription)
+            .map_or(DEFAULT_SEQUENCE_KEY, |callback| callback as usize)
+    })
+    else {
+        return;
+    };
+
+    let subscription = subscription_of(kind, pattern.as_deref(), &channel);
+    let subscribed_callback = subscription.as_ref().and_then(subscription_callback);
+    let pubsub_callback = subscribed_callback.unwrap_or(pubsub_callback);
+
+    // Prepare pointers while keeping strings alive
+    let pattern_ptr = pattern
+        .as_ref()
+        .map(|p| p.as_ptr())
+        .unwrap_or(std::ptr::null());
+    let pattern_len = pattern.as_ref().map(|p| p.len() as u64).unwrap_or(0);
+    let channel_ptr = channel.as_ptr();
+    let channel_len = channel.len() as u64;
+    let message_ptr = message.as_ptr();
+    let message_len = message.len() as u64;
+
+    // Call callback while strings are still alive
+    unsafe {
+        pubsub_callback(
+            kind,
+            message_ptr,
+            message_len,
+            channel_ptr,
+            channel_len,
+            pattern_ptr,
+            pattern_len,
+            sequence,
+        );
+    }
+
+    if let (
+        Some(subscription),
+        Some(callback),
+        PushKind::Unsubscribe | PushKind::PUnsubscribe | PushKind::SUnsubscribe,
+    ) = (subscription, subscribed_callback, kind)
+    {
+        let mut callbacks = subscription_callbacks
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        callbacks.remove(&subscription);
+        if !callbacks
+            .values()
+            .any(|&other| other as usize == callback as usize)
+        {
+            pubsub_counters.forget_sequence(callback as usize);
+        }
+    }
+
+    // Vec<u8> instances are automatically cleaned up here
+    // No memory leak, no use-after-free
+}
+
+/// The subscription a PubSub message or (un)subscription confirmation belongs to: its kind and its channel, or its
+/// pattern for pattern-based kinds. `None` for other push notifications.
+fn subscription_of(
+    kind: PushKind,
+    pattern: Option<&[u8]>,
+    channel: &[u8],
+) -> Option<(redis::PubSubSubscriptionKind, Vec<u8>)> {
+    use redis::PubSubSubscriptionKind;
+
+    match kind {
+        PushKind::Message | PushKind::Subscribe | PushKind::Unsubscribe => {
+            Some((PubSubSubscriptionKind::Exact, channel.to_vec()))
+        }
+        PushKind::PMessage | PushKind::PSubscribe | PushKind::PUnsubscribe => Some((
+            PubSubSubscriptionKind::Pattern,
+            pattern.unwrap_or(channel).to_vec(),
+        )),
+        PushKind::SMessage | PushKind::SSubscribe | PushKind::SUnsubscribe => {
+            Some((PubSubSubscriptionKind::Sharded, channel.to_vec()))
+        }
+        PushKind::Disconnection | PushKind::Invalidate | PushKind::Other => None,
+    }
+}
+
+/// Register `pubsub_callback` for every channel or pattern of a `SUBSCRIBE`, `PSUBSCRIBE` or `SSUBSCRIBE` command, see
+/// [`subscribe_with_callback`].
+///
+/// # Safety
+/// * `cmd_ptr` must be able to be safely casted to a valid [`CmdInfo`]. See the safety documentation of [`create_cmd`].
+#[allow(rustdoc::private_intra_doc_links)]
+unsafe fn register_subscription_callback(
+    client: &Client,
+    cmd_ptr: *const CmdInfo,
+    pubsub_callback: Option<PubSubCallback>,
+) -> Result<(), String> {
+    use redis::PubSubSubscriptionKind;
+
+    let has_pubsub_task = client
+        .pubsub_task
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .is_some();
+    if !has_pubsub_task {
+        return Err(
+            "Subscription callbacks require a client created with subscriptions and a PubSub callback".into(),
+        );
+    }
+    let kind = match unsafe { (*cmd_ptr).request_type } {
+        RequestType::Subscribe => PubSubSubscriptionKind::Exact,
+        RequestType::PSubscribe => PubSubSubscriptionKind::Pattern,
+        RequestType::SSubscribe => PubSubSubscriptionKind::Sharded,
+        _ => {
+            return Err(
+                "subscribe_with_callback requires a SUBSCRIBE, PSUBSCRIBE or SSUBSCRIBE command"
+                    .into(),
+            );
+        }
+    };
+    let cmd = unsafe { client.create_cmd(cmd_ptr) }?;
+
+    let mut callbacks = client
+        .subscription_callbacks
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    // The first argument is the command name.
+    for channel in extract_cmd_args(&cmd).into_iter().skip(1) {
+        let subscription = (kind.clone(), channel);
+        match pubsub_callback {
+            Some(callback) => callbacks.insert(subscription, callback),
+            None => callbacks.remove(&subscription),
+        };
+    }
+    Ok(())
+}
+
+/// Subscribe to channels, patterns or sharded channels, dispatching their messages to `pubsub_callback` instead of the
+/// PubSub callback passed to [`create_client`].
+///
+/// Behaves as [`command`] with a `SUBSCRIBE`, `PSUBSCRIBE` or `SSUBSCRIBE` command, which must be the request type of
+/// `cmd_ptr`. The callback is registered before the command is sent, so that no message is missed, and is used for the
+/// subscription confirmations as well. It is removed once the server confirmed the matching unsubscription, after
+/// which messages are dispatched to the global callback again. Subscribing again to the same channel replaces its
+/// callback. A `null` callback removes it. Each callback has its own sequence numbers, shared by the subscriptions it is
+/// registered for, see [`PubSubMessage::sequence`].
+///
+/// Fails if the client was not created with subscriptions and a PubSub callback, since messages are only dispatched to
+/// callbacks then.
+///
+/// # Safety
+/// * See the safety documentation of [`command`].
+/// * `pubsub_callback` could be `null`, but if it is not `null`, it must be a valid function pointer.
+///   See the safety documentation of [`PubSubCallback`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn subscribe_with_callback(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    cmd_ptr: *const CmdInfo,
+    pubsub_callback: Option<PubSubCallback>,
+) {
+    assert!(!client_ptr.is_null());
+    let client = unsafe { &*(client_ptr as *const Client) };
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: client.core.failure_callback,
+        callback_index,
+    };
+
+    if let Err(err) = unsafe { register_subscription_callback(client, cmd_ptr, pubsub_callback) } {
+        panic_guard.panicked = false;
+        unsafe {
+            report_error(
+                client.core.failure_callback,
+                callback_index,
+                err,
+                RequestErrorType::Unspecified,
+            );
+        }
+        return;
+    }
+    panic_guard.panicked = false;
+
+    unsafe {
+        execute_command(
+            client_ptr,
+            callback_index,
+            cmd_ptr,
+            std::ptr::null(),
+            None,
+            None,
+            None,
+        )
+    };
+}
+
+/// Closes the given client, deallocating it from the heap.
+/// This function should only be called once per pointer created by [`create_client`].
+/// After calling this function the `client_ptr` is not in a valid state.
+///
+/// Implements graceful shutdown coordination for PubSub tasks with timeout.
+/// In-flight commands are not waited for, use [`close_client_with_timeout`] for that.
+///
+/// # Safety
+///
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be able to be safely casted to a valid [`Arc<Client>`] via [`Arc::from_raw`]. See the safety documentation of [`Arc::from_raw`].
+#[unsafe(no_mangle)]
+pub extern "C" fn close_client(client_ptr: *const c_void) {
+    close_client_internal(client_ptr, None);
+}
+
+/// Closes the given client like [`close_client`], but first waits up to `inflight_timeout_ms` for in-flight commands to complete.
+///
+/// Once this returns, no callback is called for commands which completed within the timeout, so the caller may release
+/// the callbacks' resources. Commands still in flight after the timeout are logged and may still call their callbacks.
+///
+/// # Safety
+///
+/// * See the safety documentation of [`close_client`].
+#[unsafe(no_mangle)]
+pub extern "C" fn close_client_with_timeout(client_ptr: *const c_void, inflight_timeout_ms: u32) {
+    close_client_internal(
+        client_ptr,
+        Some(std::time::Duration::from_millis(inflight_timeout_ms.into())),
+    );
+}
+
+fn close_client_internal(client_ptr: *const c_void, inflight_timeout: Option<std::time::Duration>) {
+    assert!(!client_ptr.is_null());
+
+    // Get a reference to the client to access shutdown coordination
+    let client = unsafe { &*(client_ptr as *const Client) };
+
+    // Send auto-pipelined commands rather than dropping them, see `run_auto_pipeline`.
+    flush_auto_pipeline_queue(client, AutoPipelineMessage::Close);
+
+    // Take ownership of shutdown sender and signal graceful shutdown
+    if let Ok(mut guard) = client.pubsub_shutdown.lock()
+        && let Some(shutdown_tx) = guard.take()
     {
         logger_core::log(
             logger_core::Level::Debug,
@@ -578,12 +1536,68 @@ pub extern "C" fn close_client(client_ptr: *const c_void) {
         }
     }
 
+    // Wait for in-flight commands, so that they don't call back after the caller considers the client closed.
+    if let Some(timeout) = inflight_timeout {
+        let result = client
+            .runtime
+            .block_on(tokio::time::timeout(timeout, client.inflight.wait_idle()));
+        if result.is_err() {
+            logger_core::log(
+                logger_core::Level::Warn,
+                "close_client",
+                format!(
+                    "In-flight commands did not complete within timeout ({:?})",
+                    timeout
+                ),
+            );
+        }
+    }
+
     // This will bring the strong count down to 0 once all client requests are done.
     unsafe { Arc::decrement_strong_count(client_ptr as *const Client) };
 }
 
+/// Build a command without sending it, for debugging argument marshaling.
+///
+/// Returns an array of the arguments that would be sent, starting with the command name, e.g. `["SET", "key", "value"]`.
+/// Nothing is redacted, and neither compression nor arity validation is applied, since there is no client.
+///
+/// # Returns
+/// * A pointer to the array, or to an error value if the command could not be built. It must be freed with [`free_response`].
+///
+/// # Safety
+/// * `cmd_ptr` must not be `null`.
+/// * `cmd_ptr` must be able to be safely casted to a valid [`CmdInfo`]. See the safety documentation of [`create_cmd`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn explain_command(cmd_ptr: *const CmdInfo) -> *mut ResponseValue {
+    assert!(!cmd_ptr.is_null());
+    let value = match unsafe { create_cmd(cmd_ptr, None, None, false) } {
+        Ok(cmd) => redis::Value::Array(
+            extract_cmd_args(&cmd)
+                .into_iter()
+                .map(|arg| redis::Value::BulkString(arg.into()))
+                .collect(),
+        ),
+        Err(err) => redis::Value::ServerError(redis::ServerError::ExtensionError {
+            code: "InvalidCommand".to_string(),
+            detail: Some(err),
+        }),
+    };
+    match ResponseValue::from_value(value) {
+        Ok(response) => Box::into_raw(Box::new(response)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
 /// Execute a command.
 ///
+/// # Arguments
+/// * `has_timeout` - Whether `timeout_ms` is set. If `false`, the command's operation class timeout applies.
+/// * `timeout_ms` - The timeout of this command in milliseconds, replacing its operation class timeout and glide-core's
+///   request timeout, either shorter or longer, e.g. for a long `BLPOP`, see [`send_command_with_timeout`]. Commands with
+///   a timeout bypass auto-pipelining, so that the timeout covers only the command's own execution.
+///
 /// # Safety
 /// * `client_ptr` must not be `null`.
 /// * `client_ptr` must be able to be safely casted to a valid [`Arc<Client>`] via [`Arc::from_raw`]. See the safety documentation of [`Arc::from_raw`].
@@ -599,109 +1613,202 @@ pub unsafe extern "C-unwind" fn command(
     callback_index: usize,
     cmd_ptr: *const CmdInfo,
     route_info: *const RouteInfo,
+    has_timeout: bool,
+    timeout_ms: u32,
 ) {
-    let client = unsafe {
-        // we increment the strong count to ensure that the client is not dropped just because we turned it into an Arc.
-        Arc::increment_strong_count(client_ptr);
-        Arc::from_raw(client_ptr as *mut Client)
+    unsafe {
+        execute_command(
+            client_ptr,
+            callback_index,
+            cmd_ptr,
+            route_info,
+            None,
+            has_timeout.then_some(timeout_ms),
+            None,
+        )
     };
-    let core = client.core.clone();
+}
 
-    let mut panic_guard = PanicGuard {
-        panicked: true,
-        failure_callback: core.failure_callback,
-        callback_index,
+/// Execute a command within its own OpenTelemetry span.
+///
+/// Behaves as [`command`] without a timeout, but when OpenTelemetry is initialized, a span named after the command is created and
+/// ended once the command completes, fails or its task panics. Failures are recorded as events on the span.
+/// Traced commands bypass auto-pipelining, so that the span covers only the command's own execution.
+///
+/// # Returns
+/// * The id of the created span, for correlation, or null if OpenTelemetry is not initialized.
+///   A non-null pointer must be freed with [`free_string`].
+///
+/// # Safety
+/// * See the safety documentation of [`command`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn command_traced(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    cmd_ptr: *const CmdInfo,
+    route_info: *const RouteInfo,
+) -> *mut c_char {
+    let span = if GlideOpenTelemetry::is_initialized() {
+        let request_type = unsafe { (*cmd_ptr).request_type };
+        get_command_name(request_type as u32)
+            .map(|command_name| CommandSpan(GlideOpenTelemetry::new_span(&command_name)))
+    } else {
+        None
     };
+    let span_id = span
+        .as_ref()
+        .and_then(|span| CString::new(span.0.id()).ok())
+        .map_or(std::ptr::null_mut(), CString::into_raw);
 
-    let mut cmd = match unsafe { create_cmd(cmd_ptr, core.client.compression_manager().as_ref()) } {
-        Ok(cmd) => cmd,
-        Err(err) => {
-            panic_guard.panicked = false;
-            unsafe {
-                report_error(
-                    core.failure_callback,
-                    callback_index,
-                    err,
-                    RequestErrorType::Unspecified,
-                );
-            }
-            return;
-        }
+    unsafe {
+        execute_command(
+            client_ptr,
+            callback_index,
+            cmd_ptr,
+            route_info,
+            span,
+            None,
+            None,
+        )
     };
+    span_id
+}
 
-    let route = match unsafe { create_route(route_info, Some(&cmd)) } {
-        Ok(route) => route,
-        Err(err) => {
-            panic_guard.panicked = false;
-            unsafe {
-                report_error(
-                    core.failure_callback,
-                    callback_index,
-                    err,
-                    RequestErrorType::Unspecified,
-                );
-            }
-            return;
-        }
+/// Execute a command which must complete before an absolute deadline.
+///
+/// Behaves as [`command`] without a timeout, but the command fails with a timeout error once `deadline_ms` has passed, or right away if it
+/// already has. The remaining time replaces the command's timeout if shorter, so a deadline further away than the timeout
+/// can't extend it. Commands with a deadline bypass auto-pipelining, so that the deadline covers only the command's own
+/// execution.
+///
+/// # Arguments
+/// * `deadline_ms` - The deadline in milliseconds since the Unix epoch.
+///
+/// # Safety
+/// * See the safety documentation of [`command`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn command_with_deadline(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    cmd_ptr: *const CmdInfo,
+    route_info: *const RouteInfo,
+    deadline_ms: u64,
+) {
+    let deadline = std::time::UNIX_EPOCH + std::time::Duration::from_millis(deadline_ms);
+    unsafe {
+        execute_command(
+            client_ptr,
+            callback_index,
+            cmd_ptr,
+            route_info,
+            None,
+            None,
+            Some(deadline),
+        )
     };
+}
 
-    let request_type = unsafe { (*cmd_ptr).request_type };
+/// Execute a command replying with a bulk string, and copy the reply into a buffer provided by the caller.
+///
+/// Behaves as [`command`], but a bulk string reply is copied into `buffer` instead of being reported as a string, which
+/// lets the caller reuse a buffer for frequent small reads rather than copying and freeing every reply. The reply is
+/// reported as an integer holding its length in bytes. If the reply is longer than `capacity`, nothing is copied and the
+/// reported length is the size the buffer needs, so the caller can retry with a larger buffer. A `nil` reply is
+/// reported as is, and any other reply fails the command. The command bypasses auto-pipelining and is not timed by
+/// [`set_command_timing_callback`].
+///
+/// # Arguments
+/// * `buffer` - The buffer to copy the reply into.
+/// * `capacity` - The size of `buffer` in bytes.
+///
+/// # Safety
+/// * See the safety documentation of [`command`].
+/// * `buffer` must point to `capacity` writable bytes, which must remain valid and not be accessed by the caller until
+///   the command's success or failure callback is called.
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn command_into_buffer(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    cmd_ptr: *const CmdInfo,
+    route_info: *const RouteInfo,
+    buffer: *mut u8,
+    capacity: usize,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
 
-    // Resolve the actual command type for CustomCommand (needed for decompression)
-    let resolved_request_type = if matches!(request_type, RequestType::CustomCommand) {
-        resolve_custom_command_type(&extract_cmd_args(&cmd))
-    } else {
-        request_type
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let command = unsafe { client.create_cmd(cmd_ptr) }.and_then(|cmd| {
+        let route = unsafe { create_route(route_info, Some(&cmd)) }?;
+        Ok((cmd, route))
+    });
+    let (mut cmd, route) = match command {
+        Ok(command) => command,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            panic_guard.panicked = false;
+            return;
+        }
     };
 
-    client.runtime.spawn(async move {
+    let request_type = unsafe { decompression_type(cmd_ptr, &cmd) };
+    let timeout = Some(
+        client
+            .request_timeouts
+            .for_command(unsafe { (*cmd_ptr).operation_class }, &cmd),
+    );
+    // A raw pointer is not `Send`, the caller keeps the buffer valid until the command completes.
+    let buffer = buffer as usize;
+
+    client.spawn_tracked(async move {
         let mut panic_guard = PanicGuard {
             panicked: true,
             failure_callback: core.failure_callback,
             callback_index,
         };
 
-        let result = core.client.clone().send_command(&mut cmd, route).await;
-        match result {
-            Ok(value) => {
-                // Decompress response if compression is enabled
-                let original = value.clone();
-                let value = glide_core::compression::process_response_for_decompression(
-                    value,
-                    resolved_request_type,
-                    core.client.compression_manager().as_deref(),
-                )
-                .unwrap_or_else(|e| {
-                    logger_core::log_warn(
-                        "response_decompression",
-                        format!("Failed to decompress response: {}", e),
-                    );
-                    original
-                });
-                match ResponseValue::from_value(value) {
-                    Ok(response) => {
-                        let ptr = Box::into_raw(Box::new(response));
-                        unsafe { (core.success_callback)(callback_index, ptr) };
+        let result = send_command_retrying_read_only(&core, &mut cmd, route, timeout).await;
+        let result =
+            result.and_then(
+                |value| match decompress_response(&core, value, request_type) {
+                    redis::Value::BulkString(bytes) => {
+                        if bytes.len() <= capacity {
+                            unsafe {
+                                std::ptr::copy_nonoverlapping(
+                                    bytes.as_ptr(),
+                                    buffer as *mut u8,
+                                    bytes.len(),
+                                )
+                            };
+                        }
+                        Ok(redis::Value::Int(bytes.len() as i64))
                     }
-                    Err(err) => unsafe {
-                        report_error(
-                            core.failure_callback,
-                            callback_index,
-                            err,
-                            RequestErrorType::Unspecified,
-                        );
-                    },
-                }
-            }
-            Err(err) => unsafe {
-                report_error(
-                    core.failure_callback,
-                    callback_index,
-                    error_message(&err),
-                    error_type(&err),
-                );
-            },
-        };
+                    redis::Value::Nil => Ok(redis::Value::Nil),
+                    _ => Err(redis::RedisError::from((
+                        redis::ErrorKind::TypeError,
+                        "Reply is not a bulk string",
+                    ))),
+                },
+            );
+        unsafe { report_result(&core, callback_index, result) };
         panic_guard.panicked = false;
         drop(panic_guard);
     });
@@ -710,23 +1817,35 @@ pub unsafe extern "C-unwind" fn command(
     drop(panic_guard);
 }
 
-/// Execute a batch.
+/// The span of a command executed by [`command_traced`], ended once dropped.
+///
+/// The span is moved into the command's task, so it is ended even if the task panics.
+struct CommandSpan(GlideSpan);
+
+impl CommandSpan {
+    fn record_error(&self, message: &str) {
+        self.0.add_event(&format!("error: {message}"));
+    }
+}
+
+impl Drop for CommandSpan {
+    fn drop(&mut self) {
+        self.0.end();
+    }
+}
+
+/// Shared implementation of [`command`], [`command_traced`] and [`command_with_deadline`].
 ///
 /// # Safety
-/// * `client_ptr` must not be `null`.
-/// * `client_ptr` must be able to be safely casted to a valid [`Arc<Client>`] via [`Arc::from_raw`]. See the safety documentation of [`Arc::from_raw`].
-/// * This function should only be called should with a pointer created by [`create_client`], before [`close_client`] was called with the pointer.
-/// * `batch_ptr` must not be `null`.
-/// * `batch_ptr` must be able to be safely casted to a valid [`BatchInfo`]. See the safety documentation of [`create_pipeline`].
-/// * `options_ptr` could be `null`, but if it is not `null`, it must be a valid [`BatchOptionsInfo`] pointer. See the safety documentation of [`get_pipeline_options`].
-#[allow(rustdoc::private_intra_doc_links)]
-#[unsafe(no_mangle)]
-pub unsafe extern "C-unwind" fn batch(
+/// * See the safety documentation of [`command`].
+unsafe fn execute_command(
     client_ptr: *const c_void,
     callback_index: usize,
-    batch_ptr: *const BatchInfo,
-    raise_on_error: bool,
-    options_ptr: *const BatchOptionsInfo,
+    cmd_ptr: *const CmdInfo,
+    route_info: *const RouteInfo,
+    span: Option<CommandSpan>,
+    timeout_ms: Option<u32>,
+    deadline: Option<std::time::SystemTime>,
 ) {
     let client = unsafe {
         // we increment the strong count to ensure that the client is not dropped just because we turned it into an Arc.
@@ -741,115 +1860,127 @@ pub unsafe extern "C-unwind" fn batch(
         callback_index,
     };
 
-    let pipeline =
-        match unsafe { create_pipeline(batch_ptr, core.client.compression_manager().as_ref()) } {
-            Ok(pipeline) => pipeline,
-            Err(err) => {
-                panic_guard.panicked = false;
-                unsafe {
-                    report_error(
-                        core.failure_callback,
-                        callback_index,
-                        err,
-                        RequestErrorType::Unspecified,
-                    );
-                }
-                return;
+    let mut cmd = match unsafe { client.create_cmd(cmd_ptr) } {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            if let Some(span) = &span {
+                span.record_error(&err);
             }
-        };
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return;
+        }
+    };
 
-    let (routing, timeout, pipeline_retry_strategy) =
-        match unsafe { get_pipeline_options(options_ptr) } {
-            Ok(opts) => opts,
-            Err(err) => {
+    let route = match unsafe { create_route(route_info, Some(&cmd)) } {
+        Ok(route) => route,
+        Err(err) => {
+            if let Some(span) = &span {
+                span.record_error(&err);
+            }
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return;
+        }
+    };
+
+    let request_type = unsafe { (*cmd_ptr).request_type };
+    let decode_numbers = unsafe { (*cmd_ptr).decode_numbers };
+    let timeout = Some(timeout_ms.unwrap_or_else(|| {
+        client
+            .request_timeouts
+            .for_command(unsafe { (*cmd_ptr).operation_class }, &cmd)
+    }));
+    let timeout =
+        match deadline.map(|deadline| deadline.duration_since(std::time::SystemTime::now())) {
+            None => timeout,
+            Some(Ok(remaining)) if !remaining.is_zero() => {
+                let remaining_ms = u32::try_from(remaining.as_millis().max(1)).unwrap_or(u32::MAX);
+                Some(timeout.map_or(remaining_ms, |timeout| timeout.min(remaining_ms)))
+            }
+            Some(_) => {
                 panic_guard.panicked = false;
                 unsafe {
                     report_error(
                         core.failure_callback,
                         callback_index,
-                        err,
-                        RequestErrorType::Unspecified,
+                        "Deadline already passed".into(),
+                        RequestErrorType::Timeout,
                     );
                 }
                 return;
             }
         };
 
-    // Clone compression manager for use in async block
-    let compression_manager = core.client.compression_manager();
+    let resolved_request_type = unsafe { decompression_type(cmd_ptr, &cmd) };
 
-    client.runtime.spawn(async move {
-        let mut panic_guard = PanicGuard {
-            panicked: true,
-            failure_callback: core.failure_callback,
+    // Unrouted commands are batched when auto-pipelining is enabled, see `run_auto_pipeline`.
+    // Traced commands and commands with a timeout or deadline are sent on their own, so that their span, timeout or
+    // deadline covers only their own execution.
+    if let (None, Some(queue), None, None, None) =
+        (&route, &client.auto_pipeline, &span, &timeout_ms, &deadline)
+    {
+        let queued = QueuedCommand {
+            cmd,
             callback_index,
+            request_type: resolved_request_type,
+            decode_numbers,
+            _inflight: client.inflight.track(),
         };
-
-        let result = if pipeline.is_atomic() {
-            core.client
-                .clone()
-                .send_transaction(&pipeline, routing, timeout, raise_on_error)
-                .await
-        } else {
-            core.client
-                .clone()
-                .send_pipeline(
-                    &pipeline,
-                    routing,
-                    raise_on_error,
-                    timeout,
-                    pipeline_retry_strategy,
-                )
-                .await
-        };
-
-        // Process batch response for decompression if compression is enabled
-        match result {
-            Ok(value) => {
-                let final_value = if let Some(ref manager) = compression_manager {
-                    match glide_core::compression::decompress_batch_response(
-                        value.clone(),
-                        manager.as_ref(),
-                    ) {
-                        Ok(decompressed) => decompressed,
-                        Err(e) => {
-                            logger_core::log_warn(
-                                "batch_decompression",
-                                format!(
-                                    "Failed to decompress batch response: {}, returning original",
-                                    e
-                                ),
-                            );
-                            value
-                        }
-                    }
-                } else {
-                    value
-                };
-                match ResponseValue::from_value(final_value) {
-                    Ok(response) => {
-                        let ptr = Box::into_raw(Box::new(response));
-                        unsafe { (core.success_callback)(callback_index, ptr) };
-                    }
-                    Err(err) => unsafe {
-                        report_error(
-                            core.failure_callback,
-                            callback_index,
-                            err,
-                            RequestErrorType::Unspecified,
-                        );
-                    },
-                }
-            }
-            Err(err) => unsafe {
+        if queue.send(AutoPipelineMessage::Command(queued)).is_err() {
+            unsafe {
                 report_error(
                     core.failure_callback,
                     callback_index,
-                    error_message(&err),
-                    error_type(&err),
+                    "Auto-pipeline flush task is no longer running".into(),
+                    RequestErrorType::Disconnect,
                 );
-            },
+            }
+        }
+        panic_guard.panicked = false;
+        return;
+    }
+
+    let inflight = client.inflight.clone();
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
         };
+
+        if let Some(span) = &span {
+            cmd.set_span(Some(span.0.clone()));
+        }
+        let _tracked = inflight.track_command(callback_index, &cmd);
+        let result = run_command(
+            &core,
+            &mut cmd,
+            route,
+            timeout,
+            request_type,
+            decode_numbers,
+            callback_index,
+        )
+        .await;
+        if let (Some(span), Err(err)) = (&span, &result) {
+            span.record_error(&error_message(err));
+        }
+        unsafe { report_command_result(&core, callback_index, result, resolved_request_type) };
         panic_guard.panicked = false;
         drop(panic_guard);
     });
@@ -858,253 +1989,4447 @@ pub unsafe extern "C-unwind" fn batch(
     drop(panic_guard);
 }
 
-/// Free the memory allocated for a [`ResponseValue`] and nested structure.
+/// Send a command that is not auto-pipelined, shared by [`execute_command`] and [`command_async`].
+///
+/// Injects faults, retries `READONLY` errors and decodes numeric strings if requested. The command is timed, and the
+/// duration is passed to the timing callback with `timing_index`.
+async fn run_command(
+    core: &CommandExecutionCore,
+    cmd: &mut redis::Cmd,
+    route: Option<RoutingInfo>,
+    timeout: Option<u32>,
+    request_type: RequestType,
+    decode_numbers: bool,
+    timing_index: usize,
+) -> redis::RedisResult<redis::Value> {
+    let timing = core.start_timing();
+    #[cfg(feature = "fault-injection")]
+    let result = match inject_fault(core, request_type).await {
+        Some(err) => Err(err),
+        None => send_command_retrying_read_only(core, cmd, route, timeout).await,
+    };
+    #[cfg(not(feature = "fault-injection"))]
+    let result = {
+        let _ = request_type;
+        send_command_retrying_read_only(core, cmd, route, timeout).await
+    };
+    unsafe { report_timing(timing, timing_index) };
+    if decode_numbers {
+        result.map(decode_numeric_strings)
+    } else {
+        result
+    }
+}
+
+/// Enable or disable per-command timing for a client.
+///
+/// While a callback is set, the commands executed with [`command`], [`command_traced`] and [`command_async`] are timed from sending the
+/// command until its reply or error was received, and the duration is passed to `callback` right before the result is
+/// reported to the success or failure callback. An auto-pipelined command is passed the duration of the window it was
+/// sent with. Timing adds a clock read per command, so it is disabled unless a callback is set. Pass `null` to disable it.
 ///
 /// # Safety
-/// * `ptr` must not be `null`.
-/// * `ptr` must be able to be safely casted to a valid [`Box<ResponseValue>`] via [`Box::from_raw`]. See the safety documentation of [`Box::from_raw`].
+/// * `client_ptr` must not be `null` and must be obtained from [`create_client`], and not yet closed.
+/// * `callback` must be `null` or a valid function pointer, which remains valid until it is replaced or the client is closed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn set_command_timing_callback(
+    client_ptr: *const c_void,
+    callback: Option<CommandTimingCallback>,
+) {
+    assert!(!client_ptr.is_null());
+    let client = unsafe { &*(client_ptr as *const Client) };
+    *client
+        .core
+        .timing_callback
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = callback;
+}
+
+/// Get the settings a client is using, for diagnostics.
+///
+/// The settings are reported as a map, with the defaults glide-core applied to the settings which were not configured,
+/// e.g. `request_timeout_ms`, `protocol`, `tls_mode` and `read_from`. Secrets are not reported, see [`effective_config`].
+/// Returns `null` on failure. The returned pointer must be freed with [`free_response`].
+///
+/// # Safety
+/// * `client_ptr` must not be `null` and must be obtained from [`create_client`], and not yet closed.
 #[allow(rustdoc::private_intra_doc_links)]
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn free_response(ptr: *mut ResponseValue) {
-    unsafe {
-        // Take ownership of ptr in the Box.
-        let boxed = Box::from_raw(ptr);
-        boxed.free_memory(); // This releases the contents of ResponseValue, but not the memory used by ResponseValue itself. This must be done explicitly.
-        // boxed is dropped here, releasing the memory used directly by ResponseValue itself.
+pub unsafe extern "C" fn get_effective_config(client_ptr: *const c_void) -> *mut ResponseValue {
+    assert!(!client_ptr.is_null());
+    let client = unsafe { &*(client_ptr as *const Client) };
+    match ResponseValue::from_value(client.effective_config.clone()) {
+        Ok(response) => Box::into_raw(Box::new(response)),
+        Err(_) => std::ptr::null_mut(),
     }
 }
 
-/// Frees memory allocated for a C string.
+/// List the commands of a client which have been in flight for at least `threshold_ms` milliseconds.
+///
+/// Only commands executed with [`command`] and [`command_traced`] which are sent on their own are tracked, from the time
+/// they are sent until their result is received. This helps finding blocking commands, such as a `BLPOP` without a timeout,
+/// that were forgotten. Returns an array with an entry per command, from the longest in flight, where every entry is an
+/// array of the callback index, the command name and the elapsed time in milliseconds, or `null` on failure.
+/// The returned pointer must be freed with [`free_response`].
 ///
-/// # Parameters
-/// * `str_ptr`: Pointer to the C string to free.
 /// # Safety
-/// * `str_ptr` must be a valid pointer to a C string allocated by Rust (e.g., via `CString::into_raw`), or null.
+/// * `client_ptr` must not be `null` and must be obtained from [`create_client`], and not yet closed.
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn free_string(str_ptr: *mut c_char) {
-    if !str_ptr.is_null() {
-        unsafe {
-            let _ = CString::from_raw(str_ptr);
-        };
+pub unsafe extern "C" fn list_blocking_commands(
+    client_ptr: *const c_void,
+    threshold_ms: u64,
+) -> *mut ResponseValue {
+    assert!(!client_ptr.is_null());
+    let client = unsafe { &*(client_ptr as *const Client) };
+    let threshold = std::time::Duration::from_millis(threshold_ms);
+    let mut commands: Vec<(usize, Vec<u8>, std::time::Duration)> = client
+        .inflight
+        .lock_commands()
+        .iter()
+        .map(|(callback_index, command)| {
+            (
+                *callback_index,
+                command.name.clone(),
+                command.started.elapsed(),
+            )
+        })
+        .filter(|(_, _, elapsed)| *elapsed >= threshold)
+        .collect();
+    commands.sort_by(|a, b| b.2.cmp(&a.2));
+
+    let value = redis::Value::Array(
+        commands
+            .into_iter()
+            .map(|(callback_index, name, elapsed)| {
+                redis::Value::Array(vec![
+                    redis::Value::Int(callback_index as i64),
+                    redis::Value::BulkString(name),
+                    redis::Value::Int(i64::try_from(elapsed.as_millis()).unwrap_or(i64::MAX)),
+                ])
+            })
+            .collect(),
+    );
+    match ResponseValue::from_value(value) {
+        Ok(response) => Box::into_raw(Box::new(response)),
+        Err(_) => std::ptr::null_mut(),
     }
 }
 
-impl From<logger_core::Level> for Level {
-    fn from(level: logger_core::Level) -> Self {
-        match level {
-            logger_core::Level::Error => Level::Error,
+/// The result slots of the commands executed with [`command_async`], keyed by handle.
+struct CommandHandles {
+    next_handle: std::sync::atomic::AtomicU64,
+    /// `None` while the command is pending.
+    slots: std::sync::Mutex<std::collections::HashMap<u64, Option<CommandOutcome>>>,
+}
+
+impl Default for CommandHandles {
+    fn default() -> Self {
+        Self {
+            // 0 is never used as a handle.
+            next_handle: std::sync::atomic::AtomicU64::new(1),
+            slots: Default::default(),
+        }
+    }
+}
+
+/// The result of a command executed with [`command_async`].
+enum CommandOutcome {
+    Value(redis::Value),
+    Error(String, RequestErrorType),
+}
+
+impl CommandHandles {
+    fn lock(
+        &self,
+    ) -> std::sync::MutexGuard<'_, std::collections::HashMap<u64, Option<CommandOutcome>>> {
+        self.slots
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Allocate a pending slot.
+    fn allocate(&self) -> u64 {
+        let handle = self
+            .next_handle
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.lock().insert(handle, None);
+        handle
+    }
+
+    /// Store the result of a command, unless its handle was dropped meanwhile.
+    fn complete(&self, handle: u64, outcome: CommandOutcome) {
+        if let Some(slot) = self.lock().get_mut(&handle) {
+            *slot = Some(outcome);
+        }
+    }
+}
+
+/// Execute a command, returning a handle to poll its result with [`poll_command`] instead of calling a callback.
+///
+/// This is an alternative to [`command`] for callers that prefer polling. The command is executed like with
+/// [`command`], including fault injection and `READONLY` retries, except that it is never auto-pipelined. If a timing
+/// callback is set, it is passed the handle instead of a callback index. Errors found before sending the command, e.g. an invalid
+/// route, are reported by polling too.
+///
+/// # Returns
+/// * A handle, never `0`. Its slot is freed once [`poll_command`] returned the result, or by [`drop_command_handle`].
+///
+/// # Safety
+/// * See the safety documentation of [`command`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn command_async(
+    client_ptr: *const c_void,
+    cmd_ptr: *const CmdInfo,
+    route_info: *const RouteInfo,
+) -> u64 {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+    let handles = client.command_handles.clone();
+    let handle = handles.allocate();
+
+    let cmd_and_route = unsafe { client.create_cmd(cmd_ptr) }
+        .and_then(|cmd| unsafe { create_route(route_info, Some(&cmd)) }.map(|route| (cmd, route)));
+    let (mut cmd, route) = match cmd_and_route {
+        Ok(cmd_and_route) => cmd_and_route,
+        Err(err) => {
+            handles.complete(
+                handle,
+                CommandOutcome::Error(err, RequestErrorType::Unspecified),
+            );
+            return handle;
+        }
+    };
+
+    let request_type = unsafe { (*cmd_ptr).request_type };
+    let decode_numbers = unsafe { (*cmd_ptr).decode_numbers };
+    let timeout = Some(
+        client
+            .request_timeouts
+            .for_command(unsafe { (*cmd_ptr).operation_class }, &cmd),
+    );
+    let resolved_request_type = unsafe { decompression_type(cmd_ptr, &cmd) };
+
+    client.spawn_tracked(async move {
+        let result = tokio::spawn(async move {
+            let timing_index = usize::try_from(handle).unwrap_or(usize::MAX);
+            let result = run_command(
+                &core,
+                &mut cmd,
+                route,
+                timeout,
+                request_type,
+                decode_numbers,
+                timing_index,
+            )
+            .await;
+            result.map(|value| decompress_response(&core, value, resolved_request_type))
+        })
+        .await;
+        let outcome = match result {
+            Ok(Ok(value)) => CommandOutcome::Value(value),
+            Ok(Err(err)) => CommandOutcome::Error(error_message(&err), error_type(&err)),
+            Err(_) => {
+                CALLBACK_PANICS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                CommandOutcome::Error(
+                    "Native function panicked".into(),
+                    RequestErrorType::Unspecified,
+                )
+            }
+        };
+        handles.complete(handle, outcome);
+    });
+
+    handle
+}
+
+/// Check whether a command executed with [`command_async`] completed, without blocking.
+///
+/// # Returns
+/// * [`CommandPollStatus::Pending`] if the command has not completed yet.
+/// * [`CommandPollStatus::Completed`] if it succeeded: `value_out` is set to the result, which must be freed with
+///   [`free_response`], and the handle's slot is freed.
+/// * [`CommandPollStatus::Failed`] if it failed: `error_out` is set to the error message, which must be freed with
+///   [`free_string`], `error_type_out` to the error type, and the handle's slot is freed.
+/// * [`CommandPollStatus::UnknownHandle`] if the handle is unknown, was dropped, or its result was already returned.
+///
+/// # Safety
+/// * `client_ptr` must not be `null` and must be obtained from [`create_client`], and not yet closed.
+/// * `value_out`, `error_out` and `error_type_out` must be valid pointers to write to.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn poll_command(
+    client_ptr: *const c_void,
+    handle: u64,
+    value_out: *mut *mut ResponseValue,
+    error_out: *mut *mut c_char,
+    error_type_out: *mut RequestErrorType,
+) -> CommandPollStatus {
+    assert!(!client_ptr.is_null());
+    let client = unsafe { &*(client_ptr as *const Client) };
+    let outcome = {
+        let mut slots = client.command_handles.lock();
+        match slots.get(&handle) {
+            None => return CommandPollStatus::UnknownHandle,
+            Some(None) => return CommandPollStatus::Pending,
+            Some(Some(_)) => {}
+        }
+        slots.remove(&handle).flatten()
+    };
+
+    let (error, error_type) = match outcome {
+        Some(CommandOutcome::Value(value)) => match client.core.response_value(value) {
+            Ok(response) => {
+                unsafe { *value_out = Box::into_raw(Box::new(response)) };
+                return CommandPollStatus::Completed;
+            }
+            Err(err) => (err, RequestErrorType::Unspecified),
+        },
+        Some(CommandOutcome::Error(error, error_type)) => (error, error_type),
+        None => return CommandPollStatus::UnknownHandle,
+    };
+    let error = CString::new(error).unwrap_or_else(|_| c"Invalid error message".to_owned());
+    unsafe {
+        *error_out = error.into_raw();
+        *error_type_out = error_type;
+    }
+    CommandPollStatus::Failed
+}
+
+/// Free the slot of a command executed with [`command_async`] whose result won't be polled.
+///
+/// If the command is still pending it is not cancelled, but its result is discarded once it completes.
+/// Dropping an unknown handle does nothing.
+///
+/// # Safety
+/// * `client_ptr` must not be `null` and must be obtained from [`create_client`], and not yet closed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn drop_command_handle(client_ptr: *const c_void, handle: u64) {
+    assert!(!client_ptr.is_null());
+    let client = unsafe { &*(client_ptr as *const Client) };
+    client.command_handles.lock().remove(&handle);
+}
+
+/// Execute a command on every node of a multi-node route, tolerating failures of individual nodes.
+///
+/// Unlike [`command`], which fails the whole request if any node fails or times out, this reports a map
+/// of `host:port` to the node's reply. A node that failed or timed out is reported as an error entry in the map,
+/// so the caller gets partial results instead of a single error. Nodes the cluster flags as failed are not sent the
+/// command, and are reported as an error entry as well. The nodes are discovered with `CLUSTER NODES`, so this is only
+/// supported in cluster mode. The replies are decompressed like the reply of [`command`].
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be able to be safely casted to a valid [`Arc<Client>`] via [`Arc::from_raw`]. See the safety documentation of [`Arc::from_raw`].
+/// * This function should only be called should with a pointer created by [`create_client`], before [`close_client`] was called with the pointer.
+/// * Pointers to callbacks stored in [`Client`] should remain valid. See the safety documentation of [`SuccessCallback`] and [`FailureCallback`].
+/// * `cmd_ptr` must not be `null`.
+/// * `cmd_ptr` must be able to be safely casted to a valid [`CmdInfo`]. See the safety documentation of [`create_cmd`].
+/// * `route_info` must not be `null` and must be a valid [`RouteInfo`] pointer for an `AllNodes` or `AllPrimaries` route. See the safety documentation of [`create_route`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn command_per_node(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    cmd_ptr: *const CmdInfo,
+    route_info: *const RouteInfo,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let cmd = match unsafe { client.create_cmd(cmd_ptr) } {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return;
+        }
+    };
+
+    let primaries_only = match unsafe { create_route(route_info, Some(&cmd)) } {
+        Ok(Some(RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllNodes, _)))) => false,
+        Ok(Some(RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllMasters, _)))) => true,
+        Ok(_) => {
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    "Per-node execution requires an AllNodes or AllPrimaries route".into(),
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return;
+        }
+        Err(err) => {
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return;
+        }
+    };
+    let resolved_request_type = unsafe { decompression_type(cmd_ptr, &cmd) };
+
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let result = send_command_per_node(&core, cmd, primaries_only).await;
+        unsafe { report_command_result(&core, callback_index, result, resolved_request_type) };
+        panic_guard.panicked = false;
+    });
+
+    panic_guard.panicked = false;
+}
+
+/// Send `cmd` to every node listed by `CLUSTER NODES` concurrently, collecting a map of `host:port` to reply,
+/// with a server error entry for every node that failed, is flagged as failed, or whose request panicked.
+async fn send_command_per_node(
+    core: &CommandExecutionCore,
+    cmd: redis::Cmd,
+    primaries_only: bool,
+) -> redis::RedisResult<redis::Value> {
+    let node_error = |code: &str, detail: String| {
+        redis::Value::ServerError(redis::ServerError::ExtensionError {
+            code: code.to_string(),
+            detail: Some(detail),
+        })
+    };
+    let nodes: Vec<ClusterNode> = get_cluster_nodes(core)
+        .await?
+        .into_iter()
+        .filter(|node| !primaries_only || node.is_primary())
+        .collect();
+
+    let mut replies: Vec<redis::Value> = vec![redis::Value::Nil; nodes.len()];
+    let mut requests = tokio::task::JoinSet::new();
+    let mut request_indices = std::collections::HashMap::new();
+    for (index, node) in nodes.iter().enumerate() {
+        if node.is_failed() {
+            replies[index] = node_error("ERR", "The node is flagged as failed".to_string());
+            continue;
+        }
+        let mut client = core.client.clone();
+        let mut cmd = cmd.clone();
+        let route = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+            host: node.host.clone(),
+            port: node.port,
+        });
+        let request =
+            requests.spawn(async move { client.send_command(&mut cmd, Some(route)).await });
+        request_indices.insert(request.id(), index);
+    }
+
+    while let Some(joined) = requests.join_next_with_id().await {
+        let (id, reply) = match joined {
+            Ok((id, Ok(reply))) => (id, reply),
+            Ok((id, Err(err))) => (
+                id,
+                node_error(err.code().unwrap_or("ERR"), error_message(&err)),
+            ),
+            Err(err) => (
+                err.id(),
+                node_error("ERR", format!("The request to the node panicked: {err}")),
+            ),
+        };
+        replies[request_indices[&id]] = reply;
+    }
+
+    Ok(redis::Value::Map(
+        nodes
+            .into_iter()
+            .map(|node| (node.host, node.port))
+            .zip(replies)
+            .map(|((host, port), reply)| {
+                (redis::Value::SimpleString(format!("{host}:{port}")), reply)
+            })
+            .collect(),
+    ))
+}
+
+/// Execute the same transaction on every primary, e.g. for maintenance tasks touching each shard.
+///
+/// Like [`command_per_node`], this reports a map of `host:port` to the result of the transaction on that node, and
+/// tolerates failures of individual nodes. A node's entry is the array of replies of the transaction, nil if it was
+/// aborted on that node because a watched key changed, or an error if it failed. The primaries are discovered with
+/// `CLUSTER NODES`, so this is only supported in cluster mode. Every node is sent the whole transaction, so its keys
+/// must map to slots served by each primary, i.e. it should only contain keyless commands.
+///
+/// # Safety
+/// * See the safety documentation of [`batch`]. `batch_ptr` must describe an atomic batch, and the options must not
+///   have a route.
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn transaction_per_primary(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    batch_ptr: *const BatchInfo,
+    raise_on_error: bool,
+    options_ptr: *const BatchOptionsInfo,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let pipeline_and_timeout = unsafe {
+        create_pipeline(
+            batch_ptr,
+            core.client.compression_manager().as_ref(),
+            client.key_prefix.as_deref(),
+            client.validate_command_arity,
+        )
+    }
+    .and_then(|pipeline| {
+        let (routing, timeout, _) = unsafe { get_pipeline_options(options_ptr) }?;
+        if !client.cluster_mode {
+            Err("transaction_per_primary is only supported in cluster mode".to_owned())
+        } else if !pipeline.is_atomic() {
+            Err("transaction_per_primary requires an atomic batch".to_owned())
+        } else if routing.is_some() {
+            Err("transaction_per_primary can't be combined with a route".to_owned())
+        } else {
+            Ok((pipeline, core.batch_timeout(timeout)))
+        }
+    });
+    let (pipeline, timeout) = match pipeline_and_timeout {
+        Ok(pipeline_and_timeout) => pipeline_and_timeout,
+        Err(err) => {
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return;
+        }
+    };
+
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let result = send_transaction_per_primary(&core, pipeline, timeout, raise_on_error).await;
+        unsafe { report_result(&core, callback_index, result) };
+        panic_guard.panicked = false;
+    });
+
+    panic_guard.panicked = false;
+}
+
+/// Send `pipeline` as a transaction to every primary listed by `CLUSTER NODES` concurrently, collecting a map of
+/// `host:port` to result, with a server error entry for every node that failed.
+async fn send_transaction_per_primary(
+    core: &CommandExecutionCore,
+    pipeline: redis::Pipeline,
+    timeout: Option<u32>,
+    raise_on_error: bool,
+) -> redis::RedisResult<redis::Value> {
+    let addresses: Vec<(String, u16)> = get_cluster_nodes(core)
+        .await?
+        .into_iter()
+        .filter(|node| !node.is_failed() && node.is_primary())
+        .map(|node| (node.host, node.port))
+        .collect();
+
+    let pipeline = Arc::new(pipeline);
+    let mut requests = tokio::task::JoinSet::new();
+    for (index, (host, port)) in addresses.iter().cloned().enumerate() {
+        let mut client = core.client.clone();
+        let pipeline = pipeline.clone();
+        requests.spawn(async move {
+            let route = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port });
+            let result = client
+                .send_transaction(&pipeline, Some(route), timeout, raise_on_error)
+                .await;
+            (index, result)
+        });
+    }
+
+    let compression_manager = core.client.compression_manager();
+    let mut results: Vec<redis::Value> = vec![redis::Value::Nil; addresses.len()];
+    while let Some(joined) = requests.join_next().await {
+        let Ok((index, result)) = joined else {
+            continue;
+        };
+        results[index] = match (result, &compression_manager) {
+            (Ok(value), Some(manager)) => {
+                glide_core::compression::decompress_batch_response(value.clone(), manager.as_ref())
+                    .unwrap_or(value)
+            }
+            (Ok(value), None) => value,
+            (Err(err), _) => redis::Value::ServerError(redis::ServerError::ExtensionError {
+                code: err.code().unwrap_or("ERR").to_string(),
+                detail: Some(error_message(&err)),
+            }),
+        };
+    }
+
+    Ok(redis::Value::Map(
+        addresses
+            .into_iter()
+            .zip(results)
+            .map(|((host, port), result)| {
+                (redis::Value::SimpleString(format!("{host}:{port}")), result)
+            })
+            .collect(),
+    ))
+}
+
+/// Send a command, with `timeout_ms` replacing glide-core's request timeout if given, see [`RequestTimeouts`].
+///
+/// The command is sent with `send_command`, keeping glide-core's retries, client-side cache and blocking command
+/// timeouts. A timeout shorter than glide-core's request timeout, which is the longest configured one, cuts the request
+/// off on the client. Blocking commands are sent as they are, as glide-core derives their timeout from their arguments,
+/// see [`is_blocking_cmd`].
+///
+/// glide-core only lets a batch override its request timeout, so a longer timeout, e.g. for a command tagged with
+/// [`OperationClass::Unbounded`](ffi::OperationClass::Unbounded), falls back to sending a batch of this single command,
+/// without retries. glide-core only routes a batch to a single node, so a command explicitly routed to multiple nodes
+/// keeps glide-core's request timeout instead.
+async fn send_command_with_timeout(
+    core: &CommandExecutionCore,
+    cmd: &mut redis::Cmd,
+    route: Option<RoutingInfo>,
+    timeout_ms: Option<u32>,
+) -> redis::RedisResult<redis::Value> {
+    let mut client = core.client.clone();
+    let timeout_ms = timeout_ms.filter(|&timeout_ms| timeout_ms != core.request_timeout_ms);
+    let Some(timeout_ms) = timeout_ms.filter(|_| !is_blocking_cmd(cmd)) else {
+        return client.send_command(cmd, route).await;
+    };
+    if timeout_ms < core.request_timeout_ms {
+        let send = client.send_command(cmd, route);
+        return tokio::time::timeout(std::time::Duration::from_millis(timeout_ms.into()), send)
+            .await
+            .unwrap_or_else(|_| {
+                Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "Request timed out").into())
+            });
+    }
+    if let Some(RoutingInfo::MultiNode(_)) = route {
+        return client.send_command(cmd, route).await;
+    }
+
+    let mut pipeline = redis::Pipeline::with_capacity(1);
+    pipeline.add_command(cmd.clone());
+    let reply = client
+        .send_pipeline(
+            &pipeline,
+            route,
+            true,
+            Some(timeout_ms),
+            glide_core::client::PipelineRetryStrategy::new(false, false),
+        )
+        .await?;
+    let replies = match reply {
+        redis::Value::Array(replies) => <[redis::Value; 1]>::try_from(replies),
+        reply => Err(vec![reply]),
+    };
+    match replies {
+        Ok([redis::Value::ServerError(err)]) => Err(err.into()),
+        Ok([reply]) => Ok(reply),
+        Err(replies) => Err(redis::RedisError::from((
+            redis::ErrorKind::ResponseError,
+            "Unexpected single command batch response",
+            format!("{replies:?}"),
+        ))),
+    }
+}
+
+/// Send a command with [`send_command_with_timeout`], retrying it while it is rejected with a `READONLY` error, if the
+/// client is configured to, see [`read_only_retry_config`].
+///
+/// glide-core does not allow triggering a topology refresh, but updates its slot map when a node redirects a command and
+/// on its periodic topology checks. The retries therefore wait for the configured delay, doubled on every retry, to give it
+/// time to notice the promoted primary. The error of the last attempt is returned once the retries are exhausted.
+async fn send_command_retrying_read_only(
+    core: &CommandExecutionCore,
+    cmd: &mut redis::Cmd,
+    route: Option<RoutingInfo>,
+    timeout_ms: Option<u32>,
+) -> redis::RedisResult<redis::Value> {
+    let mut result = send_command_with_timeout(core, cmd, route.clone(), timeout_ms).await;
+    let Some(retry) = core.read_only_retry else {
+        return result;
+    };
+    let mut delay = std::time::Duration::from_millis(retry.delay_ms.into());
+    for _ in 0..retry.max_retries {
+        if !matches!(&result, Err(err) if err.kind() == redis::ErrorKind::ReadOnly) {
+            break;
+        }
+        tokio::time::sleep(delay).await;
+        delay = delay.saturating_mul(2);
+        result = send_command_with_timeout(core, cmd, route.clone(), timeout_ms).await;
+    }
+    result
+}
+
+/// Get the message of a failed command reported to the failure callback.
+///
+/// A `READONLY` error is reported as sent by the server, e.g. `READONLY You can't write against a read only replica.`,
+/// so the caller can tell it apart from other errors.
+fn command_error_message(err: &redis::RedisError) -> String {
+    match (err.kind(), err.detail()) {
+        (redis::ErrorKind::ReadOnly, Some(detail)) => format!("READONLY {detail}"),
+        _ => error_message(err),
+    }
+}
+
+/// Execute a command on the node at `host:port`, after checking that the node is part of the current topology.
+///
+/// Unlike a [`RouteInfo`] `ByAddress` route, the address is first looked up with `CLUSTER NODES`. If the node was removed
+/// from the topology or is flagged as failed, a clear error is reported instead of sending the command. This allows routing by
+/// criteria the other route types cannot express, e.g. an address picked from `CLUSTER SHARDS`. Only supported in cluster mode.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be able to be safely casted to a valid [`Arc<Client>`] via [`Arc::from_raw`]. See the safety documentation of [`Arc::from_raw`].
+/// * This function should only be called should with a pointer created by [`create_client`], before [`close_client`] was called with the pointer.
+/// * Pointers to callbacks stored in [`Client`] should remain valid. See the safety documentation of [`SuccessCallback`] and [`FailureCallback`].
+/// * `cmd_ptr` must not be `null`.
+/// * `cmd_ptr` must be able to be safely casted to a valid [`CmdInfo`]. See the safety documentation of [`create_cmd`].
+/// * `host` must be a valid C string.
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn command_by_address(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    cmd_ptr: *const CmdInfo,
+    host: *const c_char,
+    port: u16,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let cmd = match unsafe { client.create_cmd(cmd_ptr) } {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return;
+        }
+    };
+
+    let host = match unsafe { CStr::from_ptr(host) }.to_str() {
+        Ok(host) => host.to_owned(),
+        Err(_) => {
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    "Invalid host argument".into(),
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return;
+        }
+    };
+
+    let resolved_request_type = unsafe { decompression_type(cmd_ptr, &cmd) };
+
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let result = send_command_to_known_node(&core, cmd, host, port).await;
+        unsafe { report_command_result(&core, callback_index, result, resolved_request_type) };
+        panic_guard.panicked = false;
+    });
+
+    panic_guard.panicked = false;
+}
+
+/// Check that `host:port` is a node of the current topology which is not flagged as failed.
+fn check_known_node(nodes: &[ClusterNode], host: &str, port: u16) -> Result<(), String> {
+    match nodes
+        .iter()
+        .find(|node| node.host == host && node.port == port)
+    {
+        None => Err(format!(
+            "Node {host}:{port} is not part of the current cluster topology"
+        )),
+        Some(node) if node.is_failed() => Err(format!("Node {host}:{port} is flagged as failed")),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Send `cmd` to `host:port` if [`check_known_node`] accepts it.
+async fn send_command_to_known_node(
+    core: &CommandExecutionCore,
+    mut cmd: redis::Cmd,
+    host: String,
+    port: u16,
+) -> redis::RedisResult<redis::Value> {
+    let nodes = get_cluster_nodes(core).await?;
+    check_known_node(&nodes, &host, port).map_err(|detail| {
+        redis::RedisError::from((
+            redis::ErrorKind::ClientError,
+            "Unknown or unhealthy node",
+            detail,
+        ))
+    })?;
+    let route = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port });
+    core.client
+        .clone()
+        .send_command(&mut cmd, Some(route))
+        .await
+}
+
+/// Execute a command on a preferred node, e.g. a replica dedicated to analytics, falling back as configured if it can't serve it.
+///
+/// The preferred node is given either by its node id or as `host:port`, and looked up with `CLUSTER NODES`. It can't serve
+/// the command if it is not part of the topology, is flagged as failed, doesn't serve the slot of the command's key, or
+/// can't be reached. In that case `fallback` selects whether an error is reported, or the command is sent to a replica or
+/// to the primary serving its slot. A command without a key falls back to its default routing. Only supported in cluster mode.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be able to be safely casted to a valid [`Arc<Client>`] via [`Arc::from_raw`]. See the safety documentation of [`Arc::from_raw`].
+/// * This function should only be called should with a pointer created by [`create_client`], before [`close_client`] was called with the pointer.
+/// * Pointers to callbacks stored in [`Client`] should remain valid. See the safety documentation of [`SuccessCallback`] and [`FailureCallback`].
+/// * `cmd_ptr` must not be `null`.
+/// * `cmd_ptr` must be able to be safely casted to a valid [`CmdInfo`]. See the safety documentation of [`create_cmd`].
+/// * `node` must be a valid C string.
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn command_preferring_node(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    cmd_ptr: *const CmdInfo,
+    node: *const c_char,
+    fallback: PreferredNodeFallback,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let cmd = match unsafe { client.create_cmd(cmd_ptr) } {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return;
+        }
+    };
+
+    let node = match unsafe { CStr::from_ptr(node) }.to_str() {
+        Ok(node) => node.to_owned(),
+        Err(_) => {
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    "Invalid node argument".into(),
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return;
+        }
+    };
+
+    let resolved_request_type = unsafe { decompression_type(cmd_ptr, &cmd) };
+
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let result = send_command_preferring_node(&core, cmd, &node, fallback).await;
+        unsafe { report_command_result(&core, callback_index, result, resolved_request_type) };
+        panic_guard.panicked = false;
+    });
+
+    panic_guard.panicked = false;
+}
+
+/// Find the preferred node of [`command_preferring_node`] by id or `host:port`, and check that it can serve `slot`.
+fn check_preferred_node(
+    nodes: &[ClusterNode],
+    preferred: &str,
+    slot: Option<u16>,
+) -> Result<(String, u16), String> {
+    let node = nodes
+        .iter()
+        .find(|node| node.id == preferred || format!("{}:{}", node.host, node.port) == preferred)
+        .ok_or_else(|| format!("Node {preferred} is not part of the current cluster topology"))?;
+    if node.is_failed() {
+        return Err(format!("Node {preferred} is flagged as failed"));
+    }
+    if let Some(slot) = slot {
+        let primary = if node.is_primary() {
+            Some(node)
+        } else {
+            nodes
+                .iter()
+                .find(|primary| node.primary_id.as_deref() == Some(primary.id.as_str()))
+        };
+        if !primary.is_some_and(|primary| primary.serves_slot(slot)) {
+            return Err(format!("Node {preferred} does not serve slot {slot}"));
+        }
+    }
+    Ok((node.host.clone(), node.port))
+}
+
+/// Send `cmd` to the preferred node if [`check_preferred_node`] accepts it and it is reachable, otherwise apply `fallback`.
+async fn send_command_preferring_node(
+    core: &CommandExecutionCore,
+    mut cmd: redis::Cmd,
+    preferred: &str,
+    fallback: PreferredNodeFallback,
+) -> redis::RedisResult<redis::Value> {
+    let nodes = get_cluster_nodes(core).await?;
+    let slot = match RoutingInfo::for_routable(&cmd) {
+        Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) => {
+            Some(route.slot())
+        }
+        _ => None,
+    };
+
+    let mut client = core.client.clone();
+    let reason = match check_preferred_node(&nodes, preferred, slot) {
+        Ok((host, port)) => {
+            let route = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port });
+            match client.send_command(&mut cmd, Some(route)).await {
+                Err(err) if err.is_io_error() || err.is_timeout() => error_message(&err),
+                result => return result,
+            }
+        }
+        Err(reason) => reason,
+    };
+
+    let slot_addr = match fallback {
+        PreferredNodeFallback::Fail => {
+            return Err(redis::RedisError::from((
+                redis::ErrorKind::ClientError,
+                "Preferred node can't serve the command",
+                reason,
+            )));
+        }
+        PreferredNodeFallback::Replica => redis::cluster_routing::SlotAddr::ReplicaRequired,
+        PreferredNodeFallback::Primary => redis::cluster_routing::SlotAddr::Master,
+    };
+    let route = slot.map(|slot| {
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(
+            redis::cluster_routing::Route::new(slot, slot_addr),
+        ))
+    });
+    client.send_command(&mut cmd, route).await
+}
+
+/// The request type to decompress the reply of the command built from `cmd_ptr` as, resolving the actual command of a
+/// `CustomCommand` from its arguments. `None` if the command skips compression, see [`CmdInfo::skip_compression`].
+///
+/// # Safety
+/// * `cmd_ptr` must be able to be safely casted to a valid [`CmdInfo`]. See the safety documentation of [`create_cmd`].
+#[allow(rustdoc::private_intra_doc_links)]
+unsafe fn decompression_type(cmd_ptr: *const CmdInfo, cmd: &redis::Cmd) -> Option<RequestType> {
+    let info = unsafe { *cmd_ptr };
+    if info.skip_compression {
+        return None;
+    }
+    Some(if matches!(info.request_type, RequestType::CustomCommand) {
+        resolve_custom_command_type(&extract_cmd_args(cmd))
+    } else {
+        info.request_type
+    })
+}
+
+/// Decompress a response if compression is enabled, returning it unchanged if decompression fails.
+/// `request_type` is the type to decompress the response as, see [`decompression_type`], `None` returns it unchanged.
+fn decompress_response(
+    core: &CommandExecutionCore,
+    value: redis::Value,
+    request_type: Option<RequestType>,
+) -> redis::Value {
+    let Some(request_type) = request_type else {
+        return value;
+    };
+    let original = value.clone();
+    glide_core::compression::process_response_for_decompression(
+        value,
+        request_type,
+        core.client.compression_manager().as_deref(),
+    )
+    .unwrap_or_else(|e| {
+        logger_core::log_warn(
+            "response_decompression",
+            format!("Failed to decompress response: {}", e),
+        );
+        original
+    })
+}
+
+/// Decompress the result of a single command if needed and report it through the client's callbacks.
+///
+/// # Safety
+/// * Pointers to callbacks stored in `core` should remain valid. See the safety documentation of [`SuccessCallback`] and [`FailureCallback`].
+unsafe fn report_command_result(
+    core: &CommandExecutionCore,
+    callback_index: usize,
+    result: redis::RedisResult<redis::Value>,
+    request_type: Option<RequestType>,
+) {
+    match result {
+        Ok(value) => {
+            let value = decompress_response(core, value, request_type);
+            match core.response_value(value) {
+                Ok(response) => {
+                    let ptr = Box::into_raw(Box::new(response));
+                    unsafe { (core.success_callback)(callback_index, ptr) };
+                }
+                Err(err) => unsafe {
+                    report_error(
+                        core.failure_callback,
+                        callback_index,
+                        err,
+                        RequestErrorType::Unspecified,
+                    );
+                },
+            }
+        }
+        Err(err) => unsafe {
+            report_error(
+                core.failure_callback,
+                callback_index,
+                command_error_message(&err),
+                error_type(&err),
+            );
+        },
+    };
+}
+
+/// Spawn a command on the client's runtime and report its result through the client's callbacks.
+///
+/// This is shared by the FFI helpers that build a single [`redis::Cmd`] themselves rather than receiving a [`CmdInfo`].
+///
+/// # Safety
+/// * Pointers to callbacks stored in [`Client`] should remain valid. See the safety documentation of [`SuccessCallback`] and [`FailureCallback`].
+unsafe fn spawn_command(
+    client: &Client,
+    callback_index: usize,
+    cmd: redis::Cmd,
+    route: Option<RoutingInfo>,
+) {
+    unsafe { spawn_command_with(client, callback_index, cmd, route, |result| result) }
+}
+
+/// Same as [`spawn_command`], but passes the command result through `process` before reporting it.
+/// This lets helpers reshape a reply or replace a server error with a clearer one.
+///
+/// # Safety
+/// * See the safety documentation of [`spawn_command`].
+unsafe fn spawn_command_with<F>(
+    client: &Client,
+    callback_index: usize,
+    mut cmd: redis::Cmd,
+    route: Option<RoutingInfo>,
+    process: F,
+) where
+    F: FnOnce(redis::RedisResult<redis::Value>) -> redis::RedisResult<redis::Value>
+        + Send
+        + 'static,
+{
+    let core = client.core.clone();
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let result = process(core.client.clone().send_command(&mut cmd, route).await);
+        unsafe { report_result(&core, callback_index, result) };
+        panic_guard.panicked = false;
+        drop(panic_guard);
+    });
+}
+
+/// Report a result through the client's callbacks as is, without decompression.
+///
+/// # Safety
+/// * Pointers to callbacks stored in `core` should remain valid. See the safety documentation of [`SuccessCallback`] and [`FailureCallback`].
+unsafe fn report_result(
+    core: &CommandExecutionCore,
+    callback_index: usize,
+    result: redis::RedisResult<redis::Value>,
+) {
+    match result {
+        Ok(value) => match core.response_value(value) {
+            Ok(response) => {
+                let ptr = Box::into_raw(Box::new(response));
+                unsafe { (core.success_callback)(callback_index, ptr) };
+            }
+            Err(err) => unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            },
+        },
+        Err(err) => unsafe {
+            report_error(
+                core.failure_callback,
+                callback_index,
+                command_error_message(&err),
+                error_type(&err),
+            );
+        },
+    };
+}
+
+// ========================================================================================
+// Auto-Pipelining
+// ========================================================================================
+
+/// A command waiting in the auto-pipelining queue.
+struct QueuedCommand {
+    cmd: redis::Cmd,
+    callback_index: usize,
+    /// The type to decompress the reply as, see [`decompression_type`].
+    request_type: Option<RequestType>,
+    decode_numbers: bool,
+    /// Keeps the command tracked as in-flight until its result is reported.
+    _inflight: InflightTaskGuard,
+}
+
+/// A message to the auto-pipelining task.
+enum AutoPipelineMessage {
+    /// Queue a command.
+    Command(QueuedCommand),
+    /// Send the queued commands now, and notify once their results were reported, see [`flush_pending`].
+    Flush(tokio::sync::oneshot::Sender<()>),
+    /// Like [`AutoPipelineMessage::Flush`], then fail every command queued afterwards, since the client is closing.
+    Close(tokio::sync::oneshot::Sender<()>),
+}
+
+/// Drain the auto-pipelining queue, flushing the accumulated commands as a non-atomic pipeline once
+/// `max_batch_size` commands are queued or `flush_interval_ms` elapsed since the first one was queued.
+///
+/// Ordering and failure semantics:
+/// * Queued commands are sent in the order [`command`] was called, and each window is sent as one pipeline.
+///   A window is flushed without waiting for the previous one to complete, so replies of consecutive windows may arrive out of order.
+/// * Commands with an explicit route bypass the queue and are sent immediately, so they may overtake queued commands.
+/// * Every command's own callback is called with its own result. A server error fails only the command that caused it,
+///   while a pipeline-level error (e.g. a disconnect or timeout) fails every command in the window.
+/// * A window is sent with the global request timeout, regardless of the operation class of its commands.
+/// * A window that is full or whose interval elapsed is sent by its own task, tracked in `inflight` like the tasks of
+///   [`Client::spawn_tracked`], so that the next window is collected meanwhile.
+/// * [`flush_pending`] sends the current window immediately, and waits for the results of every window sent before it.
+///   [`close_client`] does so too, and commands queued afterwards are failed with a "client is closing" error rather than
+///   silently dropped when the client's runtime shuts down.
+async fn run_auto_pipeline(
+    core: Arc<CommandExecutionCore>,
+    mut queue: tokio::sync::mpsc::UnboundedReceiver<AutoPipelineMessage>,
+    config: AutoPipelineConfig,
+    inflight: Arc<InflightTasks>,
+) {
+    let max_batch_size = config.max_batch_size as usize;
+    let flush_interval = std::time::Duration::from_millis(config.flush_interval_ms.into());
+    let mut windows = tokio::task::JoinSet::new();
+
+    while let Some(message) = queue.recv().await {
+        // Reap the windows sent meanwhile, so that the set only holds the pending ones.
+        while windows.try_join_next().is_some() {}
+
+        let first = match message {
+            AutoPipelineMessage::Command(queued) => queued,
+            AutoPipelineMessage::Flush(done) => {
+                while windows.join_next().await.is_some() {}
+                let _ = done.send(());
+                continue;
+            }
+            AutoPipelineMessage::Close(done) => {
+                while windows.join_next().await.is_some() {}
+                let _ = done.send(());
+                break;
+            }
+        };
+
+        let deadline = tokio::time::Instant::now() + flush_interval;
+        let mut window = Vec::with_capacity(max_batch_size);
+        window.push(first);
+
+        let mut flush = None;
+        while window.len() < max_batch_size {
+            match tokio::time::timeout_at(deadline, queue.recv()).await {
+                Ok(Some(AutoPipelineMessage::Command(queued))) => window.push(queued),
+                Ok(Some(message)) => {
+                    flush = Some(message);
+                    break;
+                }
+                Ok(None) | Err(_) => break,
+            }
+        }
+
+        match flush {
+            None => {
+                let guard = inflight.track();
+                let core = core.clone();
+                windows.spawn(async move {
+                    flush_auto_pipeline(core, window).await;
+                    drop(guard);
+                });
+            }
+            Some(AutoPipelineMessage::Flush(done)) => {
+                flush_auto_pipeline(core.clone(), window).await;
+                while windows.join_next().await.is_some() {}
+                let _ = done.send(());
+            }
+            Some(AutoPipelineMessage::Close(done)) => {
+                flush_auto_pipeline(core.clone(), window).await;
+                while windows.join_next().await.is_some() {}
+                let _ = done.send(());
+                break;
+            }
+            Some(AutoPipelineMessage::Command(_)) => unreachable!(),
+        }
+    }
+
+    // The client is closing, fail the commands queued since instead of dropping them with the runtime.
+    while let Some(message) = queue.recv().await {
+        match message {
+            AutoPipelineMessage::Command(queued) => unsafe {
+                report_error(
+                    core.failure_callback,
+                    queued.callback_index,
+                    "Client is closing".into(),
+                    RequestErrorType::Disconnect,
+                );
+            },
+            AutoPipelineMessage::Flush(done) | AutoPipelineMessage::Close(done) => {
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+/// Send the commands waiting in the auto-pipelining queue now, and wait until their results, and those of the windows
+/// sent before, were reported.
+///
+/// Does nothing if auto-pipelining is disabled. This is called by [`close_client`], so that queued commands are
+/// sent rather than dropped.
+///
+/// # Safety
+/// * See the safety documentation of [`close_client`].
+#[unsafe(no_mangle)]
+pub extern "C" fn flush_pending(client_ptr: *const c_void) {
+    assert!(!client_ptr.is_null());
+    let client = unsafe { &*(client_ptr as *const Client) };
+    flush_auto_pipeline_queue(client, AutoPipelineMessage::Flush);
+}
+
+/// Send `message` to the auto-pipelining task, if any, and wait for it to be handled.
+fn flush_auto_pipeline_queue(
+    client: &Client,
+    message: fn(tokio::sync::oneshot::Sender<()>) -> AutoPipelineMessage,
+) {
+    let Some(queue) = &client.auto_pipeline else {
+        return;
+    };
+    let (done_tx, done_rx) = tokio::sync::oneshot::channel();
+    if queue.send(message(done_tx)).is_ok() {
+        let _ = client.runtime.block_on(done_rx);
+    }
+}
+
+/// Send one auto-pipelining window and report every command's result through its own callback.
+async fn flush_auto_pipeline(core: Arc<CommandExecutionCore>, window: Vec<QueuedCommand>) {
+    let mut pipeline = redis::Pipeline::with_capacity(window.len());
+    for queued in &window {
+        pipeline.add_command(queued.cmd.clone());
+    }
+
+    let timing = core.start_timing();
+    let result = core
+        .client
+        .clone()
+        .send_pipeline(
+            &pipeline,
+            None,
+            false,
+            core.batch_timeout(None),
+            glide_core::client::PipelineRetryStrategy::new(false, false),
+        )
+        .await;
+    // Every command of the window shares the window's round trip.
+    let timing = timing.map(|(callback, start)| (callback, start.elapsed()));
+
+    let results: Vec<redis::RedisResult<redis::Value>> = match result {
+        Ok(redis::Value::Array(values)) if values.len() == window.len() => values
+            .into_iter()
+            .map(|value| match value {
+                redis::Value::ServerError(err) => Err(err.into()),
+                value => Ok(value),
+            })
+            .collect(),
+        Ok(value) => {
+            let err = redis::RedisError::from((
+                redis::ErrorKind::ResponseError,
+                "Unexpected auto-pipeline response",
+                format!("{value:?}"),
+            ));
+            window.iter().map(|_| Err(err.clone())).collect()
+        }
+        Err(err) => window.iter().map(|_| Err(err.clone())).collect(),
+    };
+
+    for (queued, mut result) in window.into_iter().zip(results) {
+        if queued.decode_numbers {
+            result = result.map(decode_numeric_strings);
+        }
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index: queued.callback_index,
+        };
+        if let Some((callback, elapsed)) = timing {
+            let elapsed_ns = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+            unsafe { callback(queued.callback_index, elapsed_ns) };
+        }
+        unsafe { report_command_result(&core, queued.callback_index, result, queued.request_type) };
+        panic_guard.panicked = false;
+    }
+}
+
+/// Execute a batch.
+///
+/// # Safety
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be able to be safely casted to a valid [`Arc<Client>`] via [`Arc::from_raw`]. See the safety documentation of [`Arc::from_raw`].
+/// * This function should only be called should with a pointer created by [`create_client`], before [`close_client`] was called with the pointer.
+/// * `batch_ptr` must not be `null`.
+/// * `batch_ptr` must be able to be safely casted to a valid [`BatchInfo`]. See the safety documentation of [`create_pipeline`].
+/// * `options_ptr` could be `null`, but if it is not `null`, it must be a valid [`BatchOptionsInfo`] pointer. See the safety documentation of [`get_pipeline_options`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn batch(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    batch_ptr: *const BatchInfo,
+    raise_on_error: bool,
+    options_ptr: *const BatchOptionsInfo,
+) {
+    let client = unsafe {
+        // we increment the strong count to ensure that the client is not dropped just because we turned it into an Arc.
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let pipeline = match unsafe {
+        create_pipeline(
+            batch_ptr,
+            core.client.compression_manager().as_ref(),
+            client.key_prefix.as_deref(),
+            client.validate_command_arity,
+        )
+    } {
+        Ok(pipeline) => pipeline,
+        Err(err) => {
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return;
+        }
+    };
+
+    let (routing, timeout, pipeline_retry_strategy) =
+        match unsafe { get_pipeline_options(options_ptr) } {
+            Ok((routing, timeout, retry_strategy)) => {
+                (routing, core.batch_timeout(timeout), retry_strategy)
+            }
+            Err(err) => {
+                panic_guard.panicked = false;
+                unsafe {
+                    report_error(
+                        core.failure_callback,
+                        callback_index,
+                        err,
+                        RequestErrorType::Unspecified,
+                    );
+                }
+                return;
+            }
+        };
+
+    let stop_on_first_error =
+        !options_ptr.is_null() && unsafe { (*options_ptr).stop_on_first_error };
+    let sequential =
+        stop_on_first_error || (!options_ptr.is_null() && unsafe { (*options_ptr).sequential });
+    if stop_on_first_error && pipeline.is_atomic() {
+        panic_guard.panicked = false;
+        unsafe {
+            report_error(
+                core.failure_callback,
+                callback_index,
+                "stop_on_first_error only applies to non-atomic batches, a transaction is already all-or-nothing"
+                    .into(),
+                RequestErrorType::Unspecified,
+            );
+        }
+        return;
+    }
+
+    // A transaction whose keys share a slot the client can't verify statically (e.g. keys computed by a script) is routed
+    // to the slot of its first keyed command. The server still rejects it with CROSSSLOT if the keys actually differ.
+    let mut routing = routing;
+    let is_read_only = !options_ptr.is_null() && unsafe { (*options_ptr).is_read_only };
+    if !options_ptr.is_null() && unsafe { (*options_ptr).route_by_first_key } {
+        let first_key_routing = if !pipeline.is_atomic() {
+            Err("route_by_first_key only applies to atomic batches".to_owned())
+        } else if routing.is_some() {
+            Err("route_by_first_key can't be combined with an explicit route".to_owned())
+        } else if !client.cluster_mode {
+            Ok(None)
+        } else {
+            let slot_addr = if is_read_only {
+                redis::cluster_routing::SlotAddr::ReplicaOptional
+            } else {
+                redis::cluster_routing::SlotAddr::Master
+            };
+            first_key_route(&pipeline, slot_addr).map(Some).ok_or_else(|| {
+                "Can't route the transaction by its first key: none of its commands has a key mapping to a single slot"
+                    .to_owned()
+            })
+        };
+        match first_key_routing {
+            Ok(first_key_routing) => routing = first_key_routing,
+            Err(err) => {
+                panic_guard.panicked = false;
+                unsafe {
+                    report_error(
+                        core.failure_callback,
+                        callback_index,
+                        err,
+                        RequestErrorType::Unspecified,
+                    );
+                }
+                return;
+            }
+        }
+    }
+
+    // A read-only batch is served by replicas as allowed by the client's `ReadFrom` strategy. Non-atomic batches already
+    // route every read command this way, while a transaction is sent to a primary unless routed explicitly.
+    if is_read_only {
+        if unsafe { (*options_ptr).validate_read_only } {
+            if let Err(err) = check_read_only(&pipeline) {
+                panic_guard.panicked = false;
+                unsafe {
+                    report_error(
+                        core.failure_callback,
+                        callback_index,
+                        err,
+                        RequestErrorType::Unspecified,
+                    );
+                }
+                return;
+            }
+        }
+        if routing.is_none() && pipeline.is_atomic() {
+            routing = read_only_route(&pipeline);
+        }
+    }
+
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let result = if pipeline.is_atomic() {
+            core.client
+                .clone()
+                .send_transaction(&pipeline, routing, timeout, raise_on_error)
+                .await
+        } else if sequential {
+            send_pipeline_sequential(
+                &core,
+                &pipeline,
+                routing,
+                raise_on_error,
+                stop_on_first_error,
+                timeout,
+            )
+            .await
+        } else {
+            core.client
+                .clone()
+                .send_pipeline(
+                    &pipeline,
+                    routing,
+                    raise_on_error,
+                    timeout,
+                    pipeline_retry_strategy,
+                )
+                .await
+        };
+
+        unsafe { report_batch_result(&core, callback_index, result) };
+        panic_guard.panicked = false;
+        drop(panic_guard);
+    });
+
+    panic_guard.panicked = false;
+    drop(panic_guard);
+}
+
+/// Execute a transaction with optimistic locking: WATCH the given keys, then send the transaction, retrying the whole
+/// WATCH/MULTI/EXEC sequence while EXEC aborts because a watched key was modified.
+///
+/// The result of the first transaction which is not aborted is reported. If the transaction is still aborted after
+/// `max_retries` retries, a "max retries exceeded" error of type [`RequestErrorType::ExecAbort`] is reported instead.
+///
+/// Like a WATCH sent with [`command`], the watch applies to the client's connection, so commands sent concurrently through
+/// the same client may be executed between WATCH and EXEC. In cluster mode, the watched keys and the transaction keys must
+/// map to the same slot.
+///
+/// # Safety
+/// * `client_ptr` must not be `null` and must be obtained from [`create_client`], before [`close_client`] was called with the pointer.
+/// * `keys` and `keys_len` must be valid arrays of size `keys_count`, or both null if `keys_count` is 0.
+/// * `batch_ptr` must not be `null` and must be a valid atomic [`BatchInfo`]. See the safety documentation of [`create_pipeline`].
+/// * `options_ptr` could be `null`, but if it is not `null`, it must be a valid [`BatchOptionsInfo`] pointer. See the safety documentation of [`get_pipeline_options`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn transaction_with_retry(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    keys_count: usize,
+    keys: *const usize,
+    keys_len: *const usize,
+    batch_ptr: *const BatchInfo,
+    raise_on_error: bool,
+    options_ptr: *const BatchOptionsInfo,
+    max_retries: u32,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let prepared = unsafe {
+        create_pipeline(
+            batch_ptr,
+            core.client.compression_manager().as_ref(),
+            client.key_prefix.as_deref(),
+            client.validate_command_arity,
+        )
+    }
+    .and_then(|pipeline| match pipeline.is_atomic() {
+        true => Ok(pipeline),
+        false => Err("transaction_with_retry requires an atomic batch".to_string()),
+    })
+    .and_then(|pipeline| {
+        unsafe { get_pipeline_options(options_ptr) }
+            .map(|(routing, timeout, _)| (pipeline, routing, core.batch_timeout(timeout)))
+    });
+    let (pipeline, routing, timeout) = match prepared {
+        Ok(prepared) => prepared,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            panic_guard.panicked = false;
+            return;
+        }
+    };
+
+    let watch_keys = unsafe {
+        ffi::convert_byte_array_to_slices(keys as *const *const u8, keys_count, keys_len)
+    };
+    let mut watch_cmd = redis::cmd("WATCH");
+    for key in watch_keys {
+        watch_cmd.arg(client.prefixed_key(key));
+    }
+
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let mut attempts = 0;
+        let result = loop {
+            if keys_count > 0 {
+                let mut watch_cmd = watch_cmd.clone();
+                if let Err(err) =
+                    send_command_with_timeout(&core, &mut watch_cmd, routing.clone(), timeout).await
+                {
+                    break Err(err);
+                }
+            }
+            match core
+                .client
+                .clone()
+                .send_transaction(&pipeline, routing.clone(), timeout, raise_on_error)
+                .await
+            {
+                // EXEC replies with nil when a watched key was modified.
+                Ok(redis::Value::Nil) if attempts < max_retries => attempts += 1,
+                Ok(redis::Value::Nil) => {
+                    break Err(redis::RedisError::from((
+                        redis::ErrorKind::ExecAbortError,
+                        "Transaction aborted, max retries exceeded",
+                        format!(
+                            "watched keys were modified on all {} attempts",
+                            attempts + 1
+                        ),
+                    )));
+                }
+                result => break result,
+            }
+        };
+
+        unsafe { report_batch_result(&core, callback_index, result) };
+        panic_guard.panicked = false;
+    });
+
+    panic_guard.panicked = false;
+}
+
+/// Report the result of a batch, decompressing its values if compression is enabled.
+///
+/// # Safety
+/// * `core` callbacks must be valid, see [`create_client`].
+unsafe fn report_batch_result(
+    core: &CommandExecutionCore,
+    callback_index: usize,
+    result: redis::RedisResult<redis::Value>,
+) {
+    match result {
+        Ok(value) => {
+            let final_value = if let Some(ref manager) = core.client.compression_manager() {
+                match glide_core::compression::decompress_batch_response(
+                    value.clone(),
+                    manager.as_ref(),
+                ) {
+                    Ok(decompressed) => decompressed,
+                    Err(e) => {
+                        logger_core::log_warn(
+                            "batch_decompression",
+                            format!(
+                                "Failed to decompress batch response: {}, returning original",
+                                e
+                            ),
+                        );
+                        value
+                    }
+                }
+            } else {
+                value
+            };
+            match core.response_value(final_value) {
+                Ok(response) => {
+                    let ptr = Box::into_raw(Box::new(response));
+                    unsafe { (core.success_callback)(callback_index, ptr) };
+                }
+                Err(err) => unsafe {
+                    report_error(
+                        core.failure_callback,
+                        callback_index,
+                        err,
+                        RequestErrorType::Unspecified,
+                    );
+                },
+            }
+        }
+        Err(err) => unsafe {
+            report_error(
+                core.failure_callback,
+                callback_index,
+                error_message(&err),
+                error_type(&err),
+            );
+        },
+    };
+}
+
+/// Execute a non-atomic batch one command at a time, waiting for each reply before sending the next command.
+///
+/// By default a non-atomic batch is split into per-node sub-pipelines which are sent in parallel, so commands sent to the
+/// same node are executed in order, but there is no ordering between commands sent to different nodes. Sending the commands
+/// one by one orders them across nodes as well, at the cost of a round trip per command.
+///
+/// `timeout` applies to every command rather than to the whole batch. With `raise_on_error`, the first server error fails
+/// the batch and the remaining commands are not sent; otherwise it is reported in place of the command's reply.
+///
+/// With `stop_on_first_error`, the first error, including a connection error, stops the batch without rolling back the
+/// commands which already ran: the reply holds the replies of these commands, followed by the error. Unlike a transaction,
+/// the batch is therefore partially applied. Combined with `raise_on_error`, the error fails the batch as described above.
+async fn send_pipeline_sequential(
+    core: &CommandExecutionCore,
+    pipeline: &redis::Pipeline,
+    routing: Option<RoutingInfo>,
+    raise_on_error: bool,
+    stop_on_first_error: bool,
+    timeout: Option<u32>,
+) -> redis::RedisResult<redis::Value> {
+    let mut replies = Vec::with_capacity(pipeline.len());
+    for cmd in pipeline.cmd_iter() {
+        let mut cmd = redis::Cmd::clone(cmd);
+        match send_command_with_timeout(core, &mut cmd, routing.clone(), timeout).await {
+            Ok(reply) => replies.push(reply),
+            Err(err)
+                if !raise_on_error
+                    && (stop_on_first_error || err.kind() != redis::ErrorKind::IoError) =>
+            {
+                replies.push(redis::Value::ServerError(
+                    redis::ServerError::ExtensionError {
+                        code: err.code().unwrap_or("ERR").to_string(),
+                        detail: Some(error_message(&err)),
+                    },
+                ));
+                if stop_on_first_error {
+                    break;
+                }
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(redis::Value::Array(replies))
+}
+
+/// Free the memory allocated for a [`ResponseValue`] and nested structure.
+///
+/// # Safety
+/// * `ptr` must not be `null`.
+/// * `ptr` must be able to be safely casted to a valid [`Box<ResponseValue>`] via [`Box::from_raw`]. See the safety documentation of [`Box::from_raw`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn free_response(ptr: *mut ResponseValue) {
+    unsafe {
+        // Take ownership of ptr in the Box.
+        let boxed = Box::from_raw(ptr);
+        boxed.free_memory(); // This releases the contents of ResponseValue, but not the memory used by ResponseValue itself. This must be done explicitly.
+        // boxed is dropped here, releasing the memory used directly by ResponseValue itself.
+    }
+}
+
+/// Free many responses in a single call instead of one [`free_response`] call per response.
+///
+/// Every pointer in `ptrs` is a response returned on its own, e.g. to a success callback, and is freed like with
+/// [`free_response`], including its nested values. The array of pointers itself is owned by the caller and not freed.
+/// Does nothing if `count` is `0`.
+///
+/// # Safety
+/// * Unless `count` is `0`, `ptrs` must not be `null` and must point to `count` consecutive pointers.
+/// * Every pointer must satisfy the safety requirements of [`free_response`]. A pointer to an element of another
+///   response's array must not be passed, since that element is freed with its parent.
+/// * No pointer may appear twice, nor be freed again.
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn free_response_array(ptrs: *const *mut ResponseValue, count: usize) {
+    if count == 0 {
+        return;
+    }
+    for &ptr in unsafe { std::slice::from_raw_parts(ptrs, count) } {
+        unsafe { free_response(ptr) };
+    }
+}
+
+/// Frees memory allocated for a C string.
+///
+/// # Parameters
+/// * `str_ptr`: Pointer to the C string to free.
+/// # Safety
+/// * `str_ptr` must be a valid pointer to a C string allocated by Rust (e.g., via `CString::into_raw`), or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn free_string(str_ptr: *mut c_char) {
+    if !str_ptr.is_null() {
+        unsafe {
+            let _ = CString::from_raw(str_ptr);
+        };
+    }
+}
+
+impl From<logger_core::Level> for Level {
+    fn from(level: logger_core::Level) -> Self {
+        match level {
+            logger_core::Level::Error => Level::Error,
             logger_core::Level::Warn => Level::Warn,
             logger_core::Level::Info => Level::Info,
             logger_core::Level::Debug => Level::Debug,
             logger_core::Level::Trace => Level::Trace,
             logger_core::Level::Off => Level::Off,
         }
-    }
+    }
+}
+
+impl From<Level> for logger_core::Level {
+    fn from(level: Level) -> logger_core::Level {
+        match level {
+            Level::Error => logger_core::Level::Error,
+            Level::Warn => logger_core::Level::Warn,
+            Level::Info => logger_core::Level::Info,
+            Level::Debug => logger_core::Level::Debug,
+            Level::Trace => logger_core::Level::Trace,
+            Level::Off => logger_core::Level::Off,
+        }
+    }
+}
+
+/// # Safety
+///
+/// * `message` and `log_identifier` must not be `null`.
+/// * `message` and `log_identifier` must be able to be safely casted to a valid [`CStr`] via [`CStr::from_ptr`]. See the safety documentation of [`CStr::from_ptr`].
+#[unsafe(no_mangle)]
+#[allow(improper_ctypes_definitions)]
+pub unsafe extern "C" fn log(
+    log_level: Level,
+    log_identifier: *const c_char,
+    message: *const c_char,
+) {
+    let log_id = match unsafe { CStr::from_ptr(log_identifier) }.to_str() {
+        Ok(s) => s,
+        Err(_) => {
+            // Cannot log with invalid UTF-8 identifier; silently skip.
+            return;
+        }
+    };
+    let msg = match unsafe { CStr::from_ptr(message) }.to_str() {
+        Ok(s) => s,
+        Err(_) => {
+            // Cannot log with invalid UTF-8 message; silently skip.
+            return;
+        }
+    };
+    logger_core::log(log_level.into(), log_id, msg);
+}
+
+/// Initializes the logger with the given level and optional file name.
+///
+/// Returns a null pointer on success, or a pointer to a C string error message on failure.
+/// On success, the resolved log level is written to `*level_out`.
+/// The caller is responsible for freeing the error message using `free_string`.
+///
+/// # Safety
+///
+/// * `file_name` may be `null` (logs to console). If non-null, it must be a valid C string.
+/// * `level_out` must not be `null` and must point to a valid writable `Level`.
+#[unsafe(no_mangle)]
+#[allow(improper_ctypes_definitions)]
+pub unsafe extern "C" fn init_logger(
+    level: Option<Level>,
+    file_name: *const c_char,
+    level_out: *mut Level,
+) -> *mut c_char {
+    let file_name_as_str = if file_name.is_null() {
+        None
+    } else {
+        match unsafe { CStr::from_ptr(file_name) }.to_str() {
+            Ok(s) => Some(s),
+            Err(e) => {
+                let error_msg = format!("Invalid UTF-8 in file name: {e}");
+                return CString::new(error_msg).unwrap().into_raw();
+            }
+        }
+    };
+
+    let logger_level = logger_core::init(level.map(|level| level.into()), file_name_as_str);
+    unsafe { *level_out = logger_level.into() };
+    std::ptr::null_mut()
+}
+
+/// The hashes of the scripts stored with [`store_script`] and not dropped yet, with the number of times they were stored,
+/// see [`export_script_hashes`].
+///
+/// glide-core's script container does not list its scripts, so its reference counting is mirrored here.
+static STORED_SCRIPTS: std::sync::Mutex<std::collections::BTreeMap<String, usize>> =
+    std::sync::Mutex::new(std::collections::BTreeMap::new());
+
+fn lock_stored_scripts() -> std::sync::MutexGuard<'static, std::collections::BTreeMap<String, usize>>
+{
+    STORED_SCRIPTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[repr(C)]
+pub struct ScriptHashBuffer {
+    pub ptr: *mut u8,
+    pub len: usize,
+    pub capacity: usize,
+}
+
+/// Store a Lua script in the script cache and return its SHA1 hash.
+///
+/// # Parameters
+///
+/// * `script_bytes`: Pointer to the script bytes.
+/// * `script_len`: Length of the script in bytes.
+///
+/// # Returns
+///
+/// A pointer to a `ScriptHashBuffer` containing the SHA1 hash of the script.
+/// The caller is responsible for freeing this memory using [`free_script_hash_buffer`].
+///
+/// # Safety
+///
+/// * `script_bytes` must point to `script_len` consecutive properly initialized bytes.
+/// * The returned buffer must be freed by the caller using [`free_script_hash_buffer`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn store_script(
+    script_bytes: *const u8,
+    script_len: usize,
+) -> *mut ScriptHashBuffer {
+    let script = unsafe { std::slice::from_raw_parts(script_bytes, script_len) };
+    let hash = glide_core::scripts_container::add_script(script);
+    *lock_stored_scripts().entry(hash.clone()).or_default() += 1;
+    let mut hash = std::mem::ManuallyDrop::new(hash);
+    let script_hash_buffer = ScriptHashBuffer {
+        ptr: hash.as_mut_ptr(),
+        len: hash.len(),
+        capacity: hash.capacity(),
+    };
+    Box::into_raw(Box::new(script_hash_buffer))
+}
+
+/// Free a `ScriptHashBuffer` obtained from [`store_script`].
+///
+/// # Parameters
+///
+/// * `buffer`: Pointer to the `ScriptHashBuffer`.
+///
+/// # Safety
+///
+/// * `buffer` must be a pointer returned from [`store_script`].
+/// * This function must be called exactly once per buffer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn free_script_hash_buffer(buffer: *mut ScriptHashBuffer) {
+    if buffer.is_null() {
+        return;
+    }
+    let buffer = unsafe { Box::from_raw(buffer) };
+    let _hash = unsafe { String::from_raw_parts(buffer.ptr, buffer.len, buffer.capacity) };
+}
+
+/// Remove a script from the script cache.
+///
+/// Returns a null pointer if it succeeds and a C string error message if it fails.
+///
+/// # Parameters
+///
+/// * `hash`: The SHA1 hash of the script to remove as a byte array.
+/// * `len`: The length of `hash`.
+///
+/// # Returns
+///
+/// A null pointer on success, or a pointer to a C string error message on failure.
+/// The caller is responsible for freeing the error message using `free_string`.
+///
+/// # Safety
+///
+/// * `hash` must be a valid pointer to a UTF-8 string.
+/// * The returned error pointer (if not null) must be freed using `free_string`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn drop_script(hash: *mut u8, len: usize) -> *mut c_char {
+    if hash.is_null() {
+        return CString::new("Hash pointer was null.").unwrap().into_raw();
+    }
+
+    let slice = std::ptr::slice_from_raw_parts_mut(hash, len);
+    let Ok(hash_str) = std::str::from_utf8(unsafe { &*slice }) else {
+        return CString::new("Unable to convert hash to UTF-8 string.")
+            .unwrap()
+            .into_raw();
+    };
+
+    glide_core::scripts_container::remove_script(hash_str);
+    let mut stored_scripts = lock_stored_scripts();
+    if let Some(count) = stored_scripts.get_mut(hash_str) {
+        *count -= 1;
+        if *count == 0 {
+            stored_scripts.remove(hash_str);
+        }
+    }
+    std::ptr::null_mut()
+}
+
+/// List the SHA1 hashes of the scripts in the script cache, e.g. to pre-register them in another client.
+///
+/// The script cache is shared by all clients of the process, so the hashes of the scripts stored with [`store_script`]
+/// and not dropped with [`drop_script`] are returned, in ascending order, as an array of strings. Scripts are cached by
+/// the process rather than by a client, so a new client of the same process can already invoke them. To prime another
+/// process, store the same scripts there with [`store_script`], whose hashes match the exported ones.
+///
+/// Returns `null` on failure. The returned pointer must be freed with [`free_response`].
+#[unsafe(no_mangle)]
+pub extern "C" fn export_script_hashes() -> *mut ResponseValue {
+    let value = redis::Value::Array(
+        lock_stored_scripts()
+            .keys()
+            .map(|hash| redis::Value::BulkString(hash.clone().into_bytes()))
+            .collect(),
+    );
+    match ResponseValue::from_value(value) {
+        Ok(response) => Box::into_raw(Box::new(response)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Executes a Lua script using EVALSHA with automatic fallback to EVAL.
+///
+/// # Parameters
+///
+/// * `client_ptr`: Pointer to a valid `GlideClient` returned from [`create_client`].
+/// * `callback_index`: Unique identifier for the callback.
+/// * `hash`: SHA1 hash of the script as a null-terminated C string.
+/// * `keys_count`: Number of keys in the keys array.
+/// * `keys`: Array of pointers to key data.
+/// * `keys_len`: Array of key lengths.
+/// * `args_count`: Number of arguments in the args array.
+/// * `args`: Array of pointers to argument data.
+/// * `args_len`: Array of argument lengths.
+/// * `route_info`: Optional route, `null` routes the script by its keys, or to a random node if it has none.
+///
+/// # Safety
+///
+/// * `client_ptr` must not be `null` and must be obtained from [`create_client`].
+/// * `hash` must be a valid null-terminated C string.
+/// * `keys` and `keys_len` must be valid arrays of size `keys_count`, or both null if `keys_count` is 0.
+/// * `args` and `args_len` must be valid arrays of size `args_count`, or both null if `args_count` is 0.
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn invoke_script(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    hash: *const c_char,
+    keys_count: usize,
+    keys: *const usize,
+    keys_len: *const usize,
+    args_count: usize,
+    args: *const usize,
+    args_len: *const usize,
+    route_info: *const RouteInfo,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    // Convert hash to Rust string
+    let hash_str = match unsafe { CStr::from_ptr(hash).to_str() } {
+        Ok(s) => s.to_string(),
+        Err(e) => {
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    format!("Invalid hash string: {}", e),
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return;
+        }
+    };
+
+    // Convert keys
+    let keys_vec: Vec<Vec<u8>> = unsafe {
+        ffi::convert_byte_array_to_slices(keys as *const *const u8, keys_count, keys_len)
+    }
+    .into_iter()
+    .map(|key| client.prefixed_key(key))
+    .collect();
+
+    // Convert args
+    let args_vec: Vec<&[u8]> = unsafe {
+        ffi::convert_byte_array_to_slices(args as *const *const u8, args_count, args_len)
+    };
+
+    let route = match unsafe { create_route(route_info, Some(&redis::cmd("EVALSHA"))) } {
+        Ok(route) => route,
+        Err(err) => {
+            panic_guard.panicked = false;
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return;
+        }
+    };
+
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let keys_vec: Vec<&[u8]> = keys_vec.iter().map(Vec::as_slice).collect();
+        let result = invoke_script_reloading(&core, &hash_str, &keys_vec, &args_vec, route).await;
+
+        match result {
+            Ok(value) => match core.response_value(value) {
+                Ok(response) => {
+                    let ptr = Box::into_raw(Box::new(response));
+                    unsafe { (core.success_callback)(callback_index, ptr) };
+                }
+                Err(err) => unsafe {
+                    report_error(
+                        core.failure_callback,
+                        callback_index,
+                        err,
+                        RequestErrorType::Unspecified,
+                    );
+                },
+            },
+            Err(err) => unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    error_message(&err),
+                    error_type(&err),
+                );
+            },
+        };
+        panic_guard.panicked = false;
+        drop(panic_guard);
+    });
+
+    panic_guard.panicked = false;
+    drop(panic_guard);
+}
+
+/// Invoke a script with `EVALSHA`, reloading it if the server no longer has it cached.
+///
+/// After a `SCRIPT FLUSH` or a restart, `EVALSHA` fails with `NOSCRIPT`. The script is then invoked through glide-core,
+/// which loads it with `SCRIPT LOAD` before retrying `EVALSHA`, so the following invocations are cache hits again.
+/// Every reload is counted in the `script_reloads` counter of [`get_internal_counters`].
+async fn invoke_script_reloading(
+    core: &CommandExecutionCore,
+    hash: &str,
+    keys: &[&[u8]],
+    args: &[&[u8]],
+    route: Option<RoutingInfo>,
+) -> redis::RedisResult<redis::Value> {
+    let mut client = core.client.clone();
+    let mut evalsha = redis::cmd("EVALSHA");
+    evalsha.arg(hash).arg(keys.len()).arg(keys).arg(args);
+
+    match client.send_command(&mut evalsha, route.clone()).await {
+        Err(err) if err.kind() == redis::ErrorKind::NoScriptError => {
+            SCRIPT_RELOADS.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            client.invoke_script(hash, keys, args, route).await
+        }
+        result => result,
+    }
+}
+
+/// Remove all scripts from the server-side script cache with `SCRIPT FLUSH`, and report `OK`.
+///
+/// Scripts stored with [`store_script`] are kept, and are loaded again by [`invoke_script`] on their next invocation.
+///
+/// # Arguments
+/// * `client_ptr` - Pointer to the client
+/// * `callback_index` - Callback index for async response
+/// * `async_mode` - Whether to flush with `ASYNC`, otherwise `SYNC`
+/// * `route_info` - Optional route, `null` flushes all primaries in cluster mode
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer to a Client
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn script_flush(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    async_mode: bool,
+    route_info: *const RouteInfo,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let mut cmd = redis::cmd("SCRIPT");
+    cmd.arg("FLUSH")
+        .arg(if async_mode { "ASYNC" } else { "SYNC" });
+    let route = match unsafe { create_route(route_info, Some(&cmd)) } {
+        Ok(route) => route.unwrap_or(RoutingInfo::MultiNode((
+            MultipleNodeRoutingInfo::AllMasters,
+            Some(ResponsePolicy::AllSucceeded),
+        ))),
+        Err(err) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            panic_guard.panicked = false;
+            return;
+        }
+    };
+
+    unsafe {
+        spawn_command_with(&client, callback_index, cmd, Some(route), |result| {
+            result.map(|_| redis::Value::Okay)
+        })
+    };
+
+    panic_guard.panicked = false;
+}
+
+/// Execute a cluster scan request.
+///
+/// The cursor is the ID of a scan state kept by glide-core in memory, an opaque handle which is only valid within the
+/// process that started the scan, until it is removed with [`remove_cluster_scan_cursor`]. It must not be persisted to
+/// resume a scan after a restart; use [`request_scan`] on each node for a resumable scan.
+///
+/// # Safety
+/// * `client_ptr` must be a valid Client pointer from create_client
+/// * `cursor` must be "0" for initial scan or a valid cursor ID from previous scan
+/// * `args` and `arg_lengths` must be valid arrays of length `arg_count`
+/// * `args` format: [b"MATCH", pattern_arg, b"COUNT", count, b"TYPE", type] (all optional)
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn request_cluster_scan(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    cursor: *const c_char,
+    arg_count: u64,
+    args: *const usize,
+    arg_lengths: *const u64,
+) {
+    // Build client and add panic guard.
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    // Get the cluster scan state.
+    let cursor_id = match unsafe { scan_cursor_id(cursor) } {
+        Ok(cursor_id) => cursor_id,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            panic_guard.panicked = false;
+            return;
+        }
+    };
+
+    let scan_state_cursor = if cursor_id == "0" {
+        redis::ScanStateRC::new()
+    } else {
+        match glide_core::cluster_scan_container::get_cluster_scan_cursor(cursor_id.clone()) {
+            Ok(existing_cursor) => existing_cursor,
+            Err(_error) => {
+                unsafe {
+                    report_error(
+                        core.failure_callback,
+                        callback_index,
+                        format!("Invalid cursor ID: {}", cursor_id),
+                        RequestErrorType::Unspecified,
+                    );
+                }
+                panic_guard.panicked = false;
+                return;
+            }
+        }
+    };
+
+    // Build cluster scan arguments.
+    let cluster_scan_args = match unsafe {
+        build_cluster_scan_args(
+            arg_count,
+            args,
+            arg_lengths,
+            client.key_prefix.as_deref(),
+            core.failure_callback,
+            callback_index,
+        )
+    } {
+        Some(args) => args,
+        None => {
+            panic_guard.panicked = false;
+            return;
+        }
+    };
+
+    // Run cluster scan.
+    let key_prefix = client.key_prefix.clone();
+    client.spawn_tracked(async move {
+        let mut async_panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let result = core
+            .client
+            .clone()
+            .cluster_scan(&scan_state_cursor, cluster_scan_args)
+            .await;
+        match result {
+            Ok(value) => match ResponseValue::from_value(strip_scan_reply_prefix(
+                value,
+                key_prefix.as_deref(),
+            )) {
+                Ok(response) => {
+                    let ptr = Box::into_raw(Box::new(response));
+                    unsafe { (core.success_callback)(callback_index, ptr) };
+                }
+                Err(err) => unsafe {
+                    report_error(
+                        core.failure_callback,
+                        callback_index,
+                        err,
+                        RequestErrorType::Unspecified,
+                    );
+                },
+            },
+            Err(err) => unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    glide_core::errors::error_message(&err),
+                    glide_core::errors::error_type(&err),
+                );
+            },
+        };
+
+        async_panic_guard.panicked = false;
+    });
+
+    panic_guard.panicked = false;
+}
+
+/// Execute a standalone scan request.
+///
+/// The response is an array of the next cursor, "0" once the scan is complete, and the array of scanned keys,
+/// like the response of [`request_cluster_scan`].
+///
+/// Unlike a cluster scan cursor, the cursor is the server's own `SCAN` cursor, reported as a bulk string of its decimal
+/// value. It holds no state on the client, so it can be persisted and passed to a later call, also by another process,
+/// to resume the scan. The server keeps its `SCAN` guarantees across the resumption as long as the keyspace is not
+/// rehashed into a smaller table meanwhile.
+///
+/// # Safety
+/// * `client_ptr` must be a valid Client pointer from create_client
+/// * `cursor` must be "0" for initial scan or the cursor returned by the previous scan
+/// * `args` and `arg_lengths` must be valid arrays of length `arg_count`
+/// * `args` format: [b"MATCH", pattern_arg, b"COUNT", count, b"TYPE", type] (all optional)
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn request_scan(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    cursor: *const c_char,
+    arg_count: u64,
+    args: *const usize,
+    arg_lengths: *const u64,
+) {
+    // Build client and add panic guard.
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let cursor = match unsafe { CStr::from_ptr(cursor) }.to_str() {
+        Ok(cursor) => cursor,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    format!("Invalid UTF-8 in cursor ID: {err}"),
+                    RequestErrorType::Unspecified,
+                );
+            }
+            panic_guard.panicked = false;
+            return;
+        }
+    };
+
+    let Some(cmd) = (unsafe {
+        build_scan_cmd(
+            cursor,
+            arg_count,
+            args,
+            arg_lengths,
+            client.key_prefix.as_deref(),
+            core.failure_callback,
+            callback_index,
+        )
+    }) else {
+        panic_guard.panicked = false;
+        return;
+    };
+
+    let key_prefix = client.key_prefix.clone();
+    unsafe {
+        spawn_command_with(&client, callback_index, cmd, None, move |result| {
+            normalize_scan_cursor(result)
+                .map(|reply| strip_scan_reply_prefix(reply, key_prefix.as_deref()))
+        })
+    };
+
+    panic_guard.panicked = false;
+}
+
+/// Report the cursor of a `SCAN` reply as a bulk string of its decimal value, whatever type the server replied with, so
+/// that it can be persisted and passed back to [`request_scan`].
+fn normalize_scan_cursor(
+    result: redis::RedisResult<redis::Value>,
+) -> redis::RedisResult<redis::Value> {
+    use redis::Value;
+
+    let mut reply = match result? {
+        Value::Array(reply) if reply.len() == 2 => reply,
+        other => {
+            return Err(redis::RedisError::from((
+                redis::ErrorKind::TypeError,
+                "Unexpected SCAN response",
+                format!("{other:?}"),
+            )));
+        }
+    };
+    let cursor = match &reply[0] {
+        Value::BulkString(bytes) => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|cursor| cursor.parse::<u64>().ok()),
+        Value::SimpleString(cursor) => cursor.parse::<u64>().ok(),
+        Value::Int(cursor) => u64::try_from(*cursor).ok(),
+        _ => None,
+    };
+    let Some(cursor) = cursor else {
+        return Err(redis::RedisError::from((
+            redis::ErrorKind::TypeError,
+            "Invalid SCAN cursor",
+            format!("{:?}", reply[0]),
+        )));
+    };
+    reply[0] = Value::BulkString(cursor.to_string().into());
+    Ok(Value::Array(reply))
+}
+
+/// Remove `key_prefix`, the key prefix of the client, from the keys of a scan `reply`, an array of the cursor and the
+/// keys, see [`strip_key_prefix`].
+fn strip_scan_reply_prefix(mut reply: redis::Value, key_prefix: Option<&[u8]>) -> redis::Value {
+    if let (Some(prefix), redis::Value::Array(cursor_and_keys)) = (key_prefix, &mut reply)
+        && let Some(redis::Value::Array(keys)) = cursor_and_keys.get_mut(1)
+    {
+        strip_key_prefix(keys, prefix);
+    }
+    reply
+}
+
+/// Remove a cluster scan cursor from the Rust core container.
+///
+/// This should be called when the C# ClusterScanCursor is disposed or finalized
+/// to clean up resources allocated by the Rust core for cluster scan operations.
+///
+/// # Safety
+/// * `cursor_id` must be a valid C string or null
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn remove_cluster_scan_cursor(cursor_id: *const c_char) {
+    if cursor_id.is_null() {
+        return;
+    }
+
+    if let Ok(cursor_str) = unsafe { CStr::from_ptr(cursor_id).to_str() } {
+        glide_core::cluster_scan_container::remove_scan_state_cursor(cursor_str.to_string());
+    }
+}
+
+/// Scan the whole keyspace, passing every batch of keys returned by SCAN to `batch_callback` as soon as it is received.
+///
+/// Each batch is handed over to the caller and not kept by Rust, and only the cursor is kept between iterations, so the
+/// memory used is bounded by a single batch regardless of the number of keys. In cluster mode, all primaries are scanned
+/// like with [`request_cluster_scan`]. Once the scan completes, the success callback receives the number of delivered keys.
+///
+/// As with any `SCAN`, a key may be delivered more than once when the keyspace is modified during the scan.
+///
+/// # Safety
+/// * `client_ptr` must be a valid Client pointer from create_client
+/// * `args` and `arg_lengths` must be valid arrays of length `arg_count`
+/// * `args` format: [b"MATCH", pattern_arg, b"COUNT", count, b"TYPE", type] (all optional)
+/// * `batch_callback` must be a valid function pointer. See the safety documentation of [`FetchBatchCallback`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn scan_all_keys(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    arg_count: u64,
+    args: *const usize,
+    arg_lengths: *const u64,
+    batch_callback: FetchBatchCallback,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let scan = if client.cluster_mode {
+        unsafe {
+            build_cluster_scan_args(
+                arg_count,
+                args,
+                arg_lengths,
+                client.key_prefix.as_deref(),
+                core.failure_callback,
+                callback_index,
+            )
+        }
+        .map(KeyspaceScan::Cluster)
+    } else {
+        unsafe {
+            build_scan_cmd(
+                "0",
+                arg_count,
+                args,
+                arg_lengths,
+                client.key_prefix.as_deref(),
+                core.failure_callback,
+                callback_index,
+            )
+        }
+        .map(KeyspaceScan::Standalone)
+    };
+    let Some(scan) = scan else {
+        panic_guard.panicked = false;
+        return;
+    };
+
+    let key_prefix = client.key_prefix.clone();
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let result = scan_keyspace(
+            &core,
+            callback_index,
+            scan,
+            key_prefix.as_deref(),
+            batch_callback,
+        )
+        .await;
+        unsafe { report_result(&core, callback_index, result) };
+        panic_guard.panicked = false;
+    });
+
+    panic_guard.panicked = false;
+}
+
+/// The scan performed by [`scan_all_keys`].
+enum KeyspaceScan {
+    /// The first SCAN command, whose cursor argument is replaced on every iteration.
+    Standalone(redis::Cmd),
+    Cluster(redis::ClusterScanArgs),
+}
+
+/// Run `scan` to completion, see [`scan_all_keys`]. Returns the number of delivered keys, from which `key_prefix`, the
+/// key prefix of the client, is removed.
+async fn scan_keyspace(
+    core: &CommandExecutionCore,
+    callback_index: usize,
+    scan: KeyspaceScan,
+    key_prefix: Option<&[u8]>,
+    batch_callback: FetchBatchCallback,
+) -> redis::RedisResult<redis::Value> {
+    let mut delivered: i64 = 0;
+    let deliver = |mut keys: Vec<redis::Value>| -> redis::RedisResult<()> {
+        delivered += keys.len() as i64;
+        if let Some(prefix) = key_prefix {
+            strip_key_prefix(&mut keys, prefix);
+        }
+        let response = ResponseValue::from_value(redis::Value::Array(keys)).map_err(|err| {
+            redis::RedisError::from((redis::ErrorKind::TypeError, "Couldn't convert batch", err))
+        })?;
+        let ptr = Box::into_raw(Box::new(response));
+        unsafe { batch_callback(callback_index, ptr) };
+        Ok(())
+    };
+
+    match scan {
+        KeyspaceScan::Standalone(first_cmd) => {
+            // Keep the MATCH/COUNT/TYPE options, following the command name and the cursor.
+            let options = extract_cmd_args(&first_cmd).split_off(2);
+            let fetch = |cursor: String| {
+                let mut client = core.client.clone();
+                let mut cmd = redis::cmd("SCAN");
+                cmd.arg(cursor);
+                for option in &options {
+                    cmd.arg(option);
+                }
+                async move { client.send_command(&mut cmd, None).await }
+            };
+            let advance = |cursor: String| Ok((cursor != "0").then_some(cursor));
+            scan_pages("0".to_string(), fetch, advance, deliver).await?;
+        }
+        KeyspaceScan::Cluster(scan_args) => {
+            let fetch = |scan_state_cursor: redis::ScanStateRC| {
+                let mut client = core.client.clone();
+                let scan_args = scan_args.clone();
+                async move { client.cluster_scan(&scan_state_cursor, scan_args).await }
+            };
+            let advance = |cursor_id: String| {
+                if cursor_id == "finished" {
+                    return Ok(None);
+                }
+                // Take the next state out of the container right away, so that it only holds the cursors of C# scans.
+                let scan_state_cursor =
+                    glide_core::cluster_scan_container::get_cluster_scan_cursor(cursor_id.clone())
+                        .map_err(|_| {
+                            redis::RedisError::from((
+                                redis::ErrorKind::ClientError,
+                                "Invalid cursor ID",
+                                cursor_id.clone(),
+                            ))
+                        })?;
+                glide_core::cluster_scan_container::remove_scan_state_cursor(cursor_id);
+                Ok(Some(scan_state_cursor))
+            };
+            scan_pages(redis::ScanStateRC::new(), fetch, advance, deliver).await?;
+        }
+    }
+
+    Ok(redis::Value::Int(delivered))
+}
+
+/// Fetch the pages of a scan starting at `state`, handing each page to `deliver` before fetching the next one.
+///
+/// `advance` turns the cursor of a reply into the state to fetch the next page with, or `None` once the scan is complete.
+/// Only that state is kept between pages, so the memory used is bounded by a single page.
+async fn scan_pages<S, Fut>(
+    mut state: S,
+    mut fetch: impl FnMut(S) -> Fut,
+    mut advance: impl FnMut(String) -> redis::RedisResult<Option<S>>,
+    mut deliver: impl FnMut(Vec<redis::Value>) -> redis::RedisResult<()>,
+) -> redis::RedisResult<()>
+where
+    Fut: std::future::Future<Output = redis::RedisResult<redis::Value>>,
+{
+    loop {
+        let reply = fetch(state).await?;
+        let (cursor, keys): (String, Vec<redis::Value>) = redis::from_owned_redis_value(reply)?;
+        let next = advance(cursor)?;
+        deliver(keys)?;
+        match next {
+            Some(next) => state = next,
+            None => return Ok(()),
+        }
+    }
+}
+
+/// The object types accepted by the TYPE argument of SCAN, see <https://valkey.io/commands/type/>.
+const SCAN_OBJECT_TYPES: [&str; 6] = ["string", "list", "set", "zset", "hash", "stream"];
+
+/// Scan arguments parsed from C-style arrays, shared by the standalone and the cluster scan.
+struct ScanArgs {
+    /// The `MATCH` pattern, restricted to the keys with the client's key prefix, see [`prefixed_scan_pattern`].
+    pattern: Option<Vec<u8>>,
+    object_type: Option<&'static str>,
+    count: Option<u32>,
+}
+
+/// Validate the TYPE argument of a scan, returning the matching entry of [`SCAN_OBJECT_TYPES`].
+fn parse_scan_object_type(type_arg: &[u8]) -> Result<&'static str, String> {
+    let type_str =
+        std::str::from_utf8(type_arg).map_err(|_| "Invalid UTF-8 in TYPE argument".to_string())?;
+    SCAN_OBJECT_TYPES
+        .into_iter()
+        .find(|object_type| object_type.eq_ignore_ascii_case(type_str))
+        .ok_or_else(|| {
+            format!(
+                "Unknown TYPE value `{type_str}`, expected one of: {}",
+                SCAN_OBJECT_TYPES.join(", ")
+            )
+        })
+}
+
+/// Parse scan arguments from C-style arrays.
+///
+/// # Arguments
+///
+/// * `arg_count` - The number of arguments in the arrays
+/// * `args` - Pointer to an array of pointers to argument data
+/// * `arg_lengths` - Pointer to an array of argument lengths
+/// * `key_prefix` - The `key_prefix` of the client, if any
+///
+/// # Safety
+/// * `args` and `arg_lengths` must be valid arrays of length `arg_count`
+/// * Each pointer in `args` must point to valid memory of the corresponding length
+unsafe fn parse_scan_args(
+    arg_count: u64,
+    args: *const usize,
+    arg_lengths: *const u64,
+    key_prefix: Option<&[u8]>,
+) -> Result<ScanArgs, String> {
+    let mut scan_args = ScanArgs {
+        pattern: None,
+        object_type: None,
+        count: None,
+    };
+    if arg_count == 0 {
+        scan_args.pattern = key_prefix.map(|prefix| prefixed_scan_pattern(prefix, None));
+        return Ok(scan_args);
+    }
+
+    let arg_vec = unsafe { convert_string_pointer_array_to_vector(args, arg_count, arg_lengths) };
+
+    let mut iter = arg_vec.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg {
+            b"MATCH" => {
+                let pattern_arg = iter.next().ok_or("No argument following MATCH.")?;
+                if !pattern_arg.is_empty() {
+                    scan_args.pattern = Some(pattern_arg.to_vec());
+                }
+            }
+            b"TYPE" => {
+                let type_arg = iter.next().ok_or("No argument following TYPE.")?;
+                if !type_arg.is_empty() {
+                    scan_args.object_type = Some(parse_scan_object_type(type_arg)?);
+                }
+            }
+            b"COUNT" => {
+                let count_arg = iter.next().ok_or("No argument following COUNT.")?;
+                if !count_arg.is_empty() {
+                    let count_str = std::str::from_utf8(count_arg)
+                        .map_err(|_| "Invalid UTF-8 in COUNT argument")?;
+                    scan_args.count = Some(
+                        count_str
+                            .parse::<u32>()
+                            .map_err(|_| "Invalid COUNT value")?,
+                    );
+                }
+            }
+            _ => return Err("Unknown scan argument".into()),
+        }
+    }
+
+    if let Some(prefix) = key_prefix {
+        scan_args.pattern = Some(prefixed_scan_pattern(prefix, scan_args.pattern.as_deref()));
+    }
+    Ok(scan_args)
+}
+
+/// Build cluster scan arguments from C-style arrays.
+///
+/// # Arguments
+///
+/// * `arg_count` - The number of arguments in the arrays
+/// * `args` - Pointer to an array of pointers to argument data
+/// * `arg_lengths` - Pointer to an array of argument lengths
+/// * `key_prefix` - The `key_prefix` of the client, if any, which restricts the scan to the prefixed keys
+/// * `failure_callback` - Callback function to invoke on error
+/// * `callback_index` - Index to pass to the callback function
+///
+/// # Safety
+/// * `args` and `arg_lengths` must be valid arrays of length `arg_count`
+/// * Each pointer in `args` must point to valid memory of the corresponding length
+unsafe fn build_cluster_scan_args(
+    arg_count: u64,
+    args: *const usize,
+    arg_lengths: *const u64,
+    key_prefix: Option<&[u8]>,
+    failure_callback: FailureCallback,
+    callback_index: usize,
+) -> Option<redis::ClusterScanArgs> {
+    let scan_args = match unsafe { parse_scan_args(arg_count, args, arg_lengths, key_prefix) } {
+        Ok(scan_args) => scan_args,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return None;
+        }
+    };
+
+    // Build cluster scan arguments.
+    let mut cluster_scan_args_builder = redis::ClusterScanArgs::builder();
+
+    if let Some(pattern_arg) = scan_args.pattern {
+        cluster_scan_args_builder =
+            cluster_scan_args_builder.with_match_pattern(pattern_arg.as_slice());
+    }
+
+    if let Some(object_type) = scan_args.object_type {
+        cluster_scan_args_builder = cluster_scan_args_builder
+            .with_object_type(redis::ObjectType::from(object_type.to_string()));
+    }
+
+    if let Some(count) = scan_args.count {
+        cluster_scan_args_builder = cluster_scan_args_builder.with_count(count);
+    }
+
+    Some(cluster_scan_args_builder.build())
+}
+
+/// Build a standalone SCAN command from a cursor and C-style argument arrays.
+///
+/// # Arguments
+///
+/// * `cursor` - The cursor returned by the previous SCAN, or "0" to start a new scan
+/// * `arg_count` - The number of arguments in the arrays
+/// * `args` - Pointer to an array of pointers to argument data
+/// * `arg_lengths` - Pointer to an array of argument lengths
+/// * `key_prefix` - The `key_prefix` of the client, if any, which restricts the scan to the prefixed keys
+/// * `failure_callback` - Callback function to invoke on error
+/// * `callback_index` - Index to pass to the callback function
+///
+/// # Safety
+/// * `args` and `arg_lengths` must be valid arrays of length `arg_count`
+/// * Each pointer in `args` must point to valid memory of the corresponding length
+unsafe fn build_scan_cmd(
+    cursor: &str,
+    arg_count: u64,
+    args: *const usize,
+    arg_lengths: *const u64,
+    key_prefix: Option<&[u8]>,
+    failure_callback: FailureCallback,
+    callback_index: usize,
+) -> Option<redis::Cmd> {
+    // Like the cluster cursor ID, an invalid cursor must not silently restart the scan from "0".
+    let scan_args = match cursor
+        .parse::<u64>()
+        .map_err(|_| format!("Invalid cursor ID: {cursor}"))
+        .and_then(|_| unsafe { parse_scan_args(arg_count, args, arg_lengths, key_prefix) })
+    {
+        Ok(scan_args) => scan_args,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            return None;
+        }
+    };
+
+    let mut cmd = redis::cmd("SCAN");
+    cmd.arg(cursor);
+    if let Some(pattern_arg) = scan_args.pattern {
+        cmd.arg("MATCH").arg(pattern_arg);
+    }
+    if let Some(count) = scan_args.count {
+        cmd.arg("COUNT").arg(count);
+    }
+    if let Some(object_type) = scan_args.object_type {
+        cmd.arg("TYPE").arg(object_type);
+    }
+    Some(cmd)
+}
+
+/// Converts an array of pointers to strings to a vector of strings.
+///
+/// # Arguments
+///
+/// * `data` - Pointer to an array of pointers to string data
+/// * `len` - The number of strings in the array
+/// * `data_len` - Pointer to an array of string lengths
+///
+/// # Safety
+///
+/// `convert_string_pointer_array_to_vector` returns a `Vec` of u8 slice which holds pointers of C
+/// strings. The returned `Vec<&'a [u8]>` is meant to be copied into Rust code. Storing them
+/// for later use will cause the program to crash as the pointers will be freed by the caller.
+unsafe fn convert_string_pointer_array_to_vector<'a>(
+    data: *const usize,
+    len: u64,
+    data_len: *const u64,
+) -> Vec<&'a [u8]> {
+    let string_ptrs = unsafe { from_raw_parts(data, len as usize) };
+    let string_lengths = unsafe { from_raw_parts(data_len, len as usize) };
+
+    let mut result = Vec::<&[u8]>::with_capacity(string_ptrs.len());
+    for (i, &str_ptr) in string_ptrs.iter().enumerate() {
+        let slice = unsafe { from_raw_parts(str_ptr as *const u8, string_lengths[i] as usize) };
+        result.push(slice);
+    }
+
+    result
+}
+
+/// Manually refresh the IAM authentication token.
+///
+/// This function triggers an immediate refresh of the IAM token and updates the connection.
+/// It is only available if the client was created with IAM authentication.
+///
+/// # Arguments
+///
+/// * `client_ptr` - A pointer to a valid client returned from [`create_client`].
+/// * `callback_index` - A unique identifier for the callback to be called when the command completes.
+///
+/// # Safety
+///
+/// * `client_ptr` must not be `null`.
+/// * `client_ptr` must be able to be safely casted to a valid [`Arc<Client>`] via [`Arc::from_raw`]. See the safety documentation of [`Arc::from_raw`].
+/// * This function should only be called with a `client_ptr` created by [`create_client`], before [`close_client`] was called with the pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn refresh_iam_token(
+    client_ptr: *const c_void,
+    callback_index: usize,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    client.spawn_tracked(async move {
+        let mut async_panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let result = core.client.clone().refresh_iam_token().await;
+        match result {
+            Ok(()) => match ResponseValue::from_value(redis::Value::Okay) {
+                Ok(response) => {
+                    let ptr = Box::into_raw(Box::new(response));
+                    unsafe { (core.success_callback)(callback_index, ptr) };
+                }
+                Err(err) => unsafe {
+                    report_error(
+                        core.failure_callback,
+                        callback_index,
+                        err,
+                        RequestErrorType::Unspecified,
+                    );
+                },
+            },
+            Err(err) => unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    error_message(&err),
+                    error_type(&err),
+                );
+            },
+        };
+
+        async_panic_guard.panicked = false;
+    });
+
+    panic_guard.panicked = false;
+}
+
+/// Update connection password
+///
+/// # Arguments
+/// * `client_ptr` - Pointer to the client
+/// * `callback_index` - Callback index for async response
+/// * `password` - New password (null for password removal)
+/// * `immediate_auth` - Whether to authenticate immediately
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer to a Client
+/// * `password` must be a valid C string or null
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn update_connection_password(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    password_ptr: *const c_char,
+    immediate_auth: bool,
+) {
+    // Build client and add panic guard.
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    // Build password option.
+    let password = if password_ptr.is_null() {
+        None
+    } else {
+        match unsafe { CStr::from_ptr(password_ptr).to_str() } {
+            Ok(password_str) => {
+                if password_str.is_empty() {
+                    None
+                } else {
+                    Some(password_str.into())
+                }
+            }
+            Err(_) => {
+                unsafe {
+                    report_error(
+                        core.failure_callback,
+                        callback_index,
+                        "Invalid password argument".into(),
+                        RequestErrorType::Unspecified,
+                    );
+                }
+                panic_guard.panicked = false;
+                return;
+            }
+        }
+    };
+
+    // Run password update.
+    client.spawn_tracked(async move {
+        let mut async_panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let result = core
+            .client
+            .clone()
+            .update_connection_password(password, immediate_auth)
+            .await;
+        match result {
+            Ok(value) => match ResponseValue::from_value(value) {
+                Ok(response) => {
+                    let ptr = Box::into_raw(Box::new(response));
+                    unsafe { (core.success_callback)(callback_index, ptr) };
+                }
+                Err(err) => unsafe {
+                    report_error(
+                        core.failure_callback,
+                        callback_index,
+                        err,
+                        RequestErrorType::Unspecified,
+                    );
+                },
+            },
+            Err(err) => unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    error_message(&err),
+                    error_type(&err),
+                );
+            },
+        };
+
+        async_panic_guard.panicked = false;
+    });
+
+    panic_guard.panicked = false;
+}
+
+/// Update the connection username and password together.
+///
+/// glide-core can only change the password at runtime: the username is fixed when the client is created and used for
+/// every reconnection. Changing the username would leave reconnections authenticating as the old user with the new
+/// password, so it is rejected with an error, and the client must be recreated with the new credentials instead.
+/// When `username` is null or matches the configured username (`default` if none was configured), this behaves like
+/// [`update_connection_password`].
+///
+/// # Safety
+/// * `client_ptr` must not be `null` and must be a valid pointer to a Client
+/// * `username` and `password` must be valid C strings or null
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn update_connection_credentials(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    username_ptr: *const c_char,
+    password_ptr: *const c_char,
+    immediate_auth: bool,
+) {
+    assert!(!client_ptr.is_null());
+    let client = unsafe { &*(client_ptr as *const Client) };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    if !username_ptr.is_null() {
+        let error = match unsafe { CStr::from_ptr(username_ptr).to_str() } {
+            Ok(username) if username == client.username.as_deref().unwrap_or("default") => None,
+            Ok(username) => Some(format!(
+                "Changing the username at runtime is not supported, create a new client to authenticate as `{username}`"
+            )),
+            Err(_) => Some("Invalid username argument".to_string()),
+        };
+        if let Some(error) = error {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    error,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            panic_guard.panicked = false;
+            return;
+        }
+    }
+
+    panic_guard.panicked = false;
+    unsafe { update_connection_password(client_ptr, callback_index, password_ptr, immediate_auth) };
+}
+
+// ========================================================================================
+// Safe Collection Fetch
+// ========================================================================================
+
+/// Cardinality above which [`safe_smembers`] and [`safe_hgetall`] iterate, when the caller passes `0`.
+const DEFAULT_SAFE_FETCH_THRESHOLD: u64 = 1000;
+
+/// The collection type fetched by [`safe_fetch`].
+#[derive(Clone, Copy)]
+enum SafeFetchKind {
+    Set,
+    Hash,
+}
+
+impl SafeFetchKind {
+    /// Returns the commands to get the cardinality, fetch all elements at once, and iterate the elements.
+    fn commands(self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            SafeFetchKind::Set => ("SCARD", "SMEMBERS", "SSCAN"),
+            SafeFetchKind::Hash => ("HLEN", "HGETALL", "HSCAN"),
+        }
+    }
+}
+
+/// Fetch all members of a set without blocking the server on large sets.
+///
+/// If the set has at most `threshold` members, they are fetched at once with `SMEMBERS`.
+/// Otherwise they are iterated with `SSCAN`, using `threshold` as the `COUNT` hint. See [`safe_fetch`] for how results are delivered.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer to a Client.
+/// * `key` must point to `key_len` bytes.
+/// * `batch_callback` is optional, but if given it must be a valid function pointer. See the safety documentation of [`FetchBatchCallback`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn safe_smembers(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: usize,
+    threshold: u64,
+    batch_callback: Option<FetchBatchCallback>,
+) {
+    unsafe {
+        safe_fetch(
+            client_ptr,
+            callback_index,
+            key,
+            key_len,
+            threshold,
+            batch_callback,
+            SafeFetchKind::Set,
+        )
+    };
+}
+
+/// Fetch all fields and values of a hash without blocking the server on large hashes.
+///
+/// If the hash has at most `threshold` fields, they are fetched at once with `HGETALL`.
+/// Otherwise they are iterated with `HSCAN`, using `threshold` as the `COUNT` hint. See [`safe_fetch`] for how results are delivered.
+///
+/// # Safety
+/// * See the safety documentation of [`safe_smembers`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn safe_hgetall(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: usize,
+    threshold: u64,
+    batch_callback: Option<FetchBatchCallback>,
+) {
+    unsafe {
+        safe_fetch(
+            client_ptr,
+            callback_index,
+            key,
+            key_len,
+            threshold,
+            batch_callback,
+            SafeFetchKind::Hash,
+        )
+    };
+}
+
+/// Shared implementation of [`safe_smembers`] and [`safe_hgetall`].
+///
+/// A `threshold` of `0` uses [`DEFAULT_SAFE_FETCH_THRESHOLD`]. Results are delivered as follows:
+/// * Below the threshold, the success callback receives the whole collection, as the single-shot command would.
+/// * Above the threshold with a `batch_callback`, every scanned batch is passed to `batch_callback`, and the success callback
+///   receives the number of delivered elements once iteration completes.
+/// * Above the threshold without a `batch_callback`, the batches are accumulated and the success callback receives the whole collection.
+///
+/// As with any `SCAN`, an element may be delivered more than once when the collection is modified during iteration.
+///
+/// # Safety
+/// * See the safety documentation of [`safe_smembers`].
+#[allow(clippy::too_many_arguments)]
+unsafe fn safe_fetch(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: usize,
+    threshold: u64,
+    batch_callback: Option<FetchBatchCallback>,
+    kind: SafeFetchKind,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let key = client.prefixed_key(unsafe { from_raw_parts(key, key_len) });
+    let threshold = if threshold == 0 {
+        DEFAULT_SAFE_FETCH_THRESHOLD
+    } else {
+        threshold
+    };
+
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let result =
+            safe_fetch_value(&core, callback_index, key, threshold, batch_callback, kind).await;
+        unsafe { report_result(&core, callback_index, result) };
+        panic_guard.panicked = false;
+    });
+
+    panic_guard.panicked = false;
+}
+
+/// Fetch the collection at `key`, see [`safe_fetch`].
+async fn safe_fetch_value(
+    core: &CommandExecutionCore,
+    callback_index: usize,
+    key: Vec<u8>,
+    threshold: u64,
+    batch_callback: Option<FetchBatchCallback>,
+    kind: SafeFetchKind,
+) -> redis::RedisResult<redis::Value> {
+    let (count_cmd, fetch_cmd, scan_cmd) = kind.commands();
+    let mut client = core.client.clone();
+
+    let count = client
+        .send_command(&mut redis::cmd(count_cmd).arg(&key).to_owned(), None)
+        .await?;
+    let count: u64 = redis::from_owned_redis_value(count)?;
+    if count <= threshold {
+        return client
+            .send_command(&mut redis::cmd(fetch_cmd).arg(&key).to_owned(), None)
+            .await;
+    }
+
+    let mut cursor = "0".to_string();
+    let mut delivered: i64 = 0;
+    let mut accumulated = Vec::new();
+    loop {
+        let reply = client
+            .send_command(
+                &mut redis::cmd(scan_cmd)
+                    .arg(&key)
+                    .arg(&cursor)
+                    .arg("COUNT")
+                    .arg(threshold)
+                    .to_owned(),
+                None,
+            )
+            .await?;
+        let (next_cursor, elements): (String, Vec<redis::Value>) =
+            redis::from_owned_redis_value(reply)?;
+        cursor = next_cursor;
+
+        let batch = match kind {
+            SafeFetchKind::Set => {
+                delivered += elements.len() as i64;
+                redis::Value::Array(elements)
+            }
+            SafeFetchKind::Hash => {
+                let mut pairs = Vec::with_capacity(elements.len() / 2);
+                let mut elements = elements.into_iter();
+                while let (Some(field), Some(value)) = (elements.next(), elements.next()) {
+                    pairs.push((field, value));
+                }
+                delivered += pairs.len() as i64;
+                redis::Value::Map(pairs)
+            }
+        };
+
+        match batch_callback {
+            Some(batch_callback) => {
+                let response = ResponseValue::from_value(batch).map_err(|err| {
+                    redis::RedisError::from((
+                        redis::ErrorKind::TypeError,
+                        "Couldn't convert batch",
+                        err,
+                    ))
+                })?;
+                let ptr = Box::into_raw(Box::new(response));
+                unsafe { batch_callback(callback_index, ptr) };
+            }
+            None => accumulated.push(batch),
+        }
+
+        if cursor == "0" {
+            break;
+        }
+    }
+
+    if batch_callback.is_some() {
+        return Ok(redis::Value::Int(delivered));
+    }
+    Ok(match kind {
+        SafeFetchKind::Set => redis::Value::Set(
+            accumulated
+                .into_iter()
+                .flat_map(|batch| match batch {
+                    redis::Value::Array(elements) => elements,
+                    _ => Vec::new(),
+                })
+                .collect(),
+        ),
+        SafeFetchKind::Hash => redis::Value::Map(
+            accumulated
+                .into_iter()
+                .flat_map(|batch| match batch {
+                    redis::Value::Map(pairs) => pairs,
+                    _ => Vec::new(),
+                })
+                .collect(),
+        ),
+    })
+}
+
+// ========================================================================================
+// Server Management
+// ========================================================================================
+
+/// Estimate the memory used by a set of keys with `MEMORY USAGE`.
+///
+/// The commands are sent as a non-atomic batch, which is split by the slots of the keys so that every node only receives the commands for
+/// its own keys. Reports a map with:
+/// * `total`: the sum of the estimates in bytes, excluding missing keys.
+/// * `keys`: a map of every existing key to its estimate in bytes.
+/// * `missing`: the array of keys which don't exist.
+///
+/// # Parameters
+/// * `keys_count`: Number of keys in the keys array.
+/// * `keys`: Array of pointers to key data.
+/// * `keys_len`: Array of key lengths.
+/// * `has_samples`, `samples`: The `SAMPLES` argument of `MEMORY USAGE`, the server default of 5 is used if not given.
+///
+/// # Safety
+/// * `client_ptr` must not be `null` and must be obtained from [`create_client`].
+/// * `keys` and `keys_len` must be valid arrays of size `keys_count`, or both null if `keys_count` is 0.
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn estimate_memory(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    keys_count: usize,
+    keys: *const usize,
+    keys_len: *const usize,
+    has_samples: bool,
+    samples: u32,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let keys: Vec<Vec<u8>> = unsafe {
+        ffi::convert_byte_array_to_slices(keys as *const *const u8, keys_count, keys_len)
+    }
+    .into_iter()
+    .map(<[u8]>::to_vec)
+    .collect();
+    let mut pipeline = redis::Pipeline::with_capacity(keys.len());
+    for key in &keys {
+        let mut cmd = redis::cmd("MEMORY");
+        cmd.arg("USAGE").arg(client.prefixed_key(key));
+        if has_samples {
+            cmd.arg("SAMPLES").arg(samples);
+        }
+        pipeline.add_command(cmd);
+    }
+
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let result = estimate_memory_value(&core, keys, pipeline).await;
+        unsafe { report_result(&core, callback_index, result) };
+        panic_guard.panicked = false;
+    });
+
+    panic_guard.panicked = false;
+}
+
+/// Build the map reported by [`estimate_memory`].
+async fn estimate_memory_value(
+    core: &CommandExecutionCore,
+    keys: Vec<Vec<u8>>,
+    pipeline: redis::Pipeline,
+) -> redis::RedisResult<redis::Value> {
+    let estimates = if keys.is_empty() {
+        Vec::new()
+    } else {
+        let reply = core
+            .client
+            .clone()
+            .send_pipeline(
+                &pipeline,
+                None,
+                true,
+                core.batch_timeout(None),
+                glide_core::client::PipelineRetryStrategy::new(false, false),
+            )
+            .await?;
+        redis::from_owned_redis_value::<Vec<Option<i64>>>(reply)?
+    };
+
+    let mut total: i64 = 0;
+    let mut usages = Vec::new();
+    let mut missing = Vec::new();
+    for (key, estimate) in keys.into_iter().zip(estimates) {
+        match estimate {
+            Some(bytes) => {
+                total += bytes;
+                usages.push((redis::Value::BulkString(key), redis::Value::Int(bytes)));
+            }
+            None => missing.push(redis::Value::BulkString(key)),
+        }
+    }
+
+    Ok(redis::Value::Map(vec![
+        (
+            redis::Value::SimpleString("total".into()),
+            redis::Value::Int(total),
+        ),
+        (
+            redis::Value::SimpleString("keys".into()),
+            redis::Value::Map(usages),
+        ),
+        (
+            redis::Value::SimpleString("missing".into()),
+            redis::Value::Array(missing),
+        ),
+    ]))
+}
+
+/// Get the cluster slot of a key, as used to route commands.
+///
+/// Hash tags are respected: if the key contains a non-empty `{...}` section, only that section is hashed, so `{user}:a`
+/// and `{user}:b` share a slot.
+///
+/// # Safety
+/// * `key` must be a valid array of `key_len` bytes, or may be null if `key_len` is 0.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn compute_slot(key: *const u8, key_len: usize) -> u16 {
+    let key = if key.is_null() {
+        &[]
+    } else {
+        unsafe { from_raw_parts(key, key_len) }
+    };
+    redis::cluster_topology::get_slot(key)
+}
+
+/// Rename `key` to `new_key` with `RENAME`, and report `OK`.
+///
+/// In cluster mode, both keys must map to the same slot. This is checked before sending the command, and a clear error is
+/// reported instead of the server's `CROSSSLOT` error, see [`check_same_slot`].
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer to a Client
+/// * `key` and `new_key` must be valid arrays of `key_len` and `new_key_len` bytes
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn rename(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: usize,
+    new_key: *const u8,
+    new_key_len: usize,
+) {
+    let (key, new_key) = unsafe {
+        (
+            from_raw_parts(key, key_len),
+            from_raw_parts(new_key, new_key_len),
+        )
+    };
+    unsafe {
+        same_slot_command(
+            client_ptr,
+            callback_index,
+            "RENAME",
+            &[key, new_key],
+            |result| result,
+        )
+    };
+}
+
+/// Rename `key` to `new_key` with `RENAMENX` if `new_key` does not exist, and report `1` if it was renamed, `0` otherwise.
+///
+/// In cluster mode, both keys must map to the same slot, as for [`rename`].
+///
+/// # Safety
+/// * See the safety documentation of [`rename`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn rename_nx(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: usize,
+    new_key: *const u8,
+    new_key_len: usize,
+) {
+    let (key, new_key) = unsafe {
+        (
+            from_raw_parts(key, key_len),
+            from_raw_parts(new_key, new_key_len),
+        )
+    };
+    unsafe {
+        same_slot_command(
+            client_ptr,
+            callback_index,
+            "RENAMENX",
+            &[key, new_key],
+            |result| result,
+        )
+    };
+}
+
+/// Move `member` from the set at `source` to the set at `destination` with `SMOVE`, and report `true` if it was moved,
+/// `false` if it is not a member of `source`.
+///
+/// In cluster mode, both keys must map to the same slot, as for [`rename`].
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer to a Client
+/// * `source`, `destination` and `member` must be valid arrays of `source_len`, `destination_len` and `member_len` bytes
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn smove(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    source: *const u8,
+    source_len: usize,
+    destination: *const u8,
+    destination_len: usize,
+    member: *const u8,
+    member_len: usize,
+) {
+    let args = unsafe {
+        [
+            from_raw_parts(source, source_len),
+            from_raw_parts(destination, destination_len),
+            from_raw_parts(member, member_len),
+        ]
+    };
+    unsafe {
+        same_slot_command(client_ptr, callback_index, "SMOVE", &args, |result| {
+            result.map(|value| match value {
+                redis::Value::Int(moved) => redis::Value::Boolean(moved == 1),
+                value => value,
+            })
+        })
+    };
+}
+
+/// Pop an element from the `where_from` end of the list at `source` and push it to the `where_to` end of the list at
+/// `destination` with `LMOVE`, and report the element, or null if `source` is empty.
+///
+/// In cluster mode, both keys must map to the same slot, as for [`rename`].
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer to a Client
+/// * `source` and `destination` must be valid arrays of `source_len` and `destination_len` bytes
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn lmove(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    source: *const u8,
+    source_len: usize,
+    destination: *const u8,
+    destination_len: usize,
+    where_from: ListDirection,
+    where_to: ListDirection,
+) {
+    let args = unsafe {
+        [
+            from_raw_parts(source, source_len),
+            from_raw_parts(destination, destination_len),
+            where_from.as_arg().as_bytes(),
+            where_to.as_arg().as_bytes(),
+        ]
+    };
+    unsafe { same_slot_command(client_ptr, callback_index, "LMOVE", &args, |result| result) };
+}
+
+/// Same as [`lmove`], but with `BLMOVE`, blocking until an element is available or `timeout_secs` elapsed, and reporting null on timeout.
+///
+/// A `timeout_secs` of `0` blocks forever. As for any blocking command, glide-core extends the request timeout by the
+/// blocking timeout, so the request does not time out before the server does.
+///
+/// # Safety
+/// * See the safety documentation of [`lmove`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn blmove(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    source: *const u8,
+    source_len: usize,
+    destination: *const u8,
+    destination_len: usize,
+    where_from: ListDirection,
+    where_to: ListDirection,
+    timeout_secs: f64,
+) {
+    if !timeout_secs.is_finite() || timeout_secs < 0.0 {
+        let core = unsafe { &(*(client_ptr as *const Client)).core };
+        unsafe {
+            report_error(
+                core.failure_callback,
+                callback_index,
+                format!(
+                    "BLMOVE timeout must be a non-negative number of seconds, got {timeout_secs}"
+                ),
+                RequestErrorType::Unspecified,
+            );
+        }
+        return;
+    }
+
+    let timeout = timeout_secs.to_string();
+    let args = unsafe {
+        [
+            from_raw_parts(source, source_len),
+            from_raw_parts(destination, destination_len),
+            where_from.as_arg().as_bytes(),
+            where_to.as_arg().as_bytes(),
+            timeout.as_bytes(),
+        ]
+    };
+    unsafe { same_slot_command(client_ptr, callback_index, "BLMOVE", &args, |result| result) };
+}
+
+/// Send the multi-key command `name` with `args`, built by an FFI helper, after checking that its keys map to the same
+/// slot in cluster mode. The reply is passed through `process` before it is reported, as in [`spawn_command_with`].
+///
+/// The key prefix of the client is applied first, since it changes the slot of the keys, see [`apply_key_prefix`].
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer to a Client
+unsafe fn same_slot_command<F>(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    name: &str,
+    args: &[&[u8]],
+    process: F,
+) where
+    F: FnOnce(redis::RedisResult<redis::Value>) -> redis::RedisResult<redis::Value>
+        + Send
+        + 'static,
+{
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let mut cmd = redis::cmd(name);
+    if let Err(err) =
+        apply_key_prefix(&mut cmd, args, client.key_prefix.as_deref()).and_then(|()| {
+            match client.cluster_mode {
+                true => check_same_slot(&cmd),
+                false => Ok(()),
+            }
+        })
+    {
+        unsafe {
+            report_error(
+                core.failure_callback,
+                callback_index,
+                err,
+                RequestErrorType::Unspecified,
+            );
+        }
+        panic_guard.panicked = false;
+        return;
+    }
+
+    unsafe { spawn_command_with(&client, callback_index, cmd, None, process) };
+
+    panic_guard.panicked = false;
+}
+
+/// Issue `FAILOVER` with an optional target replica and `FORCE`/`ABORT`/`TIMEOUT` options.
+///
+/// The options are validated before anything is sent, see [`create_failover_cmd`].
+/// Validation errors are reported through the failure callback.
+///
+/// # Arguments
+/// * `client_ptr` - Pointer to the client
+/// * `callback_index` - Callback index for async response
+/// * `host` - Host of the target replica for `TO`, or `null` for no target
+/// * `port` - Port of the target replica for `TO`, or `0` for no target
+/// * `force` - Whether to add `FORCE`. Requires a target and a timeout
+/// * `abort` - Whether to add `ABORT`. Cannot be combined with any other option
+/// * `has_timeout` - Whether `timeout_ms` is set
+/// * `timeout_ms` - `TIMEOUT` in milliseconds
+/// * `route_info` - Optional route, `null` routes to the primary
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer to a Client
+/// * `host` must be a valid C string or null
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn failover(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    host: *const c_char,
+    port: u16,
+    force: bool,
+    abort: bool,
+    has_timeout: bool,
+    timeout_ms: u32,
+    route_info: *const RouteInfo,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let host = if host.is_null() {
+        None
+    } else {
+        match unsafe { CStr::from_ptr(host) }.to_str() {
+            Ok(host) => Some(host.to_owned()),
+            Err(_) => {
+                unsafe {
+                    report_error(
+                        core.failure_callback,
+                        callback_index,
+                        "Invalid UTF-8 in FAILOVER host".into(),
+                        RequestErrorType::Unspecified,
+                    );
+                }
+                panic_guard.panicked = false;
+                return;
+            }
+        }
+    };
+    let port = (port != 0).then_some(port);
+
+    let cmd = match create_failover_cmd(host, port, force, abort, has_timeout.then_some(timeout_ms))
+    {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            panic_guard.panicked = false;
+            return;
+        }
+    };
+
+    let route = match unsafe { create_route(route_info, Some(&cmd)) } {
+        Ok(route) => route,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            panic_guard.panicked = false;
+            return;
+        }
+    };
+
+    unsafe { spawn_command(&client, callback_index, cmd, route) };
+
+    panic_guard.panicked = false;
+}
+
+/// Issue `WAITAOF numlocal numreplicas timeout` and report the acknowledgements as a map.
+///
+/// On success the callback receives a map with `local` (number of local AOF fsyncs, 0 or 1)
+/// and `replicas` (number of replicas that acknowledged the fsync).
+/// `WAITAOF` requires Valkey/Redis 7.2 or later; older servers are reported with a dedicated error
+/// instead of the generic unknown command reply.
+///
+/// # Arguments
+/// * `client_ptr` - Pointer to the client
+/// * `callback_index` - Callback index for async response
+/// * `num_local` - Number of local fsyncs to wait for (0 or 1)
+/// * `num_replicas` - Number of replica fsyncs to wait for
+/// * `timeout_ms` - Timeout in milliseconds, `0` blocks forever
+/// * `route_info` - Optional route, `null` routes to the primary
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer to a Client
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn wait_aof(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    num_local: u32,
+    num_replicas: u32,
+    timeout_ms: u64,
+    route_info: *const RouteInfo,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let cmd = match create_wait_aof_cmd(num_local, num_replicas, timeout_ms) {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            panic_guard.panicked = false;
+            return;
+        }
+    };
+
+    let route = match unsafe { create_route(route_info, Some(&cmd)) } {
+        Ok(route) => route,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            panic_guard.panicked = false;
+            return;
+        }
+    };
+
+    unsafe { spawn_command_with(&client, callback_index, cmd, route, parse_wait_aof_response) };
+
+    panic_guard.panicked = false;
 }
 
-impl From<Level> for logger_core::Level {
-    fn from(level: Level) -> logger_core::Level {
-        match level {
-            Level::Error => logger_core::Level::Error,
-            Level::Warn => logger_core::Level::Warn,
-            Level::Info => logger_core::Level::Info,
-            Level::Debug => logger_core::Level::Debug,
-            Level::Trace => logger_core::Level::Trace,
-            Level::Off => logger_core::Level::Off,
+/// Get the current config epoch of the cluster, the `cluster_current_epoch` field of `CLUSTER INFO`.
+///
+/// The epoch is bumped by every failover and slot migration, so comparing it between calls cheaply tells whether the
+/// topology may have changed. The reply is reported as an `Int`. Fails for a standalone client.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer to a Client
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn get_cluster_epoch(
+    client_ptr: *const c_void,
+    callback_index: usize,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    if !client.cluster_mode {
+        unsafe {
+            report_error(
+                core.failure_callback,
+                callback_index,
+                "get_cluster_epoch requires a cluster client".into(),
+                RequestErrorType::Unspecified,
+            );
         }
+        panic_guard.panicked = false;
+        return;
     }
+
+    let mut cmd = redis::cmd("CLUSTER");
+    cmd.arg("INFO");
+    unsafe { spawn_command_with(&client, callback_index, cmd, None, parse_cluster_epoch) };
+
+    panic_guard.panicked = false;
 }
 
-/// # Safety
+/// Get the client-side caching state of the connection, the reply of `CLIENT TRACKINGINFO`.
 ///
-/// * `message` and `log_identifier` must not be `null`.
-/// * `message` and `log_identifier` must be able to be safely casted to a valid [`CStr`] via [`CStr::from_ptr`]. See the safety documentation of [`CStr::from_ptr`].
+/// The reply is reported as a map of `flags` (an array, e.g. `on` and `bcast`), `redirect` (the id of the connection
+/// receiving the invalidations, `-1` if none, `0` if invalidations are sent to the connection itself) and `prefixes`
+/// (the prefixes tracked in broadcasting mode), regardless of the protocol. glide-core multiplexes all commands to a
+/// node over one connection, so the state is the one of that connection.
+///
+/// # Arguments
+/// * `client_ptr` - Pointer to the client
+/// * `callback_index` - Callback index for async response
+/// * `route_info` - Optional route, `null` sends the command to a random node in cluster mode
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer to a Client
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+#[allow(rustdoc::private_intra_doc_links)]
 #[unsafe(no_mangle)]
-#[allow(improper_ctypes_definitions)]
-pub unsafe extern "C" fn log(
-    log_level: Level,
-    log_identifier: *const c_char,
-    message: *const c_char,
+pub unsafe extern "C-unwind" fn get_tracking_info(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    route_info: *const RouteInfo,
 ) {
-    let log_id = match unsafe { CStr::from_ptr(log_identifier) }.to_str() {
-        Ok(s) => s,
-        Err(_) => {
-            // Cannot log with invalid UTF-8 identifier; silently skip.
-            return;
-        }
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
     };
-    let msg = match unsafe { CStr::from_ptr(message) }.to_str() {
-        Ok(s) => s,
-        Err(_) => {
-            // Cannot log with invalid UTF-8 message; silently skip.
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let mut cmd = redis::cmd("CLIENT");
+    cmd.arg("TRACKINGINFO");
+    let route = match unsafe { create_route(route_info, Some(&cmd)) } {
+        Ok(route) => route,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            panic_guard.panicked = false;
             return;
         }
     };
-    logger_core::log(log_level.into(), log_id, msg);
+
+    unsafe { spawn_command_with(&client, callback_index, cmd, route, parse_tracking_info) };
+
+    panic_guard.panicked = false;
 }
 
-/// Initializes the logger with the given level and optional file name.
+/// Get information about a stream with `XINFO STREAM`, optionally with `FULL`.
 ///
-/// Returns a null pointer on success, or a pointer to a C string error message on failure.
-/// On success, the resolved log level is written to `*level_out`.
-/// The caller is responsible for freeing the error message using `free_string`.
+/// The reply is reported as a map of the stream's fields, shaped the same regardless of the protocol and server version,
+/// see [`parse_xinfo_stream`]. The command is sent to the primary owning `key`.
 ///
-/// # Safety
+/// # Arguments
+/// * `client_ptr` - Pointer to the client
+/// * `callback_index` - Callback index for async response
+/// * `key` - The stream key
+/// * `full` - Whether to send `XINFO STREAM key FULL`, which reports the entries, consumer groups and consumers
 ///
-/// * `file_name` may be `null` (logs to console). If non-null, it must be a valid C string.
-/// * `level_out` must not be `null` and must point to a valid writable `Level`.
+/// # Safety
+/// * `client_ptr` must be a valid pointer to a Client
+/// * `key` must point to `key_len` bytes.
+#[allow(rustdoc::private_intra_doc_links)]
 #[unsafe(no_mangle)]
-#[allow(improper_ctypes_definitions)]
-pub unsafe extern "C" fn init_logger(
-    level: Option<Level>,
-    file_name: *const c_char,
-    level_out: *mut Level,
-) -> *mut c_char {
-    let file_name_as_str = if file_name.is_null() {
-        None
-    } else {
-        match unsafe { CStr::from_ptr(file_name) }.to_str() {
-            Ok(s) => Some(s),
-            Err(e) => {
-                let error_msg = format!("Invalid UTF-8 in file name: {e}");
-                return CString::new(error_msg).unwrap().into_raw();
+pub unsafe extern "C-unwind" fn xinfo_stream(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: usize,
+    full: bool,
+) {
+    let key = unsafe { from_raw_parts(key, key_len) };
+    unsafe {
+        xinfo(client_ptr, callback_index, key, parse_xinfo_stream, |key| {
+            let mut cmd = redis::cmd("XINFO");
+            cmd.arg("STREAM").arg(key);
+            if full {
+                cmd.arg("FULL");
             }
-        }
+            cmd
+        })
     };
-
-    let logger_level = logger_core::init(level.map(|level| level.into()), file_name_as_str);
-    unsafe { *level_out = logger_level.into() };
-    std::ptr::null_mut()
 }
 
-#[repr(C)]
-pub struct ScriptHashBuffer {
-    pub ptr: *mut u8,
-    pub len: usize,
-    pub capacity: usize,
+/// Get the consumer groups of a stream with `XINFO GROUPS`.
+///
+/// The reply is reported as an array of maps, one per group, shaped the same regardless of the protocol and server
+/// version, see [`parse_xinfo_groups`]. The command is sent to the primary owning `key`.
+///
+/// # Safety
+/// * See the safety documentation of [`xinfo_stream`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn xinfo_groups(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: *const u8,
+    key_len: usize,
+) {
+    let key = unsafe { from_raw_parts(key, key_len) };
+    unsafe {
+        xinfo(client_ptr, callback_index, key, parse_xinfo_groups, |key| {
+            let mut cmd = redis::cmd("XINFO");
+            cmd.arg("GROUPS").arg(key);
+            cmd
+        })
+    };
 }
 
-/// Store a Lua script in the script cache and return its SHA1 hash.
+/// Shared implementation of [`xinfo_stream`] and [`xinfo_groups`], sending the command built by `cmd` for the prefixed
+/// `key` to the primary owning the prefixed key.
 ///
-/// # Parameters
+/// # Safety
+/// * See the safety documentation of [`xinfo_stream`].
+unsafe fn xinfo(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    key: &[u8],
+    process: fn(redis::RedisResult<redis::Value>) -> redis::RedisResult<redis::Value>,
+    cmd: impl FnOnce(&[u8]) -> redis::Cmd,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: client.core.failure_callback,
+        callback_index,
+    };
+
+    let key = client.prefixed_key(key);
+    let route = RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(
+        redis::cluster_routing::Route::new(
+            redis::cluster_topology::get_slot(&key),
+            redis::cluster_routing::SlotAddr::Master,
+        ),
+    ));
+    unsafe { spawn_command_with(&client, callback_index, cmd(&key), Some(route), process) };
+
+    panic_guard.panicked = false;
+}
+
+/// Write on the primary, wait for replicas to acknowledge it, then read from a replica, and report the read's reply.
 ///
-/// * `script_bytes`: Pointer to the script bytes.
-/// * `script_len`: Length of the script in bytes.
+/// The three stages run in order and stop at the first failure: the write command is sent to the primary, `WAIT num_replicas timeout`
+/// is sent to the same primary, then the read command is sent to a replica. The reported error names the failed stage, e.g.
+/// `wait stage failed: ...`. If `WAIT` returns fewer acknowledgements than `num_replicas`, the wait stage fails and the read is not sent.
 ///
-/// # Returns
+/// In cluster mode, both commands must address the same slot, the write is routed to the slot's primary and the read to one of its replicas.
+/// In standalone mode, the write and `WAIT` go to the primary, and the read is routed by the client's `ReadFrom` strategy, so it reaches
+/// a replica only if the strategy prefers replicas.
 ///
-/// A pointer to a `ScriptHashBuffer` containing the SHA1 hash of the script.
-/// The caller is responsible for freeing this memory using [`free_script_hash_buffer`].
+/// # Arguments
+/// * `client_ptr` - Pointer to the client
+/// * `callback_index` - Callback index for async response
+/// * `write_cmd_ptr` - The write command
+/// * `read_cmd_ptr` - The read command, its reply is reported
+/// * `num_replicas` - Number of replicas which must acknowledge the write
+/// * `timeout_ms` - `WAIT` timeout in milliseconds, `0` blocks forever
 ///
 /// # Safety
-///
-/// * `script_bytes` must point to `script_len` consecutive properly initialized bytes.
-/// * The returned buffer must be freed by the caller using [`free_script_hash_buffer`].
+/// * `client_ptr` must be a valid pointer to a Client
+/// * `write_cmd_ptr` and `read_cmd_ptr` must not be `null` and must be able to be safely casted to a valid [`CmdInfo`]. See the safety documentation of [`create_cmd`].
+#[allow(rustdoc::private_intra_doc_links)]
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn store_script(
-    script_bytes: *const u8,
-    script_len: usize,
-) -> *mut ScriptHashBuffer {
-    let script = unsafe { std::slice::from_raw_parts(script_bytes, script_len) };
-    let hash = glide_core::scripts_container::add_script(script);
-    let mut hash = std::mem::ManuallyDrop::new(hash);
-    let script_hash_buffer = ScriptHashBuffer {
-        ptr: hash.as_mut_ptr(),
-        len: hash.len(),
-        capacity: hash.capacity(),
+pub unsafe extern "C-unwind" fn write_then_consistent_read(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    write_cmd_ptr: *const CmdInfo,
+    read_cmd_ptr: *const CmdInfo,
+    num_replicas: u32,
+    timeout_ms: u64,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let commands = unsafe { client.create_cmd(write_cmd_ptr) }
+        .map_err(|err| format!("Invalid write command: {err}"))
+        .and_then(|write| {
+            let read = unsafe { client.create_cmd(read_cmd_ptr) }
+                .map_err(|err| format!("Invalid read command: {err}"))?;
+            Ok((write, read))
+        })
+        .and_then(|(write, read)| {
+            let routes = consistent_read_routes(client.cluster_mode, &write, &read)?;
+            Ok((write, read, routes))
+        });
+    let (mut write, mut read, (write_route, read_route)) = match commands {
+        Ok(commands) => commands,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
+            panic_guard.panicked = false;
+            return;
+        }
     };
-    Box::into_raw(Box::new(script_hash_buffer))
-}
 
-/// Free a `ScriptHashBuffer` obtained from [`store_script`].
-///
-/// # Parameters
-///
-/// * `buffer`: Pointer to the `ScriptHashBuffer`.
-///
-/// # Safety
-///
-/// * `buffer` must be a pointer returned from [`store_script`].
-/// * This function must be called exactly once per buffer.
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn free_script_hash_buffer(buffer: *mut ScriptHashBuffer) {
-    if buffer.is_null() {
-        return;
-    }
-    let buffer = unsafe { Box::from_raw(buffer) };
-    let _hash = unsafe { String::from_raw_parts(buffer.ptr, buffer.len, buffer.capacity) };
+    let request_type = unsafe { decompression_type(read_cmd_ptr, &read) };
+
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let result = write_then_consistent_read_value(
+            &core,
+            &mut write,
+            &mut read,
+            write_route,
+            read_route,
+            num_replicas,
+            timeout_ms,
+        )
+        .await;
+        unsafe { report_command_result(&core, callback_index, result, request_type) };
+        panic_guard.panicked = false;
+        drop(panic_guard);
+    });
+
+    panic_guard.panicked = false;
 }
 
-/// Remove a script from the script cache.
-///
-/// Returns a null pointer if it succeeds and a C string error message if it fails.
-///
-/// # Parameters
-///
-/// * `hash`: The SHA1 hash of the script to remove as a byte array.
-/// * `len`: The length of `hash`.
-///
-/// # Returns
+/// Resolve the routes of the write and read commands of [`write_then_consistent_read`].
 ///
-/// A null pointer on success, or a pointer to a C string error message on failure.
-/// The caller is responsible for freeing the error message using `free_string`.
-///
-/// # Safety
-///
-/// * `hash` must be a valid pointer to a UTF-8 string.
-/// * The returned error pointer (if not null) must be freed using `free_string`.
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn drop_script(hash: *mut u8, len: usize) -> *mut c_char {
-    if hash.is_null() {
-        return CString::new("Hash pointer was null.").unwrap().into_raw();
+/// Returns `(None, None)` in standalone mode. In cluster mode, both commands must address the same slot,
+/// the write is routed to the slot's primary and the read to one of its replicas.
+fn consistent_read_routes(
+    cluster_mode: bool,
+    write: &redis::Cmd,
+    read: &redis::Cmd,
+) -> Result<(Option<RoutingInfo>, Option<RoutingInfo>), String> {
+    if !cluster_mode {
+        return Ok((None, None));
+    }
+    let slot_of = |cmd: &redis::Cmd| match RoutingInfo::for_routable(cmd) {
+        Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route))) => {
+            Some(route.slot())
+        }
+        _ => None,
+    };
+    let write_slot =
+        slot_of(write).ok_or("The write command must address the keys of a single slot")?;
+    let read_slot =
+        slot_of(read).ok_or("The read command must address the keys of a single slot")?;
+    if write_slot != read_slot {
+        return Err(format!(
+            "The write and read commands must address the same slot, got slots {write_slot} and {read_slot}"
+        ));
     }
+    let route = |slot_addr| {
+        Some(RoutingInfo::SingleNode(
+            SingleNodeRoutingInfo::SpecificNode(redis::cluster_routing::Route::new(
+                write_slot, slot_addr,
+            )),
+        ))
+    };
+    Ok((
+        route(redis::cluster_routing::SlotAddr::Master),
+        route(redis::cluster_routing::SlotAddr::ReplicaRequired),
+    ))
+}
 
-    let slice = std::ptr::slice_from_raw_parts_mut(hash, len);
-    let Ok(hash_str) = std::str::from_utf8(unsafe { &*slice }) else {
-        return CString::new("Unable to convert hash to UTF-8 string.")
-            .unwrap()
-            .into_raw();
+/// Run the stages of [`write_then_consistent_read`], prefixing an error with the stage which failed.
+async fn write_then_consistent_read_value(
+    core: &CommandExecutionCore,
+    write: &mut redis::Cmd,
+    read: &mut redis::Cmd,
+    write_route: Option<RoutingInfo>,
+    read_route: Option<RoutingInfo>,
+    num_replicas: u32,
+    timeout_ms: u64,
+) -> redis::RedisResult<redis::Value> {
+    let stage_error = |stage: &'static str, err: redis::RedisError| {
+        redis::RedisError::from((err.kind(), stage, error_message(&err)))
     };
+    let mut client = core.client.clone();
+
+    client
+        .send_command(write, write_route.clone())
+        .await
+        .map_err(|err| stage_error("write stage failed", err))?;
+
+    let mut wait = redis::cmd("WAIT");
+    wait.arg(num_replicas).arg(timeout_ms);
+    let acknowledged = client
+        .send_command(&mut wait, write_route)
+        .await
+        .and_then(|value| redis::from_owned_redis_value::<u32>(value))
+        .map_err(|err| stage_error("wait stage failed", err))?;
+    if acknowledged < num_replicas {
+        return Err(redis::RedisError::from((
+            redis::ErrorKind::ClientError,
+            "wait stage failed",
+            format!("only {acknowledged} of {num_replicas} replicas acknowledged the write"),
+        )));
+    }
 
-    glide_core::scripts_container::remove_script(hash_str);
-    std::ptr::null_mut()
+    client
+        .send_command(read, read_route)
+        .await
+        .map_err(|err| stage_error("read stage failed", err))
 }
 
-/// Executes a Lua script using EVALSHA with automatic fallback to EVAL.
+/// Check that a node is alive without touching the keyspace, and report its reply.
 ///
-/// # Parameters
+/// Issues `LOLWUT`, whose reply ends with the server version. If the node rejects `LOLWUT` (e.g. it is renamed or
+/// disabled), `PING` is sent instead. On success the callback receives the reply as a string: the `LOLWUT` output, or `PONG`.
 ///
-/// * `client_ptr`: Pointer to a valid `GlideClient` returned from [`create_client`].
-/// * `callback_index`: Unique identifier for the callback.
-/// * `hash`: SHA1 hash of the script as a null-terminated C string.
-/// * `keys_count`: Number of keys in the keys array.
-/// * `keys`: Array of pointers to key data.
-/// * `keys_len`: Array of key lengths.
-/// * `args_count`: Number of arguments in the args array.
-/// * `args`: Array of pointers to argument data.
-/// * `args_len`: Array of argument lengths.
-/// * `route_bytes`: Optional routing information (not used, reserved for future).
-/// * `route_bytes_len`: Length of route_bytes.
+/// # Arguments
+/// * `client_ptr` - Pointer to the client
+/// * `callback_index` - Callback index for async response
+/// * `route_info` - Optional single-node route, `null` routes to a random node
 ///
 /// # Safety
-///
-/// * `client_ptr` must not be `null` and must be obtained from [`create_client`].
-/// * `hash` must be a valid null-terminated C string.
-/// * `keys` and `keys_len` must be valid arrays of size `keys_count`, or both null if `keys_count` is 0.
-/// * `args` and `args_len` must be valid arrays of size `args_count`, or both null if `args_count` is 0.
+/// * `client_ptr` must be a valid pointer to a Client
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+#[allow(rustdoc::private_intra_doc_links)]
 #[unsafe(no_mangle)]
-pub unsafe extern "C-unwind" fn invoke_script(
+pub unsafe extern "C-unwind" fn probe_server(
     client_ptr: *const c_void,
     callback_index: usize,
-    hash: *const c_char,
-    keys_count: usize,
-    keys: *const usize,
-    keys_len: *const usize,
-    args_count: usize,
-    args: *const usize,
-    args_len: *const usize,
-    _route_bytes: *const u8,
-    _route_bytes_len: usize,
+    route_info: *const RouteInfo,
 ) {
     let client = unsafe {
         Arc::increment_strong_count(client_ptr);
@@ -1118,95 +6443,88 @@ pub unsafe extern "C-unwind" fn invoke_script(
         callback_index,
     };
 
-    // Convert hash to Rust string
-    let hash_str = match unsafe { CStr::from_ptr(hash).to_str() } {
-        Ok(s) => s.to_string(),
-        Err(e) => {
+    let route = match unsafe { create_route(route_info, None) } {
+        Ok(None) => RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random),
+        Ok(Some(route @ RoutingInfo::SingleNode(_))) => route,
+        Ok(Some(RoutingInfo::MultiNode(_))) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    "probe_server requires a single-node route".into(),
+                    RequestErrorType::Unspecified,
+                );
+            }
             panic_guard.panicked = false;
+            return;
+        }
+        Err(err) => {
             unsafe {
                 report_error(
                     core.failure_callback,
                     callback_index,
-                    format!("Invalid hash string: {}", e),
+                    err,
                     RequestErrorType::Unspecified,
                 );
             }
+            panic_guard.panicked = false;
             return;
         }
     };
 
-    // Convert keys
-    let keys_vec: Vec<&[u8]> = unsafe {
-        ffi::convert_byte_array_to_slices(keys as *const *const u8, keys_count, keys_len)
-    };
-
-    // Convert args
-    let args_vec: Vec<&[u8]> = unsafe {
-        ffi::convert_byte_array_to_slices(args as *const *const u8, args_count, args_len)
-    };
-
-    client.runtime.spawn(async move {
+    client.spawn_tracked(async move {
         let mut panic_guard = PanicGuard {
             panicked: true,
             failure_callback: core.failure_callback,
             callback_index,
         };
 
-        let result = core
-            .client
-            .clone()
-            .invoke_script(&hash_str, &keys_vec, &args_vec, None)
-            .await;
-
-        match result {
-            Ok(value) => match ResponseValue::from_value(value) {
-                Ok(response) => {
-                    let ptr = Box::into_raw(Box::new(response));
-                    unsafe { (core.success_callback)(callback_index, ptr) };
-                }
-                Err(err) => unsafe {
-                    report_error(
-                        core.failure_callback,
-                        callback_index,
-                        err,
-                        RequestErrorType::Unspecified,
-                    );
-                },
-            },
-            Err(err) => unsafe {
-                report_error(
-                    core.failure_callback,
-                    callback_index,
-                    error_message(&err),
-                    error_type(&err),
-                );
-            },
+        let mut client = core.client.clone();
+        let result = match client
+            .send_command(&mut redis::cmd("LOLWUT"), Some(route.clone()))
+            .await
+        {
+            Err(err) if err.kind() != redis::ErrorKind::IoError => {
+                client
+                    .send_command(&mut redis::cmd("PING"), Some(route))
+                    .await
+            }
+            result => result,
         };
+        let result = result
+            .and_then(redis::from_owned_redis_value::<String>)
+            .map(redis::Value::SimpleString);
+        unsafe { report_result(&core, callback_index, result) };
         panic_guard.panicked = false;
-        drop(panic_guard);
     });
 
     panic_guard.panicked = false;
-    drop(panic_guard);
 }
 
-/// Execute a cluster scan request.
+/// Send `HELLO` without arguments to a node, and report the handshake.
+///
+/// On success the callback receives the server handshake info (`server`, `version`, `proto`, `id`, `mode`, `role`, `modules`)
+/// as a map, including with RESP2, where the server replies with a flat array of field-value pairs.
+///
+/// `HELLO` can't switch the protocol version or authenticate here: glide-core is not told about a change to the state
+/// of its connection, and would keep parsing replies with the configured protocol. The protocol and credentials are set
+/// with the connection configuration, and credentials are changed with `update_connection_credentials`.
+///
+/// # Arguments
+/// * `client_ptr` - Pointer to the client
+/// * `callback_index` - Callback index for async response
+/// * `route_info` - Optional single-node route, `null` routes to a random node
 ///
 /// # Safety
-/// * `client_ptr` must be a valid Client pointer from create_client
-/// * `cursor` must be "0" for initial scan or a valid cursor ID from previous scan
-/// * `args` and `arg_lengths` must be valid arrays of length `arg_count`
-/// * `args` format: [b"MATCH", pattern_arg, b"COUNT", count, b"TYPE", type] (all optional)
+/// * `client_ptr` must be a valid pointer to a Client
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+#[allow(rustdoc::private_intra_doc_links)]
 #[unsafe(no_mangle)]
-pub unsafe extern "C-unwind" fn request_cluster_scan(
+pub unsafe extern "C-unwind" fn hello(
     client_ptr: *const c_void,
     callback_index: usize,
-    cursor: *const c_char,
-    arg_count: u64,
-    args: *const usize,
-    arg_lengths: *const u64,
+    route_info: *const RouteInfo,
 ) {
-    // Build client and add panic guard.
     let client = unsafe {
         Arc::increment_strong_count(client_ptr);
         Arc::from_raw(client_ptr as *mut Client)
@@ -1219,62 +6537,137 @@ pub unsafe extern "C-unwind" fn request_cluster_scan(
         callback_index,
     };
 
-    // Get the cluster scan state.
-    let cursor_id = unsafe { CStr::from_ptr(cursor) }
-        .to_str()
-        .unwrap_or("0")
-        .to_owned();
-
-    let scan_state_cursor = if cursor_id == "0" {
-        redis::ScanStateRC::new()
-    } else {
-        match glide_core::cluster_scan_container::get_cluster_scan_cursor(cursor_id.clone()) {
-            Ok(existing_cursor) => existing_cursor,
-            Err(_error) => {
-                unsafe {
-                    report_error(
-                        core.failure_callback,
-                        callback_index,
-                        format!("Invalid cursor ID: {}", cursor_id),
-                        RequestErrorType::Unspecified,
-                    );
-                }
-                panic_guard.panicked = false;
-                return;
+    let route = match unsafe { create_route(route_info, None) } {
+        Ok(None) => Ok(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)),
+        Ok(Some(route @ RoutingInfo::SingleNode(_))) => Ok(route),
+        Ok(Some(RoutingInfo::MultiNode(_))) => Err("hello requires a single-node route".into()),
+        Err(err) => Err(err),
+    };
+    let route = match route {
+        Ok(route) => route,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
             }
+            panic_guard.panicked = false;
+            return;
         }
     };
 
-    // Build cluster scan arguments.
-    let cluster_scan_args = match unsafe {
-        build_cluster_scan_args(
-            arg_count,
-            args,
-            arg_lengths,
-            core.failure_callback,
+    unsafe {
+        spawn_command_with(
+            &client,
             callback_index,
+            redis::cmd("HELLO"),
+            Some(route),
+            parse_hello_response,
         )
-    } {
-        Some(args) => args,
-        None => {
+    };
+
+    panic_guard.panicked = false;
+}
+
+/// Convert the RESP2 reply of `HELLO`, a flat array of field-value pairs, to a map like the RESP3 reply.
+fn parse_hello_response(
+    result: redis::RedisResult<redis::Value>,
+) -> redis::RedisResult<redis::Value> {
+    match result? {
+        redis::Value::Array(values) if values.len() % 2 == 0 => {
+            let mut values = values.into_iter();
+            let mut entries = Vec::with_capacity(values.len() / 2);
+            while let (Some(field), Some(value)) = (values.next(), values.next()) {
+                entries.push((field, value));
+            }
+            Ok(redis::Value::Map(entries))
+        }
+        value => Ok(value),
+    }
+}
+
+/// Attach client attributes to the connections with `CLIENT SETINFO`.
+///
+/// One `CLIENT SETINFO <attribute> <value>` is sent per pair, in order, to all nodes. The first failure
+/// is reported and the remaining pairs are not sent. Reports `OK` once every pair was applied.
+///
+/// Attributes set this way are not part of the connection request, so they are not reapplied when glide-core
+/// reconnects. Use the `client_lib_name` of [`ConnectionConfig`](ffi::ConnectionConfig) for a library name that must survive reconnects.
+///
+/// # Arguments
+/// * `client_ptr` - Pointer to the client
+/// * `callback_index` - Callback index for async response
+/// * `attributes` - Array of attribute names, e.g. `lib-name` or `lib-ver`
+/// * `values` - Array of attribute values, parallel to `attributes`
+/// * `count` - Number of attribute/value pairs
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer to a Client
+/// * `attributes` and `values` must be valid pointers to `count` C string pointers. See the safety documentation of [`create_client_setinfo_cmds`].
+#[allow(rustdoc::private_intra_doc_links)]
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn set_client_info(
+    client_ptr: *const c_void,
+    callback_index: usize,
+    attributes: *const *const c_char,
+    values: *const *const c_char,
+    count: usize,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
+
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
+
+    let cmds = match unsafe { create_client_setinfo_cmds(attributes, values, count) } {
+        Ok(cmds) => cmds,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
+            }
             panic_guard.panicked = false;
             return;
         }
     };
 
-    // Run cluster scan.
-    client.runtime.spawn(async move {
+    client.spawn_tracked(async move {
         let mut async_panic_guard = PanicGuard {
             panicked: true,
             failure_callback: core.failure_callback,
             callback_index,
         };
 
-        let result = core
-            .client
-            .clone()
-            .cluster_scan(&scan_state_cursor, cluster_scan_args)
-            .await;
+        let mut result = Ok(redis::Value::Okay);
+        for mut cmd in cmds {
+            let route = RoutingInfo::MultiNode((
+                MultipleNodeRoutingInfo::AllNodes,
+                Some(ResponsePolicy::AllSucceeded),
+            ));
+            if let Err(err) = core
+                .client
+                .clone()
+                .send_command(&mut cmd, Some(route))
+                .await
+            {
+                result = Err(err);
+                break;
+            }
+        }
+
         match result {
             Ok(value) => match ResponseValue::from_value(value) {
                 Ok(response) => {
@@ -1294,8 +6687,8 @@ pub unsafe extern "C-unwind" fn request_cluster_scan(
                 report_error(
                     core.failure_callback,
                     callback_index,
-                    glide_core::errors::error_message(&err),
-                    glide_core::errors::error_type(&err),
+                    error_message(&err),
+                    error_type(&err),
                 );
             },
         };
@@ -1306,226 +6699,251 @@ pub unsafe extern "C-unwind" fn request_cluster_scan(
     panic_guard.panicked = false;
 }
 
-/// Remove a cluster scan cursor from the Rust core container.
+/// Get the database currently selected by a standalone client.
 ///
-/// This should be called when the C# ClusterScanCursor is disposed or finalized
-/// to clean up resources allocated by the Rust core for cluster scan operations.
+/// This is the database configured in [`ConnectionConfig`](ffi::ConnectionConfig), or the last one selected with
+/// [`select_database`]. A `SELECT` sent as a custom command is not tracked. Cluster clients always use database 0.
 ///
 /// # Safety
-/// * `cursor_id` must be a valid C string or null
+/// * `client_ptr` must not be `null` and must be obtained from [`create_client`], and not yet closed.
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn remove_cluster_scan_cursor(cursor_id: *const c_char) {
-    if cursor_id.is_null() {
-        return;
-    }
-
-    if let Ok(cursor_str) = unsafe { CStr::from_ptr(cursor_id).to_str() } {
-        glide_core::cluster_scan_container::remove_scan_state_cursor(cursor_str.to_string());
-    }
+pub unsafe extern "C" fn get_database(client_ptr: *const c_void) -> i64 {
+    assert!(!client_ptr.is_null());
+    let client = unsafe { &*(client_ptr as *const Client) };
+    client
+        .database_id
+        .load(std::sync::atomic::Ordering::Acquire)
 }
 
-/// Build cluster scan arguments from C-style arrays.
-///
-/// # Arguments
+/// Select the database of a standalone client with `SELECT`.
 ///
-/// * `arg_count` - The number of arguments in the arrays
-/// * `args` - Pointer to an array of pointers to argument data
-/// * `arg_lengths` - Pointer to an array of argument lengths
-/// * `failure_callback` - Callback function to invoke on error
-/// * `callback_index` - Index to pass to the callback function
+/// glide-core keeps the database selected by a `SELECT` sent through the client, and selects it again on every
+/// reconnection, so the selection survives reconnects. On success, [`get_database`] returns `database_id`.
+/// Fails in cluster mode, since cluster mode only supports database 0.
 ///
 /// # Safety
-/// * `args` and `arg_lengths` must be valid arrays of length `arg_count`
-/// * Each pointer in `args` must point to valid memory of the corresponding length
-unsafe fn build_cluster_scan_args(
-    arg_count: u64,
-    args: *const usize,
-    arg_lengths: *const u64,
-    failure_callback: FailureCallback,
+/// * `client_ptr` must be a valid pointer to a Client
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn select_database(
+    client_ptr: *const c_void,
     callback_index: usize,
-) -> Option<redis::ClusterScanArgs> {
-    if arg_count == 0 {
-        return Some(redis::ClusterScanArgs::builder().build());
-    }
-
-    let arg_vec = unsafe { convert_string_pointer_array_to_vector(args, arg_count, arg_lengths) };
+    database_id: i64,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
 
-    // Parse arguments from vector.
-    let mut pattern_arg: &[u8] = &[];
-    let mut type_arg: &[u8] = &[];
-    let mut count_arg: &[u8] = &[];
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
 
-    let mut iter = arg_vec.iter().peekable();
-    while let Some(arg) = iter.next() {
-        match *arg {
-            b"MATCH" => match iter.next() {
-                Some(p) => pattern_arg = p,
-                None => {
-                    unsafe {
-                        report_error(
-                            failure_callback,
-                            callback_index,
-                            "No argument following MATCH.".into(),
-                            RequestErrorType::Unspecified,
-                        );
-                    }
-                    return None;
-                }
-            },
-            b"TYPE" => match iter.next() {
-                Some(t) => type_arg = t,
-                None => {
-                    unsafe {
-                        report_error(
-                            failure_callback,
-                            callback_index,
-                            "No argument following TYPE.".into(),
-                            RequestErrorType::Unspecified,
-                        );
-                    }
-                    return None;
-                }
-            },
-            b"COUNT" => match iter.next() {
-                Some(c) => count_arg = c,
-                None => {
-                    unsafe {
-                        report_error(
-                            failure_callback,
-                            callback_index,
-                            "No argument following COUNT.".into(),
-                            RequestErrorType::Unspecified,
-                        );
-                    }
-                    return None;
-                }
-            },
-            _ => {
-                unsafe {
-                    report_error(
-                        failure_callback,
-                        callback_index,
-                        "Unknown cluster scan argument".into(),
-                        RequestErrorType::Unspecified,
-                    );
-                }
-                return None;
-            }
+    if client.cluster_mode {
+        unsafe {
+            report_error(
+                core.failure_callback,
+                callback_index,
+                "Selecting a database is not supported in cluster mode, which only has database 0"
+                    .into(),
+                RequestErrorType::Unspecified,
+            );
         }
+        panic_guard.panicked = false;
+        return;
     }
 
-    // Build cluster scan arguments.
-    let mut cluster_scan_args_builder = redis::ClusterScanArgs::builder();
+    let selected_database_id = client.database_id.clone();
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
 
-    if !pattern_arg.is_empty() {
-        cluster_scan_args_builder = cluster_scan_args_builder.with_match_pattern(pattern_arg);
-    }
+        let result = core
+            .client
+            .clone()
+            .send_command(&mut redis::cmd("SELECT").arg(database_id).to_owned(), None)
+            .await;
+        if result.is_ok() {
+            selected_database_id.store(database_id, std::sync::atomic::Ordering::Release);
+        }
+        unsafe { report_result(&core, callback_index, result) };
+        panic_guard.panicked = false;
+    });
 
-    if !type_arg.is_empty() {
-        let converted_type = match std::str::from_utf8(type_arg) {
-            Ok(t) => redis::ObjectType::from(t.to_string()),
-            Err(_) => {
-                unsafe {
-                    report_error(
-                        failure_callback,
-                        callback_index,
-                        "Invalid UTF-8 in TYPE argument".into(),
-                        RequestErrorType::Unspecified,
-                    );
-                }
-                return None;
-            }
-        };
+    panic_guard.panicked = false;
+}
 
-        cluster_scan_args_builder = cluster_scan_args_builder.with_object_type(converted_type);
-    }
+/// Get the mode the client was created in, and the mode of the server it is connected to.
+///
+/// Reports a map with `configured`, the mode of [`ConnectionConfig`](ffi::ConnectionConfig), and `server`, the mode
+/// the server reports in `INFO cluster`, each either `standalone` or `cluster`. Creating a cluster client against a
+/// standalone server fails, but a standalone client connects to a cluster node and then fails commands for keys
+/// served by other nodes, so comparing both modes detects this misconfiguration.
+///
+/// # Safety
+/// * `client_ptr` must be a valid pointer to a Client
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn get_client_mode(client_ptr: *const c_void, callback_index: usize) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
 
-    if !count_arg.is_empty() {
-        let count_str = match std::str::from_utf8(count_arg) {
-            Ok(c) => c,
-            Err(_) => {
-                unsafe {
-                    report_error(
-                        failure_callback,
-                        callback_index,
-                        "Invalid UTF-8 in COUNT argument".into(),
-                        RequestErrorType::Unspecified,
-                    );
-                }
-                return None;
-            }
-        };
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
 
-        let converted_count = match count_str.parse::<u32>() {
-            Ok(c) => c,
-            Err(_) => {
-                unsafe {
-                    report_error(
-                        failure_callback,
-                        callback_index,
-                        "Invalid COUNT value".into(),
-                        RequestErrorType::Unspecified,
-                    );
-                }
-                return None;
-            }
+    let cluster_mode = client.cluster_mode;
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
         };
 
-        cluster_scan_args_builder = cluster_scan_args_builder.with_count(converted_count);
-    }
+        let result = get_client_mode_value(&core, cluster_mode).await;
+        unsafe { report_result(&core, callback_index, result) };
+        panic_guard.panicked = false;
+    });
 
-    Some(cluster_scan_args_builder.build())
+    panic_guard.panicked = false;
 }
 
-/// Converts an array of pointers to strings to a vector of strings.
-///
-/// # Arguments
+/// Build the map reported by [`get_client_mode`].
+async fn get_client_mode_value(
+    core: &CommandExecutionCore,
+    cluster_mode: bool,
+) -> redis::RedisResult<redis::Value> {
+    let mode_name = |cluster: bool| if cluster { "cluster" } else { "standalone" };
+    // INFO is sent to all nodes by default, a single node is enough to learn the server mode.
+    let route = cluster_mode.then_some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random));
+    let info = core
+        .client
+        .clone()
+        .send_command(&mut redis::cmd("INFO").arg("cluster").to_owned(), route)
+        .await?;
+    let info: String = redis::from_owned_redis_value(info)?;
+    let server_cluster_mode = info.lines().any(|line| line.trim() == "cluster_enabled:1");
+
+    Ok(redis::Value::Map(vec![
+        (
+            redis::Value::SimpleString("configured".into()),
+            redis::Value::SimpleString(mode_name(cluster_mode).into()),
+        ),
+        (
+            redis::Value::SimpleString("server".into()),
+            redis::Value::SimpleString(mode_name(server_cluster_mode).into()),
+        ),
+    ]))
+}
+
+/// Get the RESP version negotiated with the server, `2` or `3`.
 ///
-/// * `data` - Pointer to an array of pointers to string data
-/// * `len` - The number of strings in the array
-/// * `data_len` - Pointer to an array of string lengths
+/// [`ConnectionConfig`](ffi::ConnectionConfig) only sets the preferred protocol, so the version is read from the `resp`
+/// field of `CLIENT INFO`, which the server reports for the connection serving the command. All connections of a client
+/// negotiate the same version. The shapes of maps and sets in [`ResponseValue`]s depend on this version.
 ///
 /// # Safety
-///
-/// `convert_string_pointer_array_to_vector` returns a `Vec` of u8 slice which holds pointers of C
-/// strings. The returned `Vec<&'a [u8]>` is meant to be copied into Rust code. Storing them
-/// for later use will cause the program to crash as the pointers will be freed by the caller.
-unsafe fn convert_string_pointer_array_to_vector<'a>(
-    data: *const usize,
-    len: u64,
-    data_len: *const u64,
-) -> Vec<&'a [u8]> {
-    let string_ptrs = unsafe { from_raw_parts(data, len as usize) };
-    let string_lengths = unsafe { from_raw_parts(data_len, len as usize) };
+/// * `client_ptr` must be a valid pointer to a Client
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn get_protocol_version(
+    client_ptr: *const c_void,
+    callback_index: usize,
+) {
+    let client = unsafe {
+        Arc::increment_strong_count(client_ptr);
+        Arc::from_raw(client_ptr as *mut Client)
+    };
+    let core = client.core.clone();
 
-    let mut result = Vec::<&[u8]>::with_capacity(string_ptrs.len());
-    for (i, &str_ptr) in string_ptrs.iter().enumerate() {
-        let slice = unsafe { from_raw_parts(str_ptr as *const u8, string_lengths[i] as usize) };
-        result.push(slice);
-    }
+    let mut panic_guard = PanicGuard {
+        panicked: true,
+        failure_callback: core.failure_callback,
+        callback_index,
+    };
 
-    result
+    let route = client
+        .cluster_mode
+        .then_some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random));
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
+            panicked: true,
+            failure_callback: core.failure_callback,
+            callback_index,
+        };
+
+        let result = get_protocol_version_value(&core, route).await;
+        unsafe { report_result(&core, callback_index, result) };
+        panic_guard.panicked = false;
+    });
+
+    panic_guard.panicked = false;
 }
 
-/// Manually refresh the IAM authentication token.
-///
-/// This function triggers an immediate refresh of the IAM token and updates the connection.
-/// It is only available if the client was created with IAM authentication.
-///
-/// # Arguments
+/// Read the `resp` field of `CLIENT INFO`, see [`get_protocol_version`].
+async fn get_protocol_version_value(
+    core: &CommandExecutionCore,
+    route: Option<RoutingInfo>,
+) -> redis::RedisResult<redis::Value> {
+    let info: String = redis::from_owned_redis_value(
+        core.client
+            .clone()
+            .send_command(&mut redis::cmd("CLIENT").arg("INFO").to_owned(), route)
+            .await?,
+    )?;
+    info.split_whitespace()
+        .find_map(|field| field.strip_prefix("resp="))
+        .and_then(|version| version.parse::<i64>().ok())
+        .map(redis::Value::Int)
+        .ok_or_else(|| {
+            redis::RedisError::from((
+                redis::ErrorKind::TypeError,
+                "CLIENT INFO has no valid resp field",
+                info.clone(),
+            ))
+        })
+}
+
+/// List the nodes with `CLUSTER NODES`, routed to a random node.
+async fn get_cluster_nodes(core: &CommandExecutionCore) -> redis::RedisResult<Vec<ClusterNode>> {
+    let nodes = core
+        .client
+        .clone()
+        .send_command(
+            &mut redis::cmd("CLUSTER").arg("NODES").to_owned(),
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)),
+        )
+        .await?;
+    let nodes: String = redis::from_owned_redis_value(nodes)?;
+    Ok(parse_cluster_nodes(&nodes))
+}
+
+/// The largest number of `PING`s [`measure_node_latencies`] sends to each node.
+const MAX_LATENCY_SAMPLES: u32 = 1000;
+
+/// Measure the round-trip latency to every cluster node.
 ///
-/// * `client_ptr` - A pointer to a valid client returned from [`create_client`].
-/// * `callback_index` - A unique identifier for the callback to be called when the command completes.
+/// Each node is sent `samples` `PING`s, one after the other, routed by address. `samples` must be positive, and is
+/// capped at [`MAX_LATENCY_SAMPLES`]. On success the callback receives a map
+/// keyed by `host:port`, where each value is a map with `min`, `avg` and `max` latencies in milliseconds, as doubles.
+/// If a node could not be reached, its value is an error entry instead. Nodes are measured concurrently.
+/// The nodes are discovered with `CLUSTER NODES`, so this is only supported in cluster mode.
 ///
 /// # Safety
-///
-/// * `client_ptr` must not be `null`.
-/// * `client_ptr` must be able to be safely casted to a valid [`Arc<Client>`] via [`Arc::from_raw`]. See the safety documentation of [`Arc::from_raw`].
-/// * This function should only be called with a `client_ptr` created by [`create_client`], before [`close_client`] was called with the pointer.
+/// * `client_ptr` must be a valid pointer to a Client
 #[unsafe(no_mangle)]
-pub unsafe extern "C-unwind" fn refresh_iam_token(
+pub unsafe extern "C-unwind" fn measure_node_latencies(
     client_ptr: *const c_void,
     callback_index: usize,
+    samples: u32,
 ) {
     let client = unsafe {
         Arc::increment_strong_count(client_ptr);
@@ -1539,64 +6957,126 @@ pub unsafe extern "C-unwind" fn refresh_iam_token(
         callback_index,
     };
 
-    client.runtime.spawn(async move {
-        let mut async_panic_guard = PanicGuard {
+    if samples == 0 {
+        unsafe {
+            report_error(
+                core.failure_callback,
+                callback_index,
+                "The number of latency samples must be positive".into(),
+                RequestErrorType::Unspecified,
+            );
+        }
+        panic_guard.panicked = false;
+        return;
+    }
+    let samples = samples.min(MAX_LATENCY_SAMPLES);
+
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
             panicked: true,
             failure_callback: core.failure_callback,
             callback_index,
         };
 
-        let result = core.client.clone().refresh_iam_token().await;
-        match result {
-            Ok(()) => match ResponseValue::from_value(redis::Value::Okay) {
-                Ok(response) => {
-                    let ptr = Box::into_raw(Box::new(response));
-                    unsafe { (core.success_callback)(callback_index, ptr) };
-                }
-                Err(err) => unsafe {
-                    report_error(
-                        core.failure_callback,
-                        callback_index,
-                        err,
-                        RequestErrorType::Unspecified,
-                    );
-                },
-            },
-            Err(err) => unsafe {
-                report_error(
-                    core.failure_callback,
-                    callback_index,
-                    error_message(&err),
-                    error_type(&err),
-                );
-            },
-        };
-
-        async_panic_guard.panicked = false;
+        let result = measure_node_latencies_value(&core, samples).await;
+        unsafe { report_result(&core, callback_index, result) };
+        panic_guard.panicked = false;
     });
 
     panic_guard.panicked = false;
 }
 
-/// Update connection password
+/// Build the map reported by [`measure_node_latencies`].
+async fn measure_node_latencies_value(
+    core: &CommandExecutionCore,
+    samples: u32,
+) -> redis::RedisResult<redis::Value> {
+    let addresses: Vec<(String, u16)> = get_cluster_nodes(core)
+        .await?
+        .into_iter()
+        .map(|node| (node.host, node.port))
+        .collect();
+
+    let mut probes = tokio::task::JoinSet::new();
+    for (index, (host, port)) in addresses.iter().cloned().enumerate() {
+        let mut client = core.client.clone();
+        probes.spawn(async move {
+            let route = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port });
+            let (mut min, mut max, mut total) = (f64::INFINITY, 0.0, 0.0);
+            for _ in 0..samples {
+                let start = std::time::Instant::now();
+                if let Err(err) = client
+                    .send_command(&mut redis::cmd("PING"), Some(route.clone()))
+                    .await
+                {
+                    return (index, Err(err));
+                }
+                let latency = start.elapsed().as_secs_f64() * 1000.0;
+                min = f64::min(min, latency);
+                max = f64::max(max, latency);
+                total += latency;
+            }
+            (index, Ok((min, total / f64::from(samples), max)))
+        });
+    }
+
+    let mut measurements: Vec<redis::Value> = vec![redis::Value::Nil; addresses.len()];
+    while let Some(joined) = probes.join_next().await {
+        let Ok((index, latencies)) = joined else {
+            continue;
+        };
+        measurements[index] = match latencies {
+            Ok((min, avg, max)) => redis::Value::Map(vec![
+                (
+                    redis::Value::SimpleString("min".to_string()),
+                    redis::Value::Double(min),
+                ),
+                (
+                    redis::Value::SimpleString("avg".to_string()),
+                    redis::Value::Double(avg),
+                ),
+                (
+                    redis::Value::SimpleString("max".to_string()),
+                    redis::Value::Double(max),
+                ),
+            ]),
+            Err(err) => redis::Value::ServerError(redis::ServerError::ExtensionError {
+                code: err.code().unwrap_or("ERR").to_string(),
+                detail: Some(error_message(&err)),
+            }),
+        };
+    }
+
+    Ok(redis::Value::Map(
+        addresses
+            .into_iter()
+            .map(|(host, port)| redis::Value::SimpleString(format!("{host}:{port}")))
+            .zip(measurements)
+            .collect(),
+    ))
+}
+
+/// Check whether a route can currently be served, without sending any command.
 ///
-/// # Arguments
-/// * `client_ptr` - Pointer to the client
-/// * `callback_index` - Callback index for async response
-/// * `password` - New password (null for password removal)
-/// * `immediate_auth` - Whether to authenticate immediately
+/// On success the callback receives a map with `valid` (a boolean) and `reason` (why the route is invalid, or nil).
+/// * `ByAddress` routes are valid if the node is part of the current topology and not flagged as failed.
+/// * `SlotId` and `SlotKey` routes are valid if a healthy primary, or a healthy replica for replica routes, serves the slot.
+/// * `Random`, `AllNodes` and `AllPrimaries` routes are valid if at least one healthy node of the required role is known.
+/// * A `null` route is always valid, as it is resolved by glide-core from the command.
+///
+/// The topology is read with `CLUSTER NODES`, so this is only supported in cluster mode.
+/// Failing to read the topology is reported through the failure callback.
 ///
 /// # Safety
 /// * `client_ptr` must be a valid pointer to a Client
-/// * `password` must be a valid C string or null
+/// * `route_info` could be `null`, but if it is not `null`, it must be a valid [`RouteInfo`] pointer. See the safety documentation of [`create_route`].
+#[allow(rustdoc::private_intra_doc_links)]
 #[unsafe(no_mangle)]
-pub unsafe extern "C-unwind" fn update_connection_password(
+pub unsafe extern "C-unwind" fn validate_route(
     client_ptr: *const c_void,
     callback_index: usize,
-    password_ptr: *const c_char,
-    immediate_auth: bool,
+    route_info: *const RouteInfo,
 ) {
-    // Build client and add panic guard.
     let client = unsafe {
         Arc::increment_strong_count(client_ptr);
         Arc::from_raw(client_ptr as *mut Client)
@@ -1609,77 +7089,94 @@ pub unsafe extern "C-unwind" fn update_connection_password(
         callback_index,
     };
 
-    // Build password option.
-    let password = if password_ptr.is_null() {
-        None
-    } else {
-        match unsafe { CStr::from_ptr(password_ptr).to_str() } {
-            Ok(password_str) => {
-                if password_str.is_empty() {
-                    None
-                } else {
-                    Some(password_str.into())
-                }
-            }
-            Err(_) => {
-                unsafe {
-                    report_error(
-                        core.failure_callback,
-                        callback_index,
-                        "Invalid password argument".into(),
-                        RequestErrorType::Unspecified,
-                    );
-                }
-                panic_guard.panicked = false;
-                return;
+    let route = match unsafe { create_route(route_info, None) } {
+        Ok(route) => route,
+        Err(err) => {
+            unsafe {
+                report_error(
+                    core.failure_callback,
+                    callback_index,
+                    err,
+                    RequestErrorType::Unspecified,
+                );
             }
+            panic_guard.panicked = false;
+            return;
         }
     };
 
-    // Run password update.
-    client.runtime.spawn(async move {
-        let mut async_panic_guard = PanicGuard {
+    client.spawn_tracked(async move {
+        let mut panic_guard = PanicGuard {
             panicked: true,
             failure_callback: core.failure_callback,
             callback_index,
         };
 
-        let result = core
-            .client
-            .clone()
-            .update_connection_password(password, immediate_auth)
-            .await;
-        match result {
-            Ok(value) => match ResponseValue::from_value(value) {
-                Ok(response) => {
-                    let ptr = Box::into_raw(Box::new(response));
-                    unsafe { (core.success_callback)(callback_index, ptr) };
-                }
-                Err(err) => unsafe {
-                    report_error(
-                        core.failure_callback,
-                        callback_index,
-                        err,
-                        RequestErrorType::Unspecified,
-                    );
-                },
-            },
-            Err(err) => unsafe {
-                report_error(
-                    core.failure_callback,
-                    callback_index,
-                    error_message(&err),
-                    error_type(&err),
-                );
-            },
+        let result = match route {
+            None => Ok(Ok(())),
+            Some(route) => get_cluster_nodes(&core)
+                .await
+                .map(|nodes| check_route(&nodes, &route)),
         };
-
-        async_panic_guard.panicked = false;
+        let result = result.map(|validation| {
+            redis::Value::Map(vec![
+                (
+                    redis::Value::SimpleString("valid".into()),
+                    redis::Value::Boolean(validation.is_ok()),
+                ),
+                (
+                    redis::Value::SimpleString("reason".into()),
+                    validation
+                        .err()
+                        .map_or(redis::Value::Nil, redis::Value::SimpleString),
+                ),
+            ])
+        });
+        unsafe { report_result(&core, callback_index, result) };
+        panic_guard.panicked = false;
     });
 
     panic_guard.panicked = false;
 }
 
+/// Check whether `route` can be served by the given nodes, see [`validate_route`].
+fn check_route(nodes: &[ClusterNode], route: &RoutingInfo) -> Result<(), String> {
+    let healthy = |node: &&ClusterNode| !node.is_failed();
+    match route {
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port }) => {
+            check_known_node(nodes, host, *port)
+        }
+        RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(route)) => {
+            let slot = route.slot();
+            let primary = nodes
+                .iter()
+                .filter(healthy)
+                .find(|node| node.is_primary() && node.serves_slot(slot))
+                .ok_or_else(|| format!("No healthy primary serves slot {slot}"))?;
+            if matches!(route.slot_addr(), redis::cluster_routing::SlotAddr::Master) {
+                return Ok(());
+            }
+            nodes
+                .iter()
+                .filter(healthy)
+                .any(|node| node.primary_id.as_deref() == Some(primary.id.as_str()))
+                .then_some(())
+                .ok_or_else(|| format!("No healthy replica serves slot {slot}"))
+        }
+        RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllMasters, _)) => nodes
+            .iter()
+            .filter(healthy)
+            .any(ClusterNode::is_primary)
+            .then_some(())
+            .ok_or_else(|| "No healthy primary is known".to_string()),
+        _ => nodes
+            .iter()
+            .any(|node| !node.is_failed())
+            .then_some(())
+            .ok_or_else(|| "No healthy node is known".to_string()),
+    }
+}
+
 // ========================================================================================
 // OpenTelemetry
 // ========================================================================================
@@ -1768,6 +7265,54 @@ pub unsafe extern "C" fn init_otel(config: *const OpenTelemetryConfigFFI) -> *co
     }
 }
 
+/// Closes the given client and then shuts down OpenTelemetry, flushing the remaining spans and metrics.
+///
+/// The client is closed first, so that the spans of its final commands end and are recorded before the flush.
+/// `timeout_ms` bounds the whole shutdown: the client waits for in-flight commands like [`close_client_with_timeout`],
+/// and OpenTelemetry is given whatever remains. A flush which doesn't complete in time is logged and abandoned.
+/// The wait for the PubSub task has its own timeout, see [`close_client`].
+///
+/// OpenTelemetry is process-wide, so no span is exported after this returns, even for other clients.
+/// If OpenTelemetry wasn't initialized, this is equivalent to [`close_client_with_timeout`].
+///
+/// # Safety
+///
+/// * See the safety documentation of [`close_client`].
+#[unsafe(no_mangle)]
+pub extern "C" fn shutdown(client_ptr: *const c_void, timeout_ms: u32) {
+    let start = std::time::Instant::now();
+    let timeout = std::time::Duration::from_millis(timeout_ms.into());
+
+    close_client_internal(client_ptr, Some(timeout));
+
+    if !GlideOpenTelemetry::is_initialized() {
+        return;
+    }
+    let glide_runtime = match glide_core::client::get_or_init_runtime() {
+        Ok(glide_runtime) => glide_runtime,
+        Err(e) => {
+            logger_core::log(
+                logger_core::Level::Warn,
+                "ffi_otel",
+                format!("Failed to get runtime, OpenTelemetry was not flushed: {e}"),
+            );
+            return;
+        }
+    };
+    let remaining = timeout.saturating_sub(start.elapsed());
+    let result = glide_runtime.runtime.block_on(tokio::time::timeout(
+        remaining,
+        tokio::task::spawn_blocking(GlideOpenTelemetry::shutdown),
+    ));
+    if result.is_err() {
+        logger_core::log(
+            logger_core::Level::Warn,
+            "ffi_otel",
+            format!("OpenTelemetry did not flush within timeout ({timeout:?})"),
+        );
+    }
+}
+
 /// Creates an OpenTelemetry span for the given request type.
 ///
 /// # Parameters
@@ -2283,3 +7828,344 @@ pub extern "C" fn get_statistics() -> Statistics {
         subscription_last_sync_timestamp: Telemetry::subscription_last_sync_timestamp(),
     }
 }
+
+/// Get connection statistics of a client, as a map keyed by metric name.
+///
+/// * `total_connections` - connections currently open by all GLIDE clients of the process. glide-core doesn't count
+///   the connections of each client, so there is no count specific to the given client.
+/// * `total_clients` - GLIDE clients currently open in the process.
+/// * `inflight_requests` - requests of the given client which have not completed yet.
+///
+/// Returns `null` on failure. The returned pointer must be freed with [`free_response`].
+///
+/// # Safety
+/// * `client_ptr` must not be `null` and must be obtained from [`create_client`], and not yet closed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn get_client_statistics(client_ptr: *const c_void) -> *mut ResponseValue {
+    use glide_core::Telemetry;
+
+    assert!(!client_ptr.is_null());
+    let client = unsafe { &*(client_ptr as *const Client) };
+    let count = |count: u64| redis::Value::Int(i64::try_from(count).unwrap_or(i64::MAX));
+    let statistics = [
+        (
+            "total_connections",
+            count(Telemetry::total_connections() as u64),
+        ),
+        ("total_clients", count(Telemetry::total_clients() as u64)),
+        (
+            "inflight_requests",
+            count(
+                client
+                    .inflight
+                    .count
+                    .load(std::sync::atomic::Ordering::SeqCst) as u64,
+            ),
+        ),
+    ];
+    let statistics = redis::Value::Map(
+        statistics
+            .into_iter()
+            .map(|(name, value)| (redis::Value::SimpleString(name.into()), value))
+            .collect(),
+    );
+    match ResponseValue::from_value(statistics) {
+        Ok(response) => Box::into_raw(Box::new(response)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+// ========================================================================================
+// Fault Injection
+// ========================================================================================
+
+/// Faults injected into the commands of a client, see [`set_fault_injection`].
+#[cfg(feature = "fault-injection")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct FaultInjectionSpec {
+    /// Milliseconds to wait before sending every affected command.
+    pub delay_ms: u32,
+    /// Probability, between 0 and 1, that an affected command fails instead of being sent.
+    pub failure_probability: f64,
+    /// Whether failed commands fail with a disconnection error rather than a server error.
+    pub simulate_disconnect: bool,
+    /// The [`RequestType`] discriminants of the affected commands, all commands if `request_type_count` is 0.
+    ///
+    /// They are kept as integers, so that an unknown one, e.g. passed by a binding built against a newer version of this
+    /// library, is never read as a [`RequestType`]: it matches no command.
+    pub request_types: *const u32,
+    pub request_type_count: usize,
+    /// Seed of the random failures, so that a test fails the same commands on every run.
+    pub seed: u64,
+}
+
+/// The state of the faults injected into the commands of a client.
+#[cfg(feature = "fault-injection")]
+struct FaultInjection {
+    delay: std::time::Duration,
+    failure_probability: f64,
+    simulate_disconnect: bool,
+    /// Empty if all commands are affected.
+    request_types: Vec<u32>,
+    /// State of the xorshift64* generator drawing failures, never 0.
+    random_state: u64,
+}
+
+#[cfg(feature = "fault-injection")]
+impl FaultInjection {
+    /// Draw the fault of a command of type `request_type`: the delay to wait, and the error to fail it with, if any.
+    fn next_fault(
+        &mut self,
+        request_type: RequestType,
+    ) -> Option<(std::time::Duration, Option<redis::RedisError>)> {
+        if !self.request_types.is_empty() && !self.request_types.contains(&(request_type as u32)) {
+            return None;
+        }
+        self.random_state ^= self.random_state >> 12;
+        self.random_state ^= self.random_state << 25;
+        self.random_state ^= self.random_state >> 27;
+        let random = self.random_state.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        // The 53 high bits give a uniform float in [0, 1).
+        let fails = ((random >> 11) as f64 / (1u64 << 53) as f64) < self.failure_probability;
+        let error = fails.then(|| match self.simulate_disconnect {
+            true => std::io::Error::new(
+                std::io::ErrorKind::ConnectionReset,
+                "Injected fault: simulated disconnect",
+            )
+            .into(),
+            false => redis::RedisError::from((
+                redis::ErrorKind::ResponseError,
+                "Injected fault",
+                "simulated command failure".to_string(),
+            )),
+        });
+        Some((self.delay, error))
+    }
+}
+
+/// Apply the injected fault of a command, if any: wait for its delay and return the error to fail it with.
+#[cfg(feature = "fault-injection")]
+async fn inject_fault(
+    core: &CommandExecutionCore,
+    request_type: RequestType,
+) -> Option<redis::RedisError> {
+    let fault = core
+        .fault_injection
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .as_mut()
+        .and_then(|fault_injection| fault_injection.next_fault(request_type));
+    let (delay, error) = fault?;
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+    error
+}
+
+/// Inject faults into the commands of a client, to test failure handling without a broken server.
+///
+/// Only available when the crate is built with the `fault-injection` feature, which must not be enabled in release builds.
+/// Commands executed with [`command`], [`command_traced`] and [`command_async`] are affected, while auto-pipelined commands, batches and
+/// the other FFI functions are not. Every affected command waits for the delay, then fails with probability
+/// `failure_probability` instead of being sent. Failures are drawn from `seed`, so a test sending the same commands in
+/// the same order fails the same ones. Pass a `null` spec to stop injecting faults.
+///
+/// # Safety
+/// * `client_ptr` must not be `null` and must be obtained from [`create_client`], and not yet closed.
+/// * `spec_ptr` must be `null` or a valid [`FaultInjectionSpec`] pointer, whose `request_types` points to
+///   `request_type_count` integers if `request_type_count` is not 0.
+#[cfg(feature = "fault-injection")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn set_fault_injection(
+    client_ptr: *const c_void,
+    spec_ptr: *const FaultInjectionSpec,
+) {
+    assert!(!client_ptr.is_null());
+    let client = unsafe { &*(client_ptr as *const Client) };
+    let fault_injection = (!spec_ptr.is_null()).then(|| {
+        let spec = unsafe { *spec_ptr };
+        let request_types = if spec.request_type_count == 0 {
+            Vec::new()
+        } else {
+            unsafe { from_raw_parts(spec.request_types, spec.request_type_count) }.to_vec()
+        };
+        FaultInjection {
+            delay: std::time::Duration::from_millis(spec.delay_ms.into()),
+            failure_probability: spec.failure_probability,
+            simulate_disconnect: spec.simulate_disconnect,
+            request_types,
+            // xorshift64* must not be seeded with 0.
+            random_state: if spec.seed == 0 {
+                0x9E37_79B9_7F4A_7C15
+            } else {
+                spec.seed
+            },
+        }
+    });
+    *client
+        .core
+        .fault_injection
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = fault_injection;
+}
+
+// ========================================================================================
+// Internal Counters
+// ========================================================================================
+
+/// Number of panics caught by a [`PanicGuard`] and reported through the failure callback.
+static CALLBACK_PANICS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Number of disconnection notifications received by PubSub tasks, each followed by a reconnect attempt.
+static PUBSUB_DISCONNECTIONS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Number of PubSub messages dropped because of an invalid structure.
+static PUBSUB_INVALID_MESSAGES: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Number of scripts reloaded after `EVALSHA` failed with `NOSCRIPT`, see [`invoke_script`].
+static SCRIPT_RELOADS: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Get counters of events that happen on the Rust side only, across all clients of the process.
+///
+/// Returns a map with:
+/// * `callback_panics` - panics caught while executing a request, reported as "Native function panicked";
+/// * `pubsub_disconnections` - disconnections observed by PubSub tasks. glide-core reconnects transparently and only
+///   notifies clients with PubSub subscriptions, so this is not a count of all disconnections of the process;
+/// * `pubsub_invalid_messages` - PubSub messages dropped because of an invalid structure. This is the only reason
+///   messages are dropped, as the PubSub channel is unbounded;
+/// * `script_reloads` - scripts reloaded by [`invoke_script`] because the server no longer had them cached.
+///
+/// # Returns
+/// * A pointer to the map, which must be freed with [`free_response`], or null if the conversion failed.
+#[unsafe(no_mangle)]
+pub extern "C" fn get_internal_counters() -> *mut ResponseValue {
+    use std::sync::atomic::Ordering;
+
+    let counters = [
+        ("callback_panics", &CALLBACK_PANICS),
+        ("pubsub_disconnections", &PUBSUB_DISCONNECTIONS),
+        ("pubsub_invalid_messages", &PUBSUB_INVALID_MESSAGES),
+        ("script_reloads", &SCRIPT_RELOADS),
+    ];
+    let value = redis::Value::Map(
+        counters
+            .into_iter()
+            .map(|(name, counter)| {
+                (
+                    redis::Value::SimpleString(name.to_string()),
+                    redis::Value::Int(counter.load(Ordering::Relaxed) as i64),
+                )
+            })
+            .collect(),
+    );
+    match ResponseValue::from_value(value) {
+        Ok(response) => Box::into_raw(Box::new(response)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Frees responses with nested values, so that `cargo miri test` checks for leaks and double frees.
+    #[test]
+    fn free_response_array_frees_every_response() {
+        let responses: Vec<*mut ResponseValue> = [
+            redis::Value::Int(1),
+            redis::Value::BulkString(b"value".to_vec()),
+            redis::Value::Array(vec![
+                redis::Value::BulkString(b"nested".to_vec()),
+                redis::Value::Map(vec![(redis::Value::Int(2), redis::Value::Nil)]),
+            ]),
+        ]
+        .into_iter()
+        .map(|value| Box::into_raw(Box::new(ResponseValue::from_value(value).unwrap())))
+        .collect();
+
+        unsafe { free_response_array(responses.as_ptr(), responses.len()) };
+    }
+
+    #[test]
+    fn free_response_array_accepts_an_empty_array() {
+        unsafe { free_response_array(std::ptr::null(), 0) };
+    }
+
+    /// A SCAN reply holding `cursor` and `keys`.
+    fn scan_reply(cursor: &str, keys: &[&str]) -> redis::Value {
+        redis::Value::Array(vec![
+            redis::Value::BulkString(cursor.into()),
+            redis::Value::Array(
+                keys.iter()
+                    .map(|key| redis::Value::BulkString(key.as_bytes().to_vec()))
+                    .collect(),
+            ),
+        ])
+    }
+
+    /// The pages of a fake keyspace, by the cursor they are fetched with.
+    fn fake_page(cursor: &str) -> redis::Value {
+        match cursor {
+            "0" => scan_reply("7", &["a", "b"]),
+            "7" => scan_reply("3", &["c"]),
+            _ => scan_reply("0", &["d"]),
+        }
+    }
+
+    #[tokio::test]
+    async fn scan_pages_delivers_each_page_before_fetching_the_next() {
+        let events = std::cell::RefCell::new(Vec::new());
+        let fetch = |cursor: String| {
+            events.borrow_mut().push(format!("fetch {cursor}"));
+            let reply = fake_page(&cursor);
+            async move { Ok(reply) }
+        };
+        let advance = |cursor: String| Ok((cursor != "0").then_some(cursor));
+        let deliver = |keys: Vec<redis::Value>| {
+            events.borrow_mut().push(format!("deliver {}", keys.len()));
+            Ok(())
+        };
+
+        scan_pages("0".to_string(), fetch, advance, deliver)
+            .await
+            .unwrap();
+
+        // No page is fetched while the previous one is still held, so at most one page is in memory.
+        assert_eq!(
+            events.into_inner(),
+            [
+                "fetch 0",
+                "deliver 2",
+                "fetch 7",
+                "deliver 1",
+                "fetch 3",
+                "deliver 1"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn scan_pages_stops_when_a_page_is_not_delivered() {
+        let mut fetches = 0;
+        let fetch = |cursor: String| {
+            fetches += 1;
+            let reply = fake_page(&cursor);
+            async move { Ok(reply) }
+        };
+        let advance = |cursor: String| Ok((cursor != "0").then_some(cursor));
+        let deliver = |_: Vec<redis::Value>| -> redis::RedisResult<()> {
+            Err(redis::RedisError::from((
+                redis::ErrorKind::TypeError,
+                "failed",
+            )))
+        };
+
+        assert!(
+            scan_pages("0".to_string(), fetch, advance, deliver)
+                .await
+                .is_err()
+        );
+        assert_eq!(fetches, 1);
+    }
+}
@@ -45,7 +45,11 @@ public abstract partial class BaseClient
         return await ScriptInvokeInternalAsync(script.Hash, options.Keys, options.Args);
     }
 
-    private async Task<ValkeyResult> ScriptInvokeInternalAsync(
+    /// <summary>
+    /// Invoke a script by its hash with <c>EVALSHA</c>, falling back to <c>EVAL</c> if the server doesn't have it cached.
+    /// </summary>
+    /// <param name="route">The route of the script, <see langword="null" /> routes it by its keys, or to a random node if it has none.</param>
+    internal async Task<ValkeyResult> ScriptInvokeInternalAsync(
         string hash,
         string[]? keys,
         string[]? args,
@@ -86,8 +90,7 @@ public abstract partial class BaseClient
                 argsCount,
                 argsPtr,
                 argsLenPtr,
-                routePtr,
-                routePtr != IntPtr.Zero ? 1UL : 0UL);
+                routePtr);
 
             // Wait for response
             IntPtr response = await message;
@@ -247,6 +250,27 @@ public abstract partial class BaseClient
         _ = await Command(Request.ScriptFlushAsync(mode));
     }
 
+    /// <summary>
+    /// Remove all scripts from the server-side script cache with <c>SCRIPT FLUSH</c>.
+    /// </summary>
+    /// <param name="mode">Whether to flush synchronously or asynchronously.</param>
+    /// <param name="route">The route of the flush, <see langword="null" /> flushes all primaries in cluster mode.</param>
+    internal async Task ScriptFlushInternalAsync(FlushMode mode, Route? route = null)
+    {
+        using FFI.Route? ffiRoute = route?.ToFfi();
+        Message message = MessageContainer.GetMessageForCall();
+        FFI.ScriptFlushFfi(ClientPointer, (ulong)message.Index, mode == FlushMode.Async, ffiRoute?.ToPtr() ?? IntPtr.Zero);
+        IntPtr response = await message;
+        try
+        {
+            _ = HandleResponse(response);
+        }
+        finally
+        {
+            FFI.FreeResponse(response);
+        }
+    }
+
     /// <inheritdoc cref="IBaseClient.ScriptShowAsync(string, CancellationToken)"/>
     public async Task<string?> ScriptShowAsync(
         string sha1Hash,
@@ -33,6 +33,9 @@ public abstract partial class BaseClient : IBaseClient
                 return;
             }
 
+            // Send auto-pipelined commands, so that they complete before pending calls are cancelled
+            FlushPendingFfi(ClientPointer);
+
             // Clean up PubSub resources
             CleanupPubSubResources();
 
@@ -168,6 +171,30 @@ public abstract partial class BaseClient : IBaseClient
             stats.SubscriptionLastSyncTimestamp);
     }
 
+    /// <summary>
+    /// Gets connection statistics of this client, keyed by metric name:
+    /// <c>total_connections</c> and <c>total_clients</c> of all clients in the process, and <c>inflight_requests</c> of
+    /// this client.
+    /// </summary>
+    /// <returns>The statistics, by metric name.</returns>
+    internal Dictionary<GlideString, object?> GetClientStatistics()
+    {
+        IntPtr response = GetClientStatisticsFfi(ClientPointer);
+        if (response == IntPtr.Zero)
+        {
+            throw new RequestException("Failed to get the client statistics");
+        }
+
+        try
+        {
+            return (Dictionary<GlideString, object?>)HandleResponse(response)!;
+        }
+        finally
+        {
+            FreeResponse(response);
+        }
+    }
+
     #endregion public methods
 
     #region protected methods
@@ -308,7 +335,8 @@ public abstract partial class BaseClient : IBaseClient
         {
             // 4. Submit request to the rust part
             Message message = MessageContainer.GetMessageForCall();
-            CommandFfi(ClientPointer, (ulong)message.Index, cmd.ToPtr(), ffiRoute?.ToPtr() ?? IntPtr.Zero);
+            CommandFfi(ClientPointer, (ulong)message.Index, cmd.ToPtr(), ffiRoute?.ToPtr() ?? IntPtr.Zero,
+                command.Timeout.HasValue, (uint)(command.Timeout?.TotalMilliseconds ?? 0));
 
             // 5. Get a response and Handle it
             response = await message;
@@ -424,7 +452,8 @@ public abstract partial class BaseClient : IBaseClient
         IntPtr channelPtr,
         ulong channelLen,
         IntPtr patternPtr,
-        ulong patternLen)
+        ulong patternLen,
+        ulong sequence)
     {
         try
         {
@@ -435,6 +464,15 @@ public abstract partial class BaseClient : IBaseClient
                 return;
             }
 
+            // Messages are delivered one at a time, so the previous sequence number is never updated concurrently.
+            ulong expectedSequence = _lastPubSubSequence + 1;
+            _lastPubSubSequence = sequence;
+            if (sequence != expectedSequence)
+            {
+                Logger.Log(Level.Warn, "PubSubCallback",
+                    $"PubSub messages lost: expected sequence number {expectedSequence}, got {sequence}");
+            }
+
             // Marshal the message from FFI callback parameters
             PubSubMessage message = MarshalPubSubMessage(
                 (PushKind)pushKind,
@@ -714,6 +752,9 @@ public abstract partial class BaseClient : IBaseClient
     /// Channel for bounded message queuing with backpressure support.
     private Channel<PubSubMessage>? _messageChannel;
 
+    /// Sequence number of the last PubSub message received from the native client, to detect lost messages.
+    private ulong _lastPubSubSequence;
+
     /// Dedicated background task for processing PubSub messages.
     private Task? _messageProcessingTask;
 
@@ -63,6 +63,21 @@ public abstract class ConnectionConfiguration
         public NodeDiscoveryMode NodeDiscoveryMode = NodeDiscoveryMode.Standard;
         public ClientSideCacheConfig? ClientSideCacheConfig;
         public AddressResolverDelegate? AddressResolver;
+        public (uint MaxBatchSize, TimeSpan FlushInterval)? AutoPipeline;
+        public TimeSpan? ReadRequestTimeout;
+        public TimeSpan? WriteRequestTimeout;
+        public string? KeyPrefix;
+        public TimeSpan? SubscribeConfirmationTimeout;
+        public bool PreserveWireTypes;
+        public (uint MaxRetries, TimeSpan Delay)? ReadOnlyErrorRetry;
+        public uint? InflightRequestsLimit;
+        public (PeriodicChecksMode Mode, TimeSpan Interval)? PeriodicChecks;
+        public ulong? MaxResponseBytes;
+        public (byte[] Certificate, byte[] Key)? ClientCertificate;
+        public bool TcpNoDelay;
+        public string? LibName;
+        public string? LibVersion;
+        public bool ValidateCommandArity;
 
         internal FFI.ConnectionConfig ToFfi() =>
             new(
@@ -85,7 +100,29 @@ public abstract class ConnectionConfiguration
                 CompressionConfig?.ToFfi(),
                 ReadOnly,
                 NodeDiscoveryMode,
-                ClientSideCacheConfig?.ToFfi()
+                ClientSideCacheConfig?.ToFfi(),
+                AutoPipeline is { } autoPipeline
+                    ? new FFI.AutoPipelineConfig(autoPipeline.MaxBatchSize, (uint)autoPipeline.FlushInterval.TotalMilliseconds)
+                    : null,
+                (uint?)ReadRequestTimeout?.TotalMilliseconds,
+                (uint?)WriteRequestTimeout?.TotalMilliseconds,
+                KeyPrefix,
+                (uint?)SubscribeConfirmationTimeout?.TotalMilliseconds,
+                PreserveWireTypes,
+                ReadOnlyErrorRetry is { } readOnlyErrorRetry
+                    ? new FFI.ReadOnlyRetryConfig(readOnlyErrorRetry.MaxRetries, (uint)readOnlyErrorRetry.Delay.TotalMilliseconds)
+                    : null,
+                InflightRequestsLimit,
+                PeriodicChecks is { } periodicChecks
+                    ? new FFI.PeriodicChecksConfig(periodicChecks.Mode, (uint)periodicChecks.Interval.TotalSeconds)
+                    : null,
+                MaxResponseBytes,
+                ClientCertificate?.Certificate ?? [],
+                ClientCertificate?.Key ?? [],
+                TcpNoDelay,
+                LibName,
+                LibVersion,
+                ValidateCommandArity
             );
     }
 
@@ -591,6 +628,54 @@ public abstract class ConnectionConfiguration
             return (T)this;
         }
 
+        /// <summary>
+        /// The client certificate and its private key, with which the client authenticates itself to servers requiring
+        /// mutual TLS. Both are PEM encoded.<br />
+        /// Requires <see cref="UseTls" /> to be enabled. If not set, the client does not authenticate itself.
+        /// </summary>
+        /// <seealso href="https://glide.valkey.io/how-to/security/tls/">Valkey GLIDE – Configure TLS</seealso>
+        public (byte[] Certificate, byte[] Key)? ClientCertificate
+        {
+            get => Config.ClientCertificate;
+            set
+            {
+                if (value is { } clientCertificate)
+                {
+                    ValidateClientCertificateData(clientCertificate.Certificate, "Client certificate", nameof(value));
+                    ValidateClientCertificateData(clientCertificate.Key, "Client key", nameof(value));
+                }
+
+                Config.ClientCertificate = value;
+            }
+        }
+
+        /// <inheritdoc cref="ClientCertificate" />
+        /// <param name="certificateData">Client certificate data</param>
+        /// <param name="keyData">Client private key data</param>
+        /// <returns>This builder for method chaining</returns>
+        /// <exception cref="ArgumentException">If the certificate or key data is null, empty, or exceeds <see cref="CertificateMaxSize"/></exception>
+        public T WithClientCertificate(byte[] certificateData, byte[] keyData)
+        {
+            ClientCertificate = (certificateData, keyData);
+            return (T)this;
+        }
+
+        private static void ValidateClientCertificateData(byte[] data, string name, string paramName)
+        {
+            if (data == null)
+            {
+                throw new ArgumentNullException(paramName, $"{name} data cannot be null");
+            }
+            else if (data.Length == 0)
+            {
+                throw new ArgumentException($"{name} data cannot be empty", paramName);
+            }
+            else if (data.Length > CertificateMaxSize)
+            {
+                throw new ArgumentException($"{name} data exceeds maximum allowed size of {CertificateMaxSize} bytes: {data.Length} bytes", paramName);
+            }
+        }
+
         #endregion
         #region Request Timeout
 
@@ -614,6 +699,70 @@ public abstract class ConnectionConfiguration
             return (T)this;
         }
 
+        #endregion
+        #region Operation Class Request Timeouts
+
+        /// <summary>
+        /// The request timeout for read-only commands, such as <c>GET</c> or <c>HGETALL</c>, so that reads can fail faster than
+        /// writes. Commands are classified by their name, including custom commands.<br />
+        /// If not explicitly set, reads use <see cref="RequestTimeout" />.
+        /// </summary>
+        public TimeSpan? ReadRequestTimeout
+        {
+            get => Config.ReadRequestTimeout;
+            set => Config.ReadRequestTimeout = value;
+        }
+
+        /// <inheritdoc cref="ReadRequestTimeout" />
+        public T WithReadRequestTimeout(TimeSpan readRequestTimeout)
+        {
+            ReadRequestTimeout = readRequestTimeout;
+            return (T)this;
+        }
+
+        /// <summary>
+        /// The request timeout for the commands which are not read-only, such as <c>SET</c> or <c>EVAL</c>, so that durable
+        /// writes can take longer than reads. Commands are classified by their name, including custom commands.<br />
+        /// If not explicitly set, writes use <see cref="RequestTimeout" />.
+        /// </summary>
+        public TimeSpan? WriteRequestTimeout
+        {
+            get => Config.WriteRequestTimeout;
+            set => Config.WriteRequestTimeout = value;
+        }
+
+        /// <inheritdoc cref="WriteRequestTimeout" />
+        public T WithWriteRequestTimeout(TimeSpan writeRequestTimeout)
+        {
+            WriteRequestTimeout = writeRequestTimeout;
+            return (T)this;
+        }
+
+        #endregion
+        #region Key Prefix
+
+        /// <summary>
+        /// A prefix prepended to every key argument, to namespace the keys of clients sharing a server.<br />
+        /// Keys are found using the known key positions of common commands, and scripts and stream info requests prefix
+        /// their keys too, as do the <c>BY</c>, <c>GET</c> and <c>STORE</c> arguments of <c>SORT</c>. Scans only return
+        /// the keys with the prefix, and return them without it. <c>KEYS</c> is rejected, use a scan instead. Commands
+        /// whose keys can't be determined statically (e.g. <c>XREAD</c>) are sent without the prefix, and a warning is
+        /// logged once per command. Other keys returned by the server keep the prefix.<br />
+        /// If not explicitly set, keys are not prefixed.
+        /// </summary>
+        public string? KeyPrefix
+        {
+            get => Config.KeyPrefix;
+            set => Config.KeyPrefix = value;
+        }
+
+        /// <inheritdoc cref="KeyPrefix" />
+        public T WithKeyPrefix(string keyPrefix)
+        {
+            KeyPrefix = keyPrefix;
+            return (T)this;
+        }
+
         #endregion
         #region Connection Timeout
 
@@ -760,6 +909,46 @@ public abstract class ConnectionConfiguration
             return (T)this;
         }
 
+        #endregion
+        #region Library Info
+
+        /// <summary>
+        /// Library name reported to the server with <c>CLIENT SETINFO LIB-NAME</c>, e.g. by a wrapper library, so that
+        /// <c>CLIENT INFO</c> attributes the connections to it.<br />
+        /// If not set, <c>GlideC#</c> is reported.
+        /// </summary>
+        public string? LibName
+        {
+            get => Config.LibName;
+            set => Config.LibName = value;
+        }
+
+        /// <inheritdoc cref="LibName" />
+        public T WithLibName(string? libName)
+        {
+            LibName = libName;
+            return (T)this;
+        }
+
+        /// <summary>
+        /// Library version reported to the server with <c>CLIENT SETINFO LIB-VER</c>.<br />
+        /// Unlike the library name, it is sent once the client is connected, so it is not reported by lazily connected
+        /// clients, and not reapplied after a reconnection.<br />
+        /// If not set, the version of the core library is reported.
+        /// </summary>
+        public string? LibVersion
+        {
+            get => Config.LibVersion;
+            set => Config.LibVersion = value;
+        }
+
+        /// <inheritdoc cref="LibVersion" />
+        public T WithLibVersion(string? libVersion)
+        {
+            LibVersion = libVersion;
+            return (T)this;
+        }
+
         #endregion
         #region Connection Retry Strategy
 
@@ -851,6 +1040,233 @@ public abstract class ConnectionConfiguration
             return (T)this;
         }
 
+        #endregion
+        #region Subscribe Confirmation Timeout
+
+        /// <summary>
+        /// How long client creation waits for the server to confirm the configured pub/sub subscriptions.
+        /// If a subscription is not confirmed in time, e.g. when subscribing during a failover, client creation fails
+        /// with a timeout error instead of silently not receiving messages.<br />
+        /// Not used with <see cref="LazyConnect" />, since subscriptions are only made once connected.<br />
+        /// If not explicitly set, client creation does not wait for confirmations.
+        /// </summary>
+        public TimeSpan? SubscribeConfirmationTimeout
+        {
+            get => Config.SubscribeConfirmationTimeout;
+            set
+            {
+                if (value <= TimeSpan.Zero)
+                {
+                    throw new ArgumentException("SubscribeConfirmationTimeout must be positive", nameof(value));
+                }
+
+                Config.SubscribeConfirmationTimeout = value;
+            }
+        }
+
+        /// <inheritdoc cref="SubscribeConfirmationTimeout" />
+        public T WithSubscribeConfirmationTimeout(TimeSpan timeout)
+        {
+            SubscribeConfirmationTimeout = timeout;
+            return (T)this;
+        }
+
+        #endregion
+        #region Preserve Wire Types
+
+        /// <summary>
+        /// Configure whether command replies keep the RESP type of their strings, e.g. for a proxy which re-serializes replies.<br />
+        /// When set, simple strings and verbatim strings are reported with their own value types instead of as plain strings,
+        /// and a verbatim string keeps its format prefix, e.g. <c>txt:</c>, as sent on the wire.<br />
+        /// If not explicitly set, a default value of <c>false</c> will be used.
+        /// </summary>
+        public bool PreserveWireTypes
+        {
+            get => Config.PreserveWireTypes;
+            set => Config.PreserveWireTypes = value;
+        }
+
+        /// <inheritdoc cref="PreserveWireTypes" />
+        public T WithPreserveWireTypes(bool preserveWireTypes)
+        {
+            PreserveWireTypes = preserveWireTypes;
+            return (T)this;
+        }
+
+        #endregion
+        #region Read Only Error Retry
+
+        /// <summary>
+        /// Configure retrying commands rejected with a <c>READONLY</c> error, as returned for a write sent to a node which is
+        /// a replica, e.g. right after a failover demoted the primary, until the client learned about the promoted primary.<br />
+        /// The command is retried up to <c>MaxRetries</c> times, waiting <c>Delay</c> before the first retry and twice as long
+        /// before every following one, so the client has time to refresh its topology. If the command is still rejected, a
+        /// <see cref="Errors.ReadOnlyException" /> is thrown.<br />
+        /// If not set, a <c>READONLY</c> error is thrown immediately.
+        /// </summary>
+        public (uint MaxRetries, TimeSpan Delay)? ReadOnlyErrorRetry
+        {
+            get => Config.ReadOnlyErrorRetry;
+            set
+            {
+                if (value is { } readOnlyErrorRetry && readOnlyErrorRetry.Delay < TimeSpan.Zero)
+                {
+                    throw new ArgumentException("READONLY error retry delay must not be negative", nameof(value));
+                }
+
+                Config.ReadOnlyErrorRetry = value;
+            }
+        }
+
+        /// <inheritdoc cref="ReadOnlyErrorRetry" />
+        public T WithReadOnlyErrorRetry(uint maxRetries, TimeSpan delay)
+        {
+            ReadOnlyErrorRetry = (maxRetries, delay);
+            return (T)this;
+        }
+
+        #endregion
+        #region Inflight Requests Limit
+
+        /// <summary>
+        /// The maximum number of requests the client has in flight at once, to bound its memory use during traffic spikes.<br />
+        /// Requests sent while the limit is reached fail immediately instead of being queued.<br />
+        /// If not explicitly set, a default value of <c>1000</c> will be used.
+        /// </summary>
+        public uint? InflightRequestsLimit
+        {
+            get => Config.InflightRequestsLimit;
+            set
+            {
+                if (value == 0)
+                {
+                    throw new ArgumentException("InflightRequestsLimit must be positive", nameof(value));
+                }
+
+                Config.InflightRequestsLimit = value;
+            }
+        }
+
+        /// <inheritdoc cref="InflightRequestsLimit" />
+        public T WithInflightRequestsLimit(uint inflightRequestsLimit)
+        {
+            InflightRequestsLimit = inflightRequestsLimit;
+            return (T)this;
+        }
+
+        #endregion
+        #region Periodic Checks
+
+        /// <summary>
+        /// Configure the periodic checks of the cluster topology, see <see cref="PeriodicChecksMode" />.<br />
+        /// The <c>Interval</c> is only used by <see cref="PeriodicChecksMode.ManualInterval" />, for which it is whole
+        /// seconds and at least one second.<br />
+        /// Ignored by standalone clients. If not set, the default checks of the core library are used.
+        /// </summary>
+        public (PeriodicChecksMode Mode, TimeSpan Interval)? PeriodicChecks
+        {
+            get => Config.PeriodicChecks;
+            set
+            {
+                if (value is { Mode: PeriodicChecksMode.ManualInterval } periodicChecks && periodicChecks.Interval < TimeSpan.FromSeconds(1))
+                {
+                    throw new ArgumentException("Periodic checks interval must be at least one second", nameof(value));
+                }
+
+                Config.PeriodicChecks = value;
+            }
+        }
+
+        /// <inheritdoc cref="PeriodicChecks" />
+        public T WithPeriodicChecks(PeriodicChecksMode mode)
+        {
+            PeriodicChecks = (mode, TimeSpan.Zero);
+            return (T)this;
+        }
+
+        /// <summary>
+        /// Check the cluster topology every <paramref name="interval" />, see <see cref="PeriodicChecks" />.
+        /// </summary>
+        public T WithPeriodicChecks(TimeSpan interval)
+        {
+            PeriodicChecks = (PeriodicChecksMode.ManualInterval, interval);
+            return (T)this;
+        }
+
+        #endregion
+        #region Max Response Bytes
+
+        /// <summary>
+        /// The maximal size of a reply in bytes, counting the bytes of all its strings, to contain a misbehaving server.<br />
+        /// A command whose reply is larger fails with a <see cref="Errors.RequestException" />. The reply is still read
+        /// by the core library before it is checked, so the limit keeps it from reaching the application but does not
+        /// prevent it from being allocated.<br />
+        /// If not set, replies are not limited.
+        /// </summary>
+        public ulong? MaxResponseBytes
+        {
+            get => Config.MaxResponseBytes;
+            set
+            {
+                if (value == 0)
+                {
+                    throw new ArgumentException("MaxResponseBytes must be positive", nameof(value));
+                }
+
+                Config.MaxResponseBytes = value;
+            }
+        }
+
+        /// <inheritdoc cref="MaxResponseBytes" />
+        public T WithMaxResponseBytes(ulong maxResponseBytes)
+        {
+            MaxResponseBytes = maxResponseBytes;
+            return (T)this;
+        }
+
+        #endregion
+        #region TCP No Delay
+
+        /// <summary>
+        /// Configure whether Nagle's algorithm is disabled on the connections, so that small requests are sent right away
+        /// instead of being coalesced, trading bandwidth for latency.<br />
+        /// If not explicitly set, a default value of <c>false</c> will be used.
+        /// </summary>
+        public bool TcpNoDelay
+        {
+            get => Config.TcpNoDelay;
+            set => Config.TcpNoDelay = value;
+        }
+
+        /// <inheritdoc cref="TcpNoDelay" />
+        public T WithTcpNoDelay(bool tcpNoDelay = true)
+        {
+            TcpNoDelay = tcpNoDelay;
+            return (T)this;
+        }
+
+        #endregion
+        #region Command Arity Validation
+
+        /// <summary>
+        /// Configure whether the number of arguments of commands taking repeated groups of arguments, such as <c>MSET</c>,
+        /// <c>HSET</c>, <c>GEOADD</c> and <c>ZADD</c>, is checked before sending them, so that a malformed command fails with a
+        /// clear error instead of the server's generic <c>wrong number of arguments</c>. Other commands are left to the server.<br />
+        /// If not explicitly set, a default value of <c>false</c> will be used.
+        /// </summary>
+        public bool ValidateCommandArity
+        {
+            get => Config.ValidateCommandArity;
+            set => Config.ValidateCommandArity = value;
+        }
+
+        /// <inheritdoc cref="ValidateCommandArity" />
+        public T WithCommandArityValidation(bool validateCommandArity = true)
+        {
+            ValidateCommandArity = validateCommandArity;
+            return (T)this;
+        }
+
         #endregion
         #region Compression
 
@@ -916,6 +1332,48 @@ public abstract class ConnectionConfiguration
             return (T)this;
         }
 
+        #endregion
+        #region Auto Pipeline
+
+        /// <summary>
+        /// Configure opt-in auto-pipelining. When enabled, commands sent without an explicit route are queued
+        /// and flushed together as a single non-atomic batch once <c>MaxBatchSize</c> commands are queued or
+        /// <c>FlushInterval</c> elapsed since the first queued command, whichever comes first.
+        /// <para />
+        /// Each command still completes with its own result, and a server error only fails the command that caused it.
+        /// Queued commands are sent in the order they were issued, but commands with an explicit route bypass the
+        /// queue and may be sent before them.<br />
+        /// If not set, auto-pipelining is disabled.
+        /// </summary>
+        public (uint MaxBatchSize, TimeSpan FlushInterval)? AutoPipeline
+        {
+            get => Config.AutoPipeline;
+            set
+            {
+                if (value is { } autoPipeline)
+                {
+                    if (autoPipeline.MaxBatchSize == 0)
+                    {
+                        throw new ArgumentException("Auto-pipeline max batch size must be positive", nameof(value));
+                    }
+
+                    if (autoPipeline.FlushInterval < TimeSpan.FromMilliseconds(1))
+                    {
+                        throw new ArgumentException("Auto-pipeline flush interval must be at least one millisecond", nameof(value));
+                    }
+                }
+
+                Config.AutoPipeline = value;
+            }
+        }
+
+        /// <inheritdoc cref="AutoPipeline" />
+        public T WithAutoPipeline(uint maxBatchSize, TimeSpan flushInterval)
+        {
+            AutoPipeline = (maxBatchSize, flushInterval);
+            return (T)this;
+        }
+
         #endregion
 
         internal ConnectionConfig Build() => Config;
@@ -35,7 +35,7 @@ public static class Errors
     /// <summary>
     /// An error on Valkey service-side that was reported during a request.
     /// </summary>
-    public sealed class RequestException : GlideException
+    public class RequestException : GlideException
     {
         /// <summary>
         /// Initializes a new instance of the <see cref="RequestException"/> class.
@@ -108,6 +108,33 @@ public static class Errors
         public ExecAbortException(string message, Exception innerException) : base(message, innerException) { }
     }
 
+    /// <summary>
+    /// An error on Valkey service-side that is thrown when a write is sent to a read-only replica, e.g. right after a failover.<br />
+    /// These errors can be temporary, see <see cref="ConnectionConfiguration.ClientConfigurationBuilder{T}.ReadOnlyErrorRetry" /> to retry them.<br />
+    /// It is a <see cref="RequestException" />, so code catching request errors catches it as well.
+    /// </summary>
+    public sealed class ReadOnlyException : RequestException
+    {
+        /// <summary>
+        /// Initializes a new instance of the <see cref="ReadOnlyException"/> class.
+        /// </summary>
+        public ReadOnlyException() : base() { }
+
+        /// <summary>
+        /// Initializes a new instance of the <see cref="ReadOnlyException"/> class with a specified error message.
+        /// </summary>
+        /// <param name="message">The message that describes the error.</param>
+        public ReadOnlyException(string message) : base(message) { }
+
+        /// <summary>
+        /// Initializes a new instance of the <see cref="ReadOnlyException"/> class with a specified
+        /// error message and a reference to the inner exception that is the cause of this exception.
+        /// </summary>
+        /// <param name="message">The message that describes the error.</param>
+        /// <param name="innerException">The exception that is the cause of the current exception.</param>
+        public ReadOnlyException(string message, Exception innerException) : base(message, innerException) { }
+    }
+
     /// <summary>
     /// A timeout from Glide to Valkey service that is thrown when a request times out.
     /// </summary>
@@ -187,6 +214,8 @@ public static class Errors
 
     internal static GlideException Create(RequestErrorType type, string message) => type switch
     {
+        // Rust reports a READONLY error as sent by the server, e.g. "READONLY You can't write against a read only replica."
+        RequestErrorType.Unspecified when message.StartsWith("READONLY ", StringComparison.Ordinal) => new ReadOnlyException(message),
         RequestErrorType.Unspecified => new RequestException(message),
         RequestErrorType.ExecAbort => new ExecAbortException(message),
         RequestErrorType.Timeout => new TimeoutException(message),
@@ -28,6 +28,29 @@ internal class Cmd<R, T> : ICmd
     public readonly RequestType Request;
     public readonly ArgsArray ArgsArray;
 
+    /// <summary>
+    /// The operation class selecting the request timeout of this command.
+    /// </summary>
+    public OperationClass OperationClass { get; init; } = OperationClass.Unspecified;
+
+    /// <summary>
+    /// The timeout of this command, replacing the timeout of its <see cref="OperationClass" /> and the client's request
+    /// timeout if set. It can be shorter or longer than them, e.g. for a blocking command waiting longer.
+    /// </summary>
+    public TimeSpan? Timeout { get; init; }
+
+    /// <summary>
+    /// Whether a string reply holding a number is decoded to a <see cref="long" /> or <see cref="double" /> before it reaches the converter.
+    /// Only set for commands known to reply with numbers, since arbitrary data may look like a number.
+    /// </summary>
+    public bool DecodeNumbers { get; init; }
+
+    /// <summary>
+    /// Whether the values of this command are sent and its reply returned as is, bypassing the client's compression,
+    /// e.g. for already compressed data.
+    /// </summary>
+    public bool SkipCompression { get; init; }
+
 #pragma warning disable IDE0046 // Convert to conditional expression
     public Func<object?, object?> GetConverter() => value =>
     {
@@ -55,7 +78,7 @@ internal class Cmd<R, T> : ICmd
     };
 #pragma warning restore IDE0046 // Convert to conditional expression
 
-    public Cmd ToFfi() => new(Request, ArgsArray.Args);
+    public Cmd ToFfi() => new(Request, ArgsArray.Args, OperationClass, DecodeNumbers, SkipCompression);
 
     public new string ToString() => $"{Request} [{string.Join(' ', ArgsArray.Args?.ToStrings() ?? [])}]";
 
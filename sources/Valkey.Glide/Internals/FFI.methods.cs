@@ -17,6 +17,9 @@ internal partial class FFI
     /// <param name="channelLen">The length of the channel name in bytes.</param>
     /// <param name="patternPtr">Pointer to the raw pattern bytes (null if no pattern).</param>
     /// <param name="patternLen">The length of the pattern in bytes (0 if no pattern).</param>
+    /// <param name="sequence">The number of the message among those of this callback, starting at 1 and incremented by 1
+    /// for every message delivered to or dropped for it, including across reconnections. A gap means a message was lost.
+    /// 0 for subscription confirmations, which are not numbered.</param>
     [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
     internal delegate void PubSubMessageCallback(
         uint pushKind,
@@ -25,20 +28,97 @@ internal partial class FFI
         IntPtr channelPtr,
         ulong channelLen,
         IntPtr patternPtr,
-        ulong patternLen);
+        ulong patternLen,
+        ulong sequence);
+
+    [LibraryImport("libglide_rs", EntryPoint = "explain_command")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial IntPtr ExplainCommandFfi(IntPtr cmdInfo);
 
     [LibraryImport("libglide_rs", EntryPoint = "command")]
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
-    public static partial void CommandFfi(IntPtr client, ulong index, IntPtr cmdInfo, IntPtr routeInfo);
+    public static partial void CommandFfi(IntPtr client, ulong index, IntPtr cmdInfo, IntPtr routeInfo, [MarshalAs(UnmanagedType.U1)] bool hasTimeout, uint timeoutMs);
+
+    /// <summary>
+    /// FFI callback delegate receiving the duration of a command.
+    /// </summary>
+    /// <param name="index">The callback index of the command.</param>
+    /// <param name="elapsedNs">The time in nanoseconds from sending the command until its reply or error was received.</param>
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    internal delegate void CommandTimingCallback(ulong index, ulong elapsedNs);
+
+    [LibraryImport("libglide_rs", EntryPoint = "set_command_timing_callback")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void SetCommandTimingCallbackFfi(IntPtr client, IntPtr callback);
+
+    [LibraryImport("libglide_rs", EntryPoint = "command_traced")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial IntPtr CommandTracedFfi(IntPtr client, ulong index, IntPtr cmdInfo, IntPtr routeInfo);
+
+    [LibraryImport("libglide_rs", EntryPoint = "subscribe_with_callback")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void SubscribeWithCallbackFfi(IntPtr client, ulong index, IntPtr cmdInfo, IntPtr pubsubCallback);
+
+    [LibraryImport("libglide_rs", EntryPoint = "command_with_deadline")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void CommandWithDeadlineFfi(IntPtr client, ulong index, IntPtr cmdInfo, IntPtr routeInfo, ulong deadlineMs);
+
+    [LibraryImport("libglide_rs", EntryPoint = "command_into_buffer")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void CommandIntoBufferFfi(IntPtr client, ulong index, IntPtr cmdInfo, IntPtr routeInfo, IntPtr buffer, UIntPtr capacity);
+
+    [LibraryImport("libglide_rs", EntryPoint = "list_blocking_commands")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial IntPtr ListBlockingCommandsFfi(IntPtr client, ulong thresholdMs);
+
+    [LibraryImport("libglide_rs", EntryPoint = "get_effective_config")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial IntPtr GetEffectiveConfigFfi(IntPtr client);
+
+    [LibraryImport("libglide_rs", EntryPoint = "command_async")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial ulong CommandAsyncFfi(IntPtr client, IntPtr cmdInfo, IntPtr routeInfo);
+
+    [LibraryImport("libglide_rs", EntryPoint = "poll_command")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial CommandPollStatus PollCommandFfi(IntPtr client, ulong handle, out IntPtr value, out IntPtr error, out RequestErrorType errorType);
+
+    [LibraryImport("libglide_rs", EntryPoint = "drop_command_handle")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void DropCommandHandleFfi(IntPtr client, ulong handle);
+
+    [LibraryImport("libglide_rs", EntryPoint = "command_per_node")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void CommandPerNodeFfi(IntPtr client, ulong index, IntPtr cmdInfo, IntPtr routeInfo);
+
+    [LibraryImport("libglide_rs", EntryPoint = "command_by_address")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void CommandByAddressFfi(IntPtr client, ulong index, IntPtr cmdInfo, IntPtr host, ushort port);
+
+    [LibraryImport("libglide_rs", EntryPoint = "command_preferring_node")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void CommandPreferringNodeFfi(IntPtr client, ulong index, IntPtr cmdInfo, IntPtr node, PreferredNodeFallback fallback);
 
     [LibraryImport("libglide_rs", EntryPoint = "batch")]
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial void BatchFfi(IntPtr client, ulong index, IntPtr batch, [MarshalAs(UnmanagedType.U1)] bool raiseOnError, IntPtr opts);
 
+    [LibraryImport("libglide_rs", EntryPoint = "transaction_with_retry")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void TransactionWithRetryFfi(IntPtr client, ulong index, ulong keysCount, IntPtr keys, IntPtr keysLen, IntPtr batch, [MarshalAs(UnmanagedType.U1)] bool raiseOnError, IntPtr opts, uint maxRetries);
+
+    [LibraryImport("libglide_rs", EntryPoint = "transaction_per_primary")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void TransactionPerPrimaryFfi(IntPtr client, ulong index, IntPtr batch, [MarshalAs(UnmanagedType.U1)] bool raiseOnError, IntPtr opts);
+
     [LibraryImport("libglide_rs", EntryPoint = "free_response")]
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial void FreeResponse(IntPtr responsePtr);
 
+    [LibraryImport("libglide_rs", EntryPoint = "free_response_array")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void FreeResponseArray(IntPtr[] responsePtrs, UIntPtr count);
+
     [LibraryImport("libglide_rs", EntryPoint = "free_string")]
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial void FreeString(IntPtr strPtr);
@@ -51,6 +131,14 @@ internal partial class FFI
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial void CloseClientFfi(IntPtr client);
 
+    [LibraryImport("libglide_rs", EntryPoint = "flush_pending")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void FlushPendingFfi(IntPtr client);
+
+    [LibraryImport("libglide_rs", EntryPoint = "close_client_with_timeout")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void CloseClientWithTimeoutFfi(IntPtr client, uint inflightTimeoutMs);
+
     [LibraryImport("libglide_rs", EntryPoint = "store_script")]
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial IntPtr StoreScriptFfi(IntPtr scriptPtr, UIntPtr scriptLen);
@@ -59,6 +147,10 @@ internal partial class FFI
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial IntPtr DropScriptFfi(IntPtr hashPtr, UIntPtr hashLen);
 
+    [LibraryImport("libglide_rs", EntryPoint = "export_script_hashes")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial IntPtr ExportScriptHashesFfi();
+
     [LibraryImport("libglide_rs", EntryPoint = "free_script_hash_buffer")]
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial void FreeScriptHashBuffer(IntPtr hashBuffer);
@@ -75,13 +167,24 @@ internal partial class FFI
         ulong argsCount,
         IntPtr args,
         IntPtr argsLen,
-        IntPtr routeInfo,
-        ulong routeInfoLen);
+        IntPtr routeInfo);
+
+    [LibraryImport("libglide_rs", EntryPoint = "script_flush")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void ScriptFlushFfi(IntPtr client, ulong index, [MarshalAs(UnmanagedType.U1)] bool asyncMode, IntPtr routeInfo);
 
     [LibraryImport("libglide_rs", EntryPoint = "request_cluster_scan")]
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial void RequestClusterScanFfi(IntPtr client, ulong index, IntPtr cursor, ulong argCount, IntPtr args, IntPtr argLengths);
 
+    [LibraryImport("libglide_rs", EntryPoint = "request_scan")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void RequestScanFfi(IntPtr client, ulong index, IntPtr cursor, ulong argCount, IntPtr args, IntPtr argLengths);
+
+    [LibraryImport("libglide_rs", EntryPoint = "scan_all_keys")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void ScanAllKeysFfi(IntPtr client, ulong index, ulong argCount, IntPtr args, IntPtr argLengths, IntPtr batchCallback);
+
     [LibraryImport("libglide_rs", EntryPoint = "remove_cluster_scan_cursor")]
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial void RemoveClusterScanCursorFfi(IntPtr cursorId);
@@ -90,6 +193,10 @@ internal partial class FFI
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial void UpdateConnectionPasswordFfi(IntPtr client, ulong index, IntPtr password, [MarshalAs(UnmanagedType.U1)] bool immediateAuth);
 
+    [LibraryImport("libglide_rs", EntryPoint = "update_connection_credentials")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void UpdateConnectionCredentialsFfi(IntPtr client, ulong index, IntPtr username, IntPtr password, [MarshalAs(UnmanagedType.U1)] bool immediateAuth);
+
     [LibraryImport("libglide_rs", EntryPoint = "refresh_iam_token")]
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial void RefreshIamTokenFfi(IntPtr client, ulong index);
@@ -98,16 +205,193 @@ internal partial class FFI
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial Statistics GetStatisticsFfi();
 
+    [LibraryImport("libglide_rs", EntryPoint = "get_client_statistics")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial IntPtr GetClientStatisticsFfi(IntPtr client);
+
+    /// <summary>
+    /// FFI callback delegate for errors which are not tied to a request.
+    /// </summary>
+    /// <param name="errorType">The type of the error.</param>
+    /// <param name="messagePtr">Pointer to the error message, freed by Rust once the callback returns.</param>
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    internal delegate void GlobalErrorCallback(RequestErrorType errorType, IntPtr messagePtr);
+
+    [LibraryImport("libglide_rs", EntryPoint = "set_global_error_callback")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void SetGlobalErrorCallbackFfi(IntPtr callback);
+
+    /// <summary>
+    /// Only exported when the native library is built with the <c>fault-injection</c> feature.
+    /// </summary>
+    [LibraryImport("libglide_rs", EntryPoint = "set_fault_injection")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void SetFaultInjectionFfi(IntPtr client, IntPtr spec);
+
+    [LibraryImport("libglide_rs", EntryPoint = "get_internal_counters")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial IntPtr GetInternalCountersFfi();
+
+    [LibraryImport("libglide_rs", EntryPoint = "get_pubsub_message")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial IntPtr GetPubSubMessageFfi(IntPtr client);
+
+    [LibraryImport("libglide_rs", EntryPoint = "drain_pubsub_messages")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial IntPtr DrainPubSubMessagesFfi(IntPtr client, uint maxCount);
+
+    [LibraryImport("libglide_rs", EntryPoint = "get_pubsub_dropped_count")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial ulong GetPubSubDroppedCountFfi(IntPtr client, [MarshalAs(UnmanagedType.U1)] bool reset);
+
     [LibraryImport("libglide_rs", EntryPoint = "get_cache_metrics")]
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial void GetCacheMetricsFfi(IntPtr client, ulong index, uint metricsType);
 
+    [LibraryImport("libglide_rs", EntryPoint = "failover")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void FailoverFfi(
+        IntPtr client,
+        ulong index,
+        IntPtr host,
+        ushort port,
+        [MarshalAs(UnmanagedType.U1)] bool force,
+        [MarshalAs(UnmanagedType.U1)] bool abort,
+        [MarshalAs(UnmanagedType.U1)] bool hasTimeout,
+        uint timeoutMs,
+        IntPtr routeInfo);
+
+    [LibraryImport("libglide_rs", EntryPoint = "wait_aof")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void WaitAofFfi(
+        IntPtr client,
+        ulong index,
+        uint numLocal,
+        uint numReplicas,
+        ulong timeoutMs,
+        IntPtr routeInfo);
+
+    [LibraryImport("libglide_rs", EntryPoint = "get_cluster_epoch")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void GetClusterEpochFfi(IntPtr client, ulong index);
+
+    [LibraryImport("libglide_rs", EntryPoint = "get_tracking_info")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void GetTrackingInfoFfi(IntPtr client, ulong index, IntPtr routeInfo);
+
+    [LibraryImport("libglide_rs", EntryPoint = "xinfo_stream")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void XInfoStreamFfi(IntPtr client, ulong index, IntPtr key, UIntPtr keyLen, [MarshalAs(UnmanagedType.U1)] bool full);
+
+    [LibraryImport("libglide_rs", EntryPoint = "xinfo_groups")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void XInfoGroupsFfi(IntPtr client, ulong index, IntPtr key, UIntPtr keyLen);
+
+    [LibraryImport("libglide_rs", EntryPoint = "write_then_consistent_read")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void WriteThenConsistentReadFfi(
+        IntPtr client,
+        ulong index,
+        IntPtr writeCmdInfo,
+        IntPtr readCmdInfo,
+        uint numReplicas,
+        ulong timeoutMs);
+
+    [LibraryImport("libglide_rs", EntryPoint = "probe_server")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void ProbeServerFfi(IntPtr client, ulong index, IntPtr routeInfo);
+
+    [LibraryImport("libglide_rs", EntryPoint = "hello")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void HelloFfi(IntPtr client, ulong index, IntPtr routeInfo);
+
+    [LibraryImport("libglide_rs", EntryPoint = "set_client_info")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void SetClientInfoFfi(
+        IntPtr client,
+        ulong index,
+        IntPtr attributes,
+        IntPtr values,
+        UIntPtr count);
+
+    [LibraryImport("libglide_rs", EntryPoint = "get_database")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial long GetDatabaseFfi(IntPtr client);
+
+    [LibraryImport("libglide_rs", EntryPoint = "select_database")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void SelectDatabaseFfi(IntPtr client, ulong index, long databaseId);
+
+    [LibraryImport("libglide_rs", EntryPoint = "compute_slot")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial ushort ComputeSlotFfi(IntPtr key, UIntPtr keyLen);
+
+    [LibraryImport("libglide_rs", EntryPoint = "rename")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void RenameFfi(IntPtr client, ulong index, IntPtr key, UIntPtr keyLen, IntPtr newKey, UIntPtr newKeyLen);
+
+    [LibraryImport("libglide_rs", EntryPoint = "rename_nx")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void RenameNxFfi(IntPtr client, ulong index, IntPtr key, UIntPtr keyLen, IntPtr newKey, UIntPtr newKeyLen);
+
+    [LibraryImport("libglide_rs", EntryPoint = "smove")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void SMoveFfi(IntPtr client, ulong index, IntPtr source, UIntPtr sourceLen, IntPtr destination, UIntPtr destinationLen, IntPtr member, UIntPtr memberLen);
+
+    [LibraryImport("libglide_rs", EntryPoint = "lmove")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void LMoveFfi(IntPtr client, ulong index, IntPtr source, UIntPtr sourceLen, IntPtr destination, UIntPtr destinationLen, ListDirection whereFrom, ListDirection whereTo);
+
+    [LibraryImport("libglide_rs", EntryPoint = "blmove")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void BLMoveFfi(IntPtr client, ulong index, IntPtr source, UIntPtr sourceLen, IntPtr destination, UIntPtr destinationLen, ListDirection whereFrom, ListDirection whereTo, double timeoutSecs);
+
+    [LibraryImport("libglide_rs", EntryPoint = "estimate_memory")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void EstimateMemoryFfi(IntPtr client, ulong index, ulong keysCount, IntPtr keys, IntPtr keysLen, [MarshalAs(UnmanagedType.U1)] bool hasSamples, uint samples);
+
+    [LibraryImport("libglide_rs", EntryPoint = "get_client_mode")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void GetClientModeFfi(IntPtr client, ulong index);
+
+    [LibraryImport("libglide_rs", EntryPoint = "get_protocol_version")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void GetProtocolVersionFfi(IntPtr client, ulong index);
+
+    [LibraryImport("libglide_rs", EntryPoint = "validate_route")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void ValidateRouteFfi(IntPtr client, ulong index, IntPtr routeInfo);
+
+    [LibraryImport("libglide_rs", EntryPoint = "measure_node_latencies")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void MeasureNodeLatenciesFfi(IntPtr client, ulong index, uint samples);
+
+    /// <summary>
+    /// FFI callback delegate receiving one batch of a collection fetched incrementally.
+    /// </summary>
+    /// <param name="index">The callback index of the fetch the batch belongs to.</param>
+    /// <param name="batchPtr">Pointer to the batch response, which must be freed with <see cref="FreeResponse" />.</param>
+    [UnmanagedFunctionPointer(CallingConvention.Cdecl)]
+    internal delegate void FetchBatchCallback(ulong index, IntPtr batchPtr);
+
+    [LibraryImport("libglide_rs", EntryPoint = "safe_smembers")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void SafeSMembersFfi(IntPtr client, ulong index, IntPtr key, UIntPtr keyLen, ulong threshold, IntPtr batchCallback);
+
+    [LibraryImport("libglide_rs", EntryPoint = "safe_hgetall")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void SafeHGetAllFfi(IntPtr client, ulong index, IntPtr key, UIntPtr keyLen, ulong threshold, IntPtr batchCallback);
+
     #region OpenTelemetry
 
     [LibraryImport("libglide_rs", EntryPoint = "init_otel")]
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial IntPtr InitOpenTelemetryFfi(IntPtr config);
 
+    [LibraryImport("libglide_rs", EntryPoint = "shutdown")]
+    [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
+    public static partial void ShutdownFfi(IntPtr client, uint timeoutMs);
+
     [LibraryImport("libglide_rs", EntryPoint = "create_otel_span")]
     [UnmanagedCallConv(CallConvs = [typeof(CallConvCdecl)])]
     public static partial IntPtr CreateOpenTelemetrySpanFfi(uint requestType);
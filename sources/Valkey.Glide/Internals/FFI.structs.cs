@@ -47,20 +47,38 @@ internal partial class FFI
         private GCHandle _pinnedArgs;
         private nuint[] _lengths = [];
         private GCHandle _pinnedLengths;
+        private GCHandle[] _pinnedValues = [];
         private readonly GlideString[] _args;
         private CmdInfo _cmd;
 
-        public Cmd(RequestType requestType, GlideString[] arguments)
+        // Arguments of at least this size are pinned and passed in place instead of being copied to unmanaged memory.
+        // Such arrays are allocated on the large object heap, which isn't compacted by default, so pinning them doesn't hinder the GC.
+        private const int PinnedArgThreshold = 85_000;
+
+        public Cmd(RequestType requestType, GlideString[] arguments, OperationClass operationClass = OperationClass.Unspecified, bool decodeNumbers = false, bool skipCompression = false)
         {
-            _cmd = new() { RequestType = requestType, ArgCount = (nuint)arguments.Length };
+            _cmd = new() { RequestType = requestType, ArgCount = (nuint)arguments.Length, OperationClass = operationClass, DecodeNumbers = decodeNumbers, SkipCompression = skipCompression };
             _args = arguments;
         }
 
+        /// <summary>
+        /// Whether the argument at <paramref name="index" /> was pinned in place rather than copied, once marshalled.
+        /// Exposed for testing the large argument path.
+        /// </summary>
+        internal bool IsArgPinned(int index) => index < _pinnedValues.Length && _pinnedValues[index].IsAllocated;
+
         protected override void FreeMemory()
         {
-            for (nuint i = 0; i < _cmd.ArgCount; i++)
+            for (int i = 0; i < (int)_cmd.ArgCount; i++)
             {
-                Marshal.FreeHGlobal(_argPtrs[i]);
+                if (i < _pinnedValues.Length && _pinnedValues[i].IsAllocated)
+                {
+                    _pinnedValues[i].Free();
+                }
+                else
+                {
+                    Marshal.FreeHGlobal(_argPtrs[i]);
+                }
             }
             _pinnedArgs.Free();
             PoolReturn(_argPtrs);
@@ -77,9 +95,21 @@ internal partial class FFI
             // 2. Copy data into allocated array in unmanaged memory
             for (int i = 0; i < _args.Length; i++)
             {
-                // 2.1 Copy an argument
-                _argPtrs[i] = Marshal.AllocHGlobal(_args[i].Length);
-                Marshal.Copy(_args[i].Bytes, 0, _argPtrs[i], _args[i].Length);
+                // 2.1 Copy an argument, or pin it in place if it is large, since Rust copies it into the command anyway
+                if (_args[i].Length >= PinnedArgThreshold)
+                {
+                    if (_pinnedValues.Length == 0)
+                    {
+                        _pinnedValues = new GCHandle[_args.Length];
+                    }
+                    _pinnedValues[i] = GCHandle.Alloc(_args[i].Bytes, GCHandleType.Pinned);
+                    _argPtrs[i] = _pinnedValues[i].AddrOfPinnedObject();
+                }
+                else
+                {
+                    _argPtrs[i] = Marshal.AllocHGlobal(_args[i].Length);
+                    Marshal.Copy(_args[i].Bytes, 0, _argPtrs[i], _args[i].Length);
+                }
                 // 2.2 Copy arg's len
                 _lengths[i] = (nuint)_args[i].Length;
             }
@@ -139,28 +169,48 @@ internal partial class FFI
     // A wrapper for a route
     internal class Route : Marshallable
     {
-        private readonly RouteInfo _info;
+        private RouteInfo _info;
+        private readonly GlideString? _slotKey;
 
         public Route(
             RouteType requestType,
             (int slotId, SlotType slotType)? slotIdInfo = null,
-            (string slotKey, SlotType slotType)? slotKeyInfo = null,
+            (GlideString slotKey, SlotType slotType)? slotKeyInfo = null,
             (string host, int port)? address = null)
         {
+            _slotKey = slotKeyInfo?.slotKey;
             _info = new()
             {
                 Type = requestType,
                 SlotId = slotIdInfo?.slotId ?? 0,
-                SlotKey = slotKeyInfo?.slotKey,
+                SlotKey = IntPtr.Zero,
+                SlotKeyLen = 0,
                 SlotType = slotIdInfo?.slotType ?? slotKeyInfo?.slotType ?? 0,
                 Host = address?.host,
                 Port = address?.port ?? 0,
             };
         }
 
-        protected override void FreeMemory() { }
+        protected override void FreeMemory()
+        {
+            if (_info.SlotKey != IntPtr.Zero)
+            {
+                Marshal.FreeHGlobal(_info.SlotKey);
+                _info.SlotKey = IntPtr.Zero;
+            }
+        }
 
-        protected override IntPtr AllocateAndCopy() => StructToPtr(_info);
+        protected override IntPtr AllocateAndCopy()
+        {
+            // The slot key is passed with its length, since binary keys may contain NUL bytes
+            if (_slotKey is not null)
+            {
+                _info.SlotKey = Marshal.AllocHGlobal(_slotKey.Length);
+                Marshal.Copy(_slotKey.Bytes, 0, _info.SlotKey, _slotKey.Length);
+                _info.SlotKeyLen = (nuint)_slotKey.Length;
+            }
+            return StructToPtr(_info);
+        }
     }
 
     internal class BatchOptions : Marshallable
@@ -172,7 +222,12 @@ internal partial class FFI
             bool? retryServerError = false,
             bool? retryConnectionError = false,
             uint? timeout = null,
-            Route? route = null
+            Route? route = null,
+            bool sequential = false,
+            bool isReadOnly = false,
+            bool validateReadOnly = false,
+            bool stopOnFirstError = false,
+            bool routeByFirstKey = false
             )
         {
             _route = route;
@@ -183,6 +238,11 @@ internal partial class FFI
                 HasTimeout = timeout is not null,
                 Timeout = timeout ?? 0,
                 Route = IntPtr.Zero,
+                Sequential = sequential,
+                IsReadOnly = isReadOnly,
+                ValidateReadOnly = validateReadOnly,
+                StopOnFirstError = stopOnFirstError,
+                RouteByFirstKey = routeByFirstKey,
             };
         }
 
@@ -206,6 +266,90 @@ internal partial class FFI
         /// </summary>
         internal NodeDiscoveryMode NodeDiscoveryMode => _request.NodeDiscoveryMode;
 
+        /// <summary>
+        /// The pub/sub reconciliation interval marshalled into the underlying FFI request, if set.
+        /// Exposed for testing that the value is correctly wired through to the FFI layer.
+        /// </summary>
+        internal uint? PubSubReconciliationIntervalMs =>
+            _request.HasPubSubReconciliationIntervalMs ? _request.PubSubReconciliationIntervalMs : null;
+
+        /// <summary>
+        /// The auto-pipelining configuration marshalled into the underlying FFI request, if enabled.
+        /// Exposed for testing that the value is correctly wired through to the FFI layer.
+        /// </summary>
+        internal AutoPipelineConfig? AutoPipelineConfig =>
+            _request.HasAutoPipelineConfig ? _request.AutoPipelineConfig : null;
+
+        /// <summary>
+        /// Whether the underlying FFI request asks for replies to keep their RESP string type.
+        /// Exposed for testing that the value is correctly wired through to the FFI layer.
+        /// </summary>
+        internal bool PreserveWireTypes => _request.PreserveWireTypes;
+
+        /// <summary>
+        /// The READONLY error retry configuration marshalled into the underlying FFI request, if set.
+        /// Exposed for testing that the value is correctly wired through to the FFI layer.
+        /// </summary>
+        internal ReadOnlyRetryConfig? ReadOnlyRetryConfig =>
+            _request.HasReadOnlyRetryConfig ? _request.ReadOnlyRetryConfig : null;
+
+        /// <summary>
+        /// The inflight requests limit marshalled into the underlying FFI request, if set.
+        /// Exposed for testing that the value is correctly wired through to the FFI layer.
+        /// </summary>
+        internal uint? InflightRequestsLimit =>
+            _request.HasInflightRequestsLimit ? _request.InflightRequestsLimit : null;
+
+        /// <summary>
+        /// The periodic topology checks configuration marshalled into the underlying FFI request, if set.
+        /// Exposed for testing that the value is correctly wired through to the FFI layer.
+        /// </summary>
+        internal PeriodicChecksConfig? PeriodicChecksConfig =>
+            _request.HasPeriodicChecksConfig ? _request.PeriodicChecksConfig : null;
+
+        /// <summary>
+        /// The maximal response size marshalled into the underlying FFI request, if set.
+        /// Exposed for testing that the value is correctly wired through to the FFI layer.
+        /// </summary>
+        internal ulong? MaxResponseBytes =>
+            _request.HasMaxResponseBytes ? _request.MaxResponseBytes : null;
+
+        /// <summary>
+        /// The client certificate marshalled into the underlying FFI request, empty if not set.
+        /// Exposed for testing that the value is correctly wired through to the FFI layer.
+        /// </summary>
+        internal byte[] ClientCertificate => CopyBytes(_request.ClientCertPtr, _request.ClientCertLen);
+
+        /// <summary>
+        /// The client private key marshalled into the underlying FFI request, empty if not set.
+        /// Exposed for testing that the value is correctly wired through to the FFI layer.
+        /// </summary>
+        internal byte[] ClientKey => CopyBytes(_request.ClientKeyPtr, _request.ClientKeyLen);
+
+        /// <summary>
+        /// Whether the underlying FFI request disables Nagle's algorithm.
+        /// Exposed for testing that the value is correctly wired through to the FFI layer.
+        /// </summary>
+        internal bool TcpNoDelay => _request.TcpNoDelay;
+
+        /// <summary>
+        /// The library name marshalled into the underlying FFI request, if set.
+        /// Exposed for testing that the value is correctly wired through to the FFI layer.
+        /// </summary>
+        internal string? LibName => _request.LibName;
+
+        /// <summary>
+        /// The library version marshalled into the underlying FFI request, if set.
+        /// Exposed for testing that the value is correctly wired through to the FFI layer.
+        /// </summary>
+        internal string? LibVersion => _request.LibVersion;
+
+        /// <summary>
+        /// Whether the underlying FFI request validates the arity of commands before sending them.
+        /// Exposed for testing that the value is correctly wired through to the FFI layer.
+        /// </summary>
+        internal bool ValidateCommandArity => _request.ValidateCommandArity;
+
         public ConnectionConfig(
             List<NodeAddress> addresses,
             TlsMode tlsMode,
@@ -226,7 +370,23 @@ internal partial class FFI
             CompressionConfig? compressionConfig,
             bool readOnly,
             NodeDiscoveryMode nodeDiscoveryMode,
-            ClientSideCacheConfig? clientSideCacheConfig)
+            ClientSideCacheConfig? clientSideCacheConfig,
+            AutoPipelineConfig? autoPipelineConfig,
+            uint? readRequestTimeout,
+            uint? writeRequestTimeout,
+            string? keyPrefix,
+            uint? subscribeConfirmationTimeout,
+            bool preserveWireTypes,
+            ReadOnlyRetryConfig? readOnlyRetryConfig,
+            uint? inflightRequestsLimit,
+            PeriodicChecksConfig? periodicChecksConfig,
+            ulong? maxResponseBytes,
+            byte[] clientCertificate,
+            byte[] clientKey,
+            bool tcpNoDelay,
+            string? libName,
+            string? libVersion,
+            bool validateCommandArity)
         {
             _request = new()
             {
@@ -263,6 +423,32 @@ internal partial class FFI
                 NodeDiscoveryMode = nodeDiscoveryMode,
                 HasClientSideCacheConfig = clientSideCacheConfig.HasValue,
                 ClientSideCacheConfig = clientSideCacheConfig ?? default,
+                HasAutoPipelineConfig = autoPipelineConfig.HasValue,
+                AutoPipelineConfig = autoPipelineConfig ?? default,
+                HasReadRequestTimeout = readRequestTimeout.HasValue,
+                ReadRequestTimeout = readRequestTimeout ?? default,
+                HasWriteRequestTimeout = writeRequestTimeout.HasValue,
+                WriteRequestTimeout = writeRequestTimeout ?? default,
+                KeyPrefix = keyPrefix,
+                HasSubscribeConfirmationTimeout = subscribeConfirmationTimeout.HasValue,
+                SubscribeConfirmationTimeout = subscribeConfirmationTimeout ?? default,
+                PreserveWireTypes = preserveWireTypes,
+                HasReadOnlyRetryConfig = readOnlyRetryConfig.HasValue,
+                ReadOnlyRetryConfig = readOnlyRetryConfig ?? default,
+                HasInflightRequestsLimit = inflightRequestsLimit.HasValue,
+                InflightRequestsLimit = inflightRequestsLimit ?? default,
+                HasPeriodicChecksConfig = periodicChecksConfig.HasValue,
+                PeriodicChecksConfig = periodicChecksConfig ?? default,
+                HasMaxResponseBytes = maxResponseBytes.HasValue,
+                MaxResponseBytes = maxResponseBytes ?? default,
+                ClientCertPtr = MarshallBytes(clientCertificate),
+                ClientCertLen = (nuint)clientCertificate.Length,
+                ClientKeyPtr = MarshallBytes(clientKey),
+                ClientKeyLen = (nuint)clientKey.Length,
+                TcpNoDelay = tcpNoDelay,
+                LibName = libName,
+                LibVersion = libVersion,
+                ValidateCommandArity = validateCommandArity,
             };
         }
 
@@ -292,6 +478,17 @@ internal partial class FFI
                 Marshal.FreeHGlobal(_request.RootCertsPtr);
                 Marshal.FreeHGlobal(_request.RootCertsLensPtr);
             }
+
+            // Free client certificate and key
+            if (_request.ClientCertPtr != IntPtr.Zero)
+            {
+                Marshal.FreeHGlobal(_request.ClientCertPtr);
+            }
+
+            if (_request.ClientKeyPtr != IntPtr.Zero)
+            {
+                Marshal.FreeHGlobal(_request.ClientKeyPtr);
+            }
         }
 
         /// <summary>
@@ -395,6 +592,37 @@ internal partial class FFI
             return certsLengthsPtr;
         }
 
+        /// <summary>
+        /// Marshals a byte array, such as the client certificate or key.
+        /// </summary>
+        /// <param name="data">The bytes to copy.</param>
+        /// <returns>Pointer to the copied bytes, or <see cref="IntPtr.Zero" /> if there are none.</returns>
+        private static IntPtr MarshallBytes(byte[] data)
+        {
+            if (data.Length == 0)
+            {
+                return IntPtr.Zero;
+            }
+
+            IntPtr dataPtr = Marshal.AllocHGlobal(data.Length);
+            Marshal.Copy(data, 0, dataPtr, data.Length);
+            return dataPtr;
+        }
+
+        /// <summary>
+        /// Copies the bytes marshalled by <see cref="MarshallBytes" /> back to a managed array.
+        /// </summary>
+        private static byte[] CopyBytes(IntPtr dataPtr, nuint length)
+        {
+            byte[] data = new byte[(int)length];
+            if (length > 0)
+            {
+                Marshal.Copy(dataPtr, data, 0, data.Length);
+            }
+
+            return data;
+        }
+
         /// <summary>
         /// Marshals the pub/sub configuration.
         /// </summary>
@@ -582,6 +810,77 @@ internal partial class FFI
         public IntPtr Args;
         public nuint ArgCount;
         public IntPtr ArgLengths;
+        public OperationClass OperationClass;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool DecodeNumbers;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool SkipCompression;
+    }
+
+    /// <summary>
+    /// The state of a command executed with <see cref="CommandAsyncFfi" />, as reported by <see cref="PollCommandFfi" />.
+    /// Must match the Rust FFI `CommandPollStatus` enum.
+    /// </summary>
+    internal enum CommandPollStatus : uint
+    {
+        /// <summary>The command has not completed yet.</summary>
+        Pending = 0,
+        /// <summary>The command succeeded, and its result was returned.</summary>
+        Completed = 1,
+        /// <summary>The command failed, and its error was returned.</summary>
+        Failed = 2,
+        /// <summary>The handle is unknown, or its result was already returned.</summary>
+        UnknownHandle = 3,
+    }
+
+    /// <summary>
+    /// What to do when the preferred node of a command can't serve it.
+    /// Must match the Rust FFI `PreferredNodeFallback` enum.
+    /// </summary>
+    internal enum PreferredNodeFallback : uint
+    {
+        /// <summary>Fail the command.</summary>
+        Fail = 0,
+        /// <summary>Send the command to any replica serving its slot.</summary>
+        Replica = 1,
+        /// <summary>Send the command to the primary serving its slot.</summary>
+        Primary = 2,
+    }
+
+    /// <summary>
+    /// The end of a list popped from or pushed to by LMOVE and BLMOVE.
+    /// Must match the Rust FFI `ListDirection` enum.
+    /// </summary>
+    internal enum ListDirection : uint
+    {
+        /// <summary>The head of the list.</summary>
+        Left = 0,
+        /// <summary>The tail of the list.</summary>
+        Right = 1,
+    }
+
+    /// <summary>
+    /// The class of operation a command is tagged with, selecting its request timeout.
+    /// Must match the Rust FFI `OperationClass` enum.
+    /// </summary>
+    internal enum OperationClass : uint
+    {
+        /// <summary>
+        /// Classified by the command itself: read-only commands use the read request timeout, other commands the write
+        /// request timeout. Both default to the global request timeout.
+        /// </summary>
+        Unspecified = 0,
+        /// <summary>Uses the read request timeout, if configured.</summary>
+        Read = 1,
+        /// <summary>Uses the write request timeout, if configured.</summary>
+        Write = 2,
+        /// <summary>
+        /// Uses no class timeout, for admin commands which may run longer than any reasonable timeout.
+        /// This disables the safety net of a timeout for the command, including the client's request timeout.
+        /// </summary>
+        Unbounded = 3,
     }
 
     [StructLayout(LayoutKind.Sequential)]
@@ -607,6 +906,21 @@ internal partial class FFI
         public bool HasTimeout;
         public uint Timeout;
         public IntPtr Route;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool Sequential;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool IsReadOnly;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool ValidateReadOnly;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool StopOnFirstError;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool RouteByFirstKey;
     }
 
     // TODO: generate this with a bindings generator
@@ -1064,8 +1378,8 @@ internal partial class FFI
         public RouteType Type;
         public int SlotId;
 
-        [MarshalAs(UnmanagedType.LPStr)]
-        public string? SlotKey;
+        public IntPtr SlotKey;
+        public nuint SlotKeyLen;
         public SlotType SlotType;
 
         [MarshalAs(UnmanagedType.LPStr)]
@@ -1145,6 +1459,61 @@ internal partial class FFI
         public bool HasClientSideCacheConfig;
         public ClientSideCacheConfig ClientSideCacheConfig;
 
+        [MarshalAs(UnmanagedType.U1)]
+        public bool HasAutoPipelineConfig;
+        public AutoPipelineConfig AutoPipelineConfig;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool HasReadRequestTimeout;
+        public uint ReadRequestTimeout;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool HasWriteRequestTimeout;
+        public uint WriteRequestTimeout;
+
+        [MarshalAs(UnmanagedType.LPStr)]
+        public string? KeyPrefix;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool HasSubscribeConfirmationTimeout;
+        public uint SubscribeConfirmationTimeout;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool PreserveWireTypes;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool HasReadOnlyRetryConfig;
+        public ReadOnlyRetryConfig ReadOnlyRetryConfig;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool HasInflightRequestsLimit;
+        public uint InflightRequestsLimit;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool HasPeriodicChecksConfig;
+        public PeriodicChecksConfig PeriodicChecksConfig;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool HasMaxResponseBytes;
+        public ulong MaxResponseBytes;
+
+        public IntPtr ClientCertPtr;
+        public nuint ClientCertLen;
+        public IntPtr ClientKeyPtr;
+        public nuint ClientKeyLen;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool TcpNoDelay;
+
+        [MarshalAs(UnmanagedType.LPStr)]
+        public string? LibName;
+
+        [MarshalAs(UnmanagedType.LPStr)]
+        public string? LibVersion;
+
+        [MarshalAs(UnmanagedType.U1)]
+        public bool ValidateCommandArity;
+
         // TODO more config params, see ffi.rs
     }
 
@@ -1271,6 +1640,82 @@ internal partial class FFI
         public readonly bool ServerAssisted = serverAssisted;
     }
 
+    [StructLayout(LayoutKind.Sequential)]
+    internal readonly struct AutoPipelineConfig(uint maxBatchSize, uint flushIntervalMs)
+    {
+        /// <summary>
+        /// Number of queued commands that triggers a flush.
+        /// </summary>
+        public readonly uint MaxBatchSize = maxBatchSize;
+
+        /// <summary>
+        /// Time in milliseconds after the first queued command that triggers a flush.
+        /// </summary>
+        public readonly uint FlushIntervalMs = flushIntervalMs;
+    }
+
+    /// <summary>
+    /// Faults to inject into the commands of a client, see <see cref="SetFaultInjectionFfi" />.
+    /// </summary>
+    [StructLayout(LayoutKind.Sequential)]
+    internal struct FaultInjectionSpec
+    {
+        /// <summary>
+        /// Milliseconds to wait before sending every affected command.
+        /// </summary>
+        public uint DelayMs;
+
+        /// <summary>
+        /// Probability, between 0 and 1, that an affected command fails instead of being sent.
+        /// </summary>
+        public double FailureProbability;
+
+        /// <summary>
+        /// Whether failed commands fail with a disconnection error rather than a server error.
+        /// </summary>
+        [MarshalAs(UnmanagedType.U1)]
+        public bool SimulateDisconnect;
+
+        /// <summary>
+        /// Pointer to the affected <see cref="RequestType" />s, as <c>uint</c>s, all commands if <see cref="RequestTypeCount" /> is 0.
+        /// </summary>
+        public IntPtr RequestTypes;
+        public nuint RequestTypeCount;
+
+        /// <summary>
+        /// Seed of the random failures, so that a test fails the same commands on every run.
+        /// </summary>
+        public ulong Seed;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal readonly struct ReadOnlyRetryConfig(uint maxRetries, uint delayMs)
+    {
+        /// <summary>
+        /// Maximal number of retries of a command rejected with a READONLY error.
+        /// </summary>
+        public readonly uint MaxRetries = maxRetries;
+
+        /// <summary>
+        /// Milliseconds before the first retry, doubled for every following retry.
+        /// </summary>
+        public readonly uint DelayMs = delayMs;
+    }
+
+    [StructLayout(LayoutKind.Sequential)]
+    internal readonly struct PeriodicChecksConfig(PeriodicChecksMode mode, uint intervalSecs)
+    {
+        /// <summary>
+        /// How the cluster topology is checked periodically.
+        /// </summary>
+        public readonly PeriodicChecksMode Mode = mode;
+
+        /// <summary>
+        /// Seconds between checks, only used by <see cref="PeriodicChecksMode.ManualInterval" />.
+        /// </summary>
+        public readonly uint IntervalSecs = intervalSecs;
+    }
+
     [StructLayout(LayoutKind.Sequential)]
     internal readonly struct Statistics
     {
@@ -1543,6 +1988,12 @@ internal partial class FFI
         [MarshalAs(UnmanagedType.LPStr)]
         public readonly string? Password = password;
 
+        /// <summary>
+        /// Whether a password is configured, so that an empty password is sent rather than treated as no password.
+        /// </summary>
+        [MarshalAs(UnmanagedType.U1)]
+        public readonly bool HasPassword = password != null;
+
         /// <summary>
         /// IAM credentials for authentication.
         /// </summary>
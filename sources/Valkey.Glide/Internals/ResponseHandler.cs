@@ -1,6 +1,9 @@
 ﻿// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
+using System.Globalization;
+using System.Numerics;
 using System.Runtime.InteropServices;
+using System.Text;
 
 using static Valkey.Glide.Errors;
 
@@ -29,6 +32,11 @@ internal class ResponseHandler
         BulkString = 8,
         OK = 9,
         Error = 10,
+        SimpleString = 11,
+        VerbatimString = 12,
+        Push = 13,
+        BigNumber = 14,
+        Attribute = 15,
     }
 
     public static object? HandleResponse(IntPtr valuePtr)
@@ -59,25 +67,84 @@ internal class ResponseHandler
 
     private static Dictionary<GlideString, object?> CreateMap(GlideValue value)
     {
-        object?[] values = CreateArray(value);
         Dictionary<GlideString, object?> res = [];
-        for (int i = 0; i < values.Length; i += 2)
+        foreach ((object? key, object? val) in ToMapEntries(CreateArray(value)))
         {
-            res[(GlideString)values[i]!] = values[i + 1];
+            res[(GlideString)key!] = val;
         }
         return res;
     }
 
+    /// <summary>
+    /// Split the flattened values of a <see cref="ValueType.Map" /> into its entries.
+    /// </summary>
+    /// <remarks>
+    /// Rust flattens a map to <c>[key0, val0, key1, val1, ...]</c>, where each key and value is a single value,
+    /// even if it is itself an array, set or map. Entries keep the order of the map.
+    /// </remarks>
+    /// <param name="values">The flattened map values.</param>
+    /// <returns>The map entries, as key and value pairs.</returns>
+    /// <exception cref="RequestException">If the number of values is odd.</exception>
+    public static (object? Key, object? Value)[] ToMapEntries(object?[] values)
+    {
+        if (values.Length % 2 != 0)
+        {
+            throw new RequestException($"Unexpected map response from Glide: got an odd number of values ({values.Length})");
+        }
+
+        var entries = new (object? Key, object? Value)[values.Length / 2];
+        for (int i = 0; i < entries.Length; i++)
+        {
+            entries[i] = (values[2 * i], values[(2 * i) + 1]);
+        }
+        return entries;
+    }
+
+    /// <summary>
+    /// Split a <see cref="ValueType.VerbatimString" /> into its 3 characters format, e.g. <c>txt</c> or <c>mkd</c>, and its text.
+    /// </summary>
+    /// <param name="verbatim">The verbatim string, starting with its format and a colon as sent on the wire.</param>
+    /// <returns>The format and the text.</returns>
+    public static (string Format, GlideString Text) SplitVerbatimString(GlideString verbatim)
+    {
+        const int prefixLength = 4;
+        return verbatim.Bytes.Length < prefixLength || verbatim.Bytes[prefixLength - 1] != (byte)':'
+            ? throw new RequestException($"Invalid verbatim string: {verbatim}")
+            : (Encoding.ASCII.GetString(verbatim.Bytes, 0, prefixLength - 1), new GlideString(verbatim.Bytes[prefixLength..]));
+    }
+
+    /// <summary>
+    /// Parse the decimal digits of a <see cref="ValueType.BigNumber" />.
+    /// </summary>
+    public static BigInteger ToBigInteger(GlideString digits)
+        => BigInteger.Parse(digits.ToString(), NumberStyles.AllowLeadingSign, CultureInfo.InvariantCulture);
+
+    /// <summary>
+    /// Convert the IEEE 754 bits of a <see cref="ValueType.Float" /> to a <see cref="double" />.
+    /// </summary>
+    /// <remarks>
+    /// Infinities map to <see cref="double.PositiveInfinity" /> and <see cref="double.NegativeInfinity" />.
+    /// Rust sends every NaN as the canonical quiet NaN, which maps to <see cref="double.NaN" />.
+    /// As for any NaN, use <see cref="double.IsNaN(double)" /> rather than <c>==</c> to detect it.
+    /// </remarks>
+    /// <param name="bits">The bits of the value.</param>
+    /// <returns>The value.</returns>
+    public static double ToDouble(nuint bits) => BitConverter.Int64BitsToDouble((long)bits);
+
     private static object? TraverseValue(GlideValue value) => value.Type switch
     {
         ValueType.Null => null,
         ValueType.Int => (long)value.Value,
-        ValueType.Float => BitConverter.Int64BitsToDouble((long)value.Value),
+        ValueType.Float => ToDouble(value.Value),
         ValueType.Bool => value.Value != 0,
-        ValueType.BulkString or ValueType.String => CreateString(value),
-        ValueType.Array => CreateArray(value),
+        ValueType.BulkString or ValueType.String or ValueType.SimpleString or ValueType.VerbatimString => CreateString(value),
+        // The first element of a push is its `FFI.PushKind`, followed by its data.
+        ValueType.Array or ValueType.Push => CreateArray(value),
         ValueType.Map => CreateMap(value),
         ValueType.Set => CreateArray(value).ToHashSet(),
+        ValueType.BigNumber => ToBigInteger(CreateString(value)),
+        // The attributes following the data of an attribute value are metadata, which commands don't expect in their reply.
+        ValueType.Attribute => CreateArray(value)[0],
         ValueType.OK => "OK",
         ValueType.Error => new RequestException(CreateString(value)!),
         _ => throw new NotImplementedException(),
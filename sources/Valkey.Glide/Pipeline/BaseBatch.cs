@@ -40,7 +40,8 @@ public abstract partial class BaseBatch<T>(bool isAtomic) : IBatch where T : Bas
             return null;
         }
 
-        Debug.Assert(response.Length == Commands.Count,
+        // A batch executed with `stopOnFirstError` only returns the responses up to the failed command.
+        Debug.Assert(response.Length <= Commands.Count,
             $"Response misaligned: received {response.Length} responses but submitted {Commands.Count} commands");
 
         for (int i = 0; i < response?.Length; i++)
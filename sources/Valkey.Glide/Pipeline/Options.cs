@@ -88,21 +88,36 @@ public class Options
     /// it will result in a timeout error.If not explicitly set, the client's
     /// <see cref="ClientConfigurationBuilder{T}.RequestTimeout" />  will be used.
     /// </param>
-    public abstract class BaseBatchOptions(uint? timeout = null)
+    /// <param name="stopOnFirstError">
+    /// If <see langword="true" />, a non-atomic batch (pipeline) is executed one command at a time, and stops at the first
+    /// command which fails, including on a connection error. The commands which already ran are not rolled back, unlike
+    /// in an atomic batch (transaction). The result holds the results of the commands which ran, followed by the error,
+    /// so it is shorter than the batch if a command failed. When the batch is executed with <c>raiseOnError</c>, the
+    /// error is thrown instead.<br />
+    /// Not supported for atomic batches (transactions), which are rejected.
+    /// <para />
+    /// By default, this is set to <see langword="false" />.
+    /// </param>
+    public abstract class BaseBatchOptions(uint? timeout = null, bool stopOnFirstError = false)
     {
         /// <summary>
         /// The duration in milliseconds that the client should wait for the batch request to complete.
         /// </summary>
         protected readonly uint? _timeout = timeout;
 
-        internal virtual FFI.BatchOptions ToFfi() => new(timeout: _timeout);
+        /// <summary>
+        /// Whether a non-atomic batch stops at the first command which fails.
+        /// </summary>
+        protected readonly bool _stopOnFirstError = stopOnFirstError;
+
+        internal virtual FFI.BatchOptions ToFfi() => new(timeout: _timeout, stopOnFirstError: _stopOnFirstError);
     }
 
     /// <summary>
     /// Options for a batch request for a standalone client.
     /// </summary>
     /// <inheritdoc cref="BaseBatchOptions" path="/param" />
-    public class BatchOptions(uint? timeout = null) : BaseBatchOptions(timeout)
+    public class BatchOptions(uint? timeout = null, bool stopOnFirstError = false) : BaseBatchOptions(timeout, stopOnFirstError)
     { }
 
     /// <summary>
@@ -152,19 +167,78 @@ public class Options
     /// <para />
     /// <b>Recommendation:</b> It is recommended to increase the <paramref name="timeout" /> when enabling these strategies.
     /// </param>
+    /// <param name="sequential">
+    /// Controls the execution order of a non-atomic batch (pipeline) whose commands are sent to multiple nodes.
+    /// <list type="bullet">
+    ///   <item>
+    ///     If <see langword="false" />, the batch is split into one sub-pipeline per node and these are sent in parallel.
+    ///     Commands sent to the same node are executed in order, but there is no ordering between commands sent to different nodes.
+    ///   </item>
+    ///   <item>
+    ///     If <see langword="true" />, commands are sent one at a time, each after the reply of the previous one was received,
+    ///     so they are executed in order across nodes as well. This costs a round trip per command, and the <paramref name="timeout" />
+    ///     applies to every command rather than to the whole batch.
+    ///   </item>
+    /// </list>
+    /// Ignored for atomic batches (transactions), which are always executed on a single node.
+    /// <para />
+    /// By default, this is set to <see langword="false" />.
+    /// </param>
+    /// <param name="readOnly">
+    /// Declares that the batch consists of read commands only, so that it is served by replicas as allowed by the
+    /// client's <see cref="ReadFrom" /> strategy.
+    /// <list type="bullet">
+    ///   <item>
+    ///     For Non-Atomic Batches (Pipelines), every read command is already routed this way.
+    ///   </item>
+    ///   <item>
+    ///     For Atomic Batches (Transactions) without a <paramref name="route" />, the transaction is sent to the node serving
+    ///     the slot of its first keyed command, preferring a replica, instead of to the primary.
+    ///   </item>
+    /// </list>
+    /// By default, this is set to <see langword="false" />.
+    /// </param>
+    /// <param name="validateReadOnly">
+    /// If <see langword="true" /> and <paramref name="readOnly" /> is set, the batch is rejected without being sent if it contains a write command.
+    /// <para />
+    /// By default, this is set to <see langword="false" />.
+    /// </param>
+    /// <param name="routeByFirstKey">
+    /// If <see langword="true" />, an Atomic Batch (Transaction) without a <paramref name="route" /> is sent to the node serving
+    /// the slot of its first keyed command, even if the slots of the other keys can't be verified by the client (e.g. keys
+    /// accessed by a script sharing a hash tag). The server still rejects the transaction if its keys span multiple slots.
+    /// <para />
+    /// The batch fails without being sent if it isn't atomic, has a <paramref name="route" />, or has no command with a key.
+    /// <para />
+    /// By default, this is set to <see langword="false" />.
+    /// </param>
     public class ClusterBatchOptions(
         uint? timeout = null,
         SingleNodeRoute? route = null,
-        ClusterBatchRetryStrategy? retryStrategy = null) : BaseBatchOptions(timeout)
+        ClusterBatchRetryStrategy? retryStrategy = null,
+        bool sequential = false,
+        bool readOnly = false,
+        bool validateReadOnly = false,
+        bool stopOnFirstError = false,
+        bool routeByFirstKey = false) : BaseBatchOptions(timeout, stopOnFirstError)
     {
         internal SingleNodeRoute? Route { get; private set; } = route;
         internal ClusterBatchRetryStrategy? RetryStrategy { get; private set; } = retryStrategy;
+        internal bool Sequential { get; private set; } = sequential;
+        internal bool ReadOnly { get; private set; } = readOnly;
+        internal bool ValidateReadOnly { get; private set; } = validateReadOnly;
+        internal bool RouteByFirstKey { get; private set; } = routeByFirstKey;
 
         internal override FFI.BatchOptions ToFfi() => new(
                 RetryStrategy?.RetryServerError,
                 RetryStrategy?.RetryConnectionError,
                 _timeout,
-                Route?.ToFfi()
+                Route?.ToFfi(),
+                Sequential,
+                ReadOnly,
+                ValidateReadOnly,
+                _stopOnFirstError,
+                RouteByFirstKey
             );
     }
 }
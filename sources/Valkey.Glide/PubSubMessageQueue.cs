@@ -53,6 +53,33 @@ public sealed class PubSubMessageQueue : IDisposable
         return false;
     }
 
+    /// <summary>
+    /// Retrieve all currently queued messages at once, without blocking.
+    /// <para />
+    /// Messages are queued by the PubSub callback as they arrive, so no native call is involved in retrieving them.
+    /// Draining the queue in batches avoids the per-message overhead of <see cref="TryGetMessage" /> for consumers
+    /// that process messages in batches.
+    /// </summary>
+    /// <param name="maxCount">The maximum number of messages to retrieve.</param>
+    /// <returns>The retrieved messages in arrival order, which is empty if no message is available.</returns>
+    /// <exception cref="ArgumentOutOfRangeException">Thrown when <paramref name="maxCount" /> is not positive.</exception>
+    /// <exception cref="ObjectDisposedException">Thrown when the queue has been disposed.</exception>
+    public IReadOnlyList<PubSubMessage> DrainMessages(int maxCount = int.MaxValue)
+    {
+        ArgumentOutOfRangeException.ThrowIfNegativeOrZero(maxCount);
+        ThrowIfDisposed();
+
+        List<PubSubMessage> messages = [];
+        while (messages.Count < maxCount && _messages.TryDequeue(out PubSubMessage? message))
+        {
+            // Consume one semaphore count per dequeued message
+            _ = _messageAvailable.Wait(0);
+            messages.Add(message);
+        }
+
+        return messages;
+    }
+
     /// <summary>
     /// Waits for and retrieves a message from the queue.
     /// </summary>
@@ -106,21 +106,34 @@ public abstract class Route
     /// Request routing configuration overrides the <see cref="ReadFromStrategy"/> connection configuration.<br />
     /// If <see cref="SlotType.Replica"/> is used, the request will be routed to a replica, even if the strategy is <see cref="ReadFromStrategy.Primary"/>.
     /// </summary>
-    /// <param name="slotKey">The request will be sent to nodes managing this key.</param>
-    /// <param name="slotType">Defines type of the node being addressed.</param>
-    public class SlotKeyRoute(string slotKey, SlotType slotType) : SingleNodeRoute
+    public class SlotKeyRoute : SingleNodeRoute
     {
         /// <summary>
         /// The key used to determine the slot for routing.
         /// </summary>
-        public readonly string SlotKey = slotKey;
+        public readonly string SlotKey;
 
         /// <summary>
         /// The type of node to route the request to.
         /// </summary>
-        public new readonly SlotType SlotType = slotType;
+        public new readonly SlotType SlotType;
+
+        private readonly GlideString _slotKey;
+
+        /// <param name="slotKey">The request will be sent to nodes managing this key.</param>
+        /// <param name="slotType">Defines type of the node being addressed.</param>
+        public SlotKeyRoute(string slotKey, SlotType slotType) : this((GlideString)slotKey, slotType) { }
+
+        /// <param name="slotKey">The request will be sent to nodes managing this key. The slot is computed from the raw bytes, so binary keys are supported.</param>
+        /// <param name="slotType">Defines type of the node being addressed.</param>
+        public SlotKeyRoute(GlideString slotKey, SlotType slotType)
+        {
+            _slotKey = slotKey;
+            SlotKey = slotKey.ToString();
+            SlotType = slotType;
+        }
 
-        internal override FFI.Route ToFfi() => new(RouteType.SlotKey, slotKeyInfo: (SlotKey, SlotType));
+        internal override FFI.Route ToFfi() => new(RouteType.SlotKey, slotKeyInfo: (_slotKey, SlotType));
     }
 
     /// <summary>
@@ -0,0 +1,28 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+namespace Valkey.Glide;
+
+/// <summary>
+/// Controls the periodic checks of the cluster topology, which detect topology changes, e.g. a failover or a resharding,
+/// before a command is redirected because of them.
+/// <para />
+/// This option is only relevant for cluster clients; it is ignored in standalone mode.
+/// </summary>
+/// <seealso href="https://glide.valkey.io/reference/connection-options/">Valkey GLIDE – Connection Options</seealso>
+public enum PeriodicChecksMode : uint
+{
+    /// <summary>
+    /// Check the topology at the default interval of the core library.
+    /// </summary>
+    Enabled = 0,
+
+    /// <summary>
+    /// Never check the topology periodically; it is only refreshed when a command is redirected.
+    /// </summary>
+    Disabled = 1,
+
+    /// <summary>
+    /// Check the topology at a custom interval.
+    /// </summary>
+    ManualInterval = 2,
+}
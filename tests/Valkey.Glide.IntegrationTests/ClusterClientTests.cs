@@ -84,6 +84,19 @@ public class ClusterClientTests(TestConfiguration config)
         Assert.Contains("# Replication", res);
     }
 
+    [Theory(DisableDiscoveryEnumeration = true)]
+    [MemberData(nameof(Config.TestClusterClients), MemberType = typeof(TestConfiguration))]
+    public async Task CustomCommandWithBinarySlotKeyRoute(GlideClusterClient client)
+    {
+        // The NUL byte is inside the hash tag, so truncating the key at it would hash a different tag
+        gs key = "{tag\0" + Guid.NewGuid() + "}key";
+        _ = await client.CustomCommand(["SET", key, "value"]);
+        long slot = (long)(await client.CustomCommand(["CLUSTER", "KEYSLOT", key])).SingleValue!;
+
+        long count = (long)(await client.CustomCommand(["CLUSTER", "COUNTKEYSINSLOT", slot.ToString()], new SlotKeyRoute(key, SlotType.Primary))).SingleValue!;
+        Assert.Equal(1, count);
+    }
+
     [Theory(DisableDiscoveryEnumeration = true)]
     [MemberData(nameof(Config.TestClusterClients), MemberType = typeof(TestConfiguration))]
     public async Task CustomCommandWithMultiNodeRoute(GlideClusterClient client)
@@ -123,6 +136,22 @@ public class ClusterClientTests(TestConfiguration config)
         Assert.Contains("# Replication", res![0] as string);
     }
 
+    [Theory(DisableDiscoveryEnumeration = true)]
+    [MemberData(nameof(ClusterClientWithAtomic))]
+    public async Task ReadOnlyBatchRejectsWriteCommands(GlideClusterClient client, bool isAtomic)
+    {
+        string key = Guid.NewGuid().ToString();
+        ClusterBatch batch = new ClusterBatch(isAtomic).CustomCommand(["GET", key]).CustomCommand(["SET", key, "value"]);
+
+        _ = await Assert.ThrowsAsync<RequestException>(async () =>
+            await client.Exec(batch, true, new(readOnly: true, validateReadOnly: true)));
+        Assert.Null(await client.CustomCommand(["GET", key]));
+
+        batch = new ClusterBatch(isAtomic).CustomCommand(["GET", key]).CustomCommand(["STRLEN", key]);
+        object?[]? res = await client.Exec(batch, true, new(readOnly: true, validateReadOnly: true));
+        Assert.Equal([null, 0L], res);
+    }
+
     [Theory(DisableDiscoveryEnumeration = true)]
     [MemberData(nameof(Config.TestClusterClients), MemberType = typeof(TestConfiguration))]
     public async Task Info(GlideClusterClient client)
@@ -1,6 +1,7 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
 using Valkey.Glide.Commands.Options;
+using Valkey.Glide.Internals;
 using Valkey.Glide.Pipeline;
 
 namespace Valkey.Glide.IntegrationTests;
@@ -256,6 +257,27 @@ public class CompressionTests(CompressionFixture fixture)
         Assert.Equal(value, retrieved.ToString());
     }
 
+    [Fact]
+    public async Task Compression_SkipCompression_SendsAndReadsValuesAsIs()
+    {
+        var configNoCompression = TestConfiguration.DefaultClientConfig().Build();
+        await using var clientNoCompression = await GlideClient.CreateClient(configNoCompression);
+        static Cmd<object?, object?> Uncompressed(params GlideString[] args)
+            => new(FFI.RequestType.CustomCommand, args, true, o => o) { SkipCompression = true };
+
+        // A value written without compression is stored as is
+        string key = $"skip_compression_test_{Guid.NewGuid()}";
+        _ = await ZstdClient.Command(Uncompressed("SET", key, LargeValue));
+        Assert.Equal(LargeValue, (await clientNoCompression.GetAsync(key)).ToString());
+
+        // A compressed value read without decompression is returned as stored
+        await ZstdClient.SetAsync(key, LargeValue);
+        GlideString? raw = (GlideString?)await ZstdClient.Command(Uncompressed("GET", key));
+        Assert.NotNull(raw);
+        Assert.True(raw.Bytes.Length < LargeValueSize);
+        Assert.Equal(LargeValue, (await ZstdClient.GetAsync(key)).ToString());
+    }
+
     [Fact]
     public async Task Compression_MultipleOperations_MaintainsDataIntegrity()
     {
@@ -1,5 +1,6 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
+using Valkey.Glide.Internals;
 using Valkey.Glide.Pipeline;
 
 using static Valkey.Glide.Pipeline.Options;
@@ -23,6 +24,45 @@ public class DebugSleepTests
         );
     }
 
+    [Fact]
+    public async Task CommandTimeout()
+    {
+        await using GlideClient client = TestConfiguration.DefaultStandaloneClient();
+        static Cmd<object?, object?> Sleep(TimeSpan timeout)
+            => new(FFI.RequestType.CustomCommand, ["DEBUG", "SLEEP", "0.5"], true, o => o) { Timeout = timeout };
+
+        // Expect a timeout exception on short timeout
+        _ = await Assert.ThrowsAsync<TimeoutException>(() => client.Command(Sleep(TimeSpan.FromMilliseconds(100))));
+
+        // Wait for server to wake up
+        await Task.Delay(TimeSpan.FromSeconds(1), TestContext.Current.CancellationToken);
+
+        // Retry with a longer timeout and expect OK
+        Assert.Equal("OK", (await client.Command(Sleep(TimeSpan.FromSeconds(2))))?.ToString());
+    }
+
+    [Fact]
+    public async Task CommandTimeoutLongerThanRequestTimeout()
+    {
+        // The client times out requests after 250ms
+        await using GlideClient client = TestConfiguration.LowTimeoutStandaloneClient();
+        Cmd<object?, object?> sleep = new(FFI.RequestType.CustomCommand, ["DEBUG", "SLEEP", "0.5"], true, o => o) { Timeout = TimeSpan.FromSeconds(2) };
+
+        // Expect OK, as the command's own timeout replaces the request timeout
+        Assert.Equal("OK", (await client.Command(sleep))?.ToString());
+    }
+
+    [Fact]
+    public async Task UnboundedOperationClassIgnoresRequestTimeout()
+    {
+        // The client times out requests after 250ms, and has no read or write timeouts
+        await using GlideClient client = TestConfiguration.LowTimeoutStandaloneClient();
+        Cmd<object?, object?> sleep = new(FFI.RequestType.CustomCommand, ["DEBUG", "SLEEP", "0.5"], true, o => o) { OperationClass = FFI.OperationClass.Unbounded };
+
+        // Expect OK, as an unbounded command isn't timed out
+        Assert.Equal("OK", (await client.Command(sleep))?.ToString());
+    }
+
 #pragma warning disable xUnit1047 // Avoid using TheoryDataRow arguments that might not be serializable
     public static IEnumerable<TheoryDataRow<BaseClient, bool>> GetTestClientWithAtomic =>
         TestConfiguration.TestClients.SelectMany(r => new TheoryDataRow<BaseClient, bool>[] { new(r.Data, true), new(r.Data, false) });
@@ -52,4 +92,28 @@ public class DebugSleepTests
             : await ((GlideClient)client).Exec((Batch)batch, true, (BatchOptions)options);
         Assert.Equal(["OK"], res);
     }
+
+    [Fact]
+    public async Task BatchSequentialOrdersCommandsAcrossNodes()
+    {
+        await using GlideClusterClient client = TestConfiguration.DefaultClusterClient();
+        // Hash tags {a} and {b} map to slots served by different primaries.
+        string blockingKey = "{a}" + Guid.NewGuid();
+        string expiringKey = "{b}" + Guid.NewGuid();
+
+        async Task<long> ExecAndGetTtl(bool sequential)
+        {
+            _ = await client.CustomCommand(["SET", expiringKey, "value", "PX", "5000"]);
+            ClusterBatch batch = new(false);
+            _ = batch.CustomCommand(["BLPOP", blockingKey, "1"]);
+            _ = batch.CustomCommand(["PTTL", expiringKey]);
+            object?[]? res = await client.Exec(batch, true, new ClusterBatchOptions(timeout: 5000, sequential: sequential));
+            return (long)res![1]!;
+        }
+
+        // In parallel, PTTL is executed without waiting for BLPOP on the other node to time out
+        Assert.True(await ExecAndGetTtl(false) > 4500);
+        // Sequentially, PTTL is executed only after BLPOP timed out
+        Assert.True(await ExecAndGetTtl(true) < 4100);
+    }
 }
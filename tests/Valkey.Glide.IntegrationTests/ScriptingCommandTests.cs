@@ -112,6 +112,24 @@ public class ScriptingCommandTests(TestConfiguration config)
         Assert.False(exists[1]); // script2 is not cached
     }
 
+    [Theory(DisableDiscoveryEnumeration = true)]
+    [MemberData(nameof(Config.TestClients), MemberType = typeof(TestConfiguration))]
+    public async Task ScriptFlushInternalAsync_RemovesAllScripts(BaseClient client)
+    {
+        foreach (FlushMode mode in new[] { FlushMode.Sync, FlushMode.Async })
+        {
+            // Load a script
+            using var script = new Script($"return 'flush internal {mode}'");
+            _ = await client.ScriptInvokeAsync(script);
+            Assert.True((await client.ScriptExistsAsync([script.Hash]))[0]);
+
+            await client.ScriptFlushInternalAsync(mode);
+
+            // Verify it no longer exists
+            Assert.False((await client.ScriptExistsAsync([script.Hash]))[0]);
+        }
+    }
+
     [Theory(DisableDiscoveryEnumeration = true)]
     [MemberData(nameof(Config.TestClients), MemberType = typeof(TestConfiguration))]
     public async Task ScriptFlushAsync_SyncMode_RemovesAllScripts(BaseClient client)
@@ -326,6 +344,62 @@ public class ScriptingCommandTests(TestConfiguration config)
         Assert.Equal(value, retrievedValue.ToString());
     }
 
+    [Fact]
+    public async Task ScriptInvokeAsync_WithClientKeyPrefix_PrefixesKeysOnly()
+    {
+        string prefix = $"{Guid.NewGuid()}:";
+        string key = Guid.NewGuid().ToString();
+        await using GlideClient prefixedClient = await GlideClient.CreateClient(
+            TestConfiguration.DefaultClientConfig().WithKeyPrefix(prefix).Build());
+        await using GlideClient client = TestConfiguration.DefaultStandaloneClient();
+
+        using var script = new Script("redis.call('SET', KEYS[1], ARGV[1]) return KEYS[1]");
+        var options = new ScriptOptions().WithKeys(key).WithArgs("value");
+
+        // The key is prefixed, while the argument is not
+        ValkeyResult result = await prefixedClient.ScriptInvokeAsync(script, options);
+        Assert.Equal(prefix + key, result.ToString());
+        Assert.Equal("value", (await client.GetAsync(prefix + key)).ToString());
+    }
+
+    [Theory(DisableDiscoveryEnumeration = true)]
+    [MemberData(nameof(Config.TestClusterClients), MemberType = typeof(TestConfiguration))]
+    public async Task ScriptInvokeInternalAsync_RandomRoute_ExecutesScript(GlideClusterClient client)
+    {
+        using var script = new Script("return 'routed'");
+
+        ValkeyResult result = await client.ScriptInvokeInternalAsync(script.Hash, null, null, Route.Random);
+
+        Assert.Equal("routed", result.ToString());
+    }
+
+    [Theory(DisableDiscoveryEnumeration = true)]
+    [MemberData(nameof(Config.TestClusterClients), MemberType = typeof(TestConfiguration))]
+    public async Task ScriptInvokeInternalAsync_SlotKeyRoute_ExecutesScriptOnKeyNode(GlideClusterClient client)
+    {
+        string key = Guid.NewGuid().ToString();
+        await client.SetAsync(key, "value");
+
+        // The key is not declared, so the script can only read it on the node owning its slot
+        using var script = new Script("return redis.call('GET', ARGV[1])");
+        ValkeyResult result = await client.ScriptInvokeInternalAsync(
+            script.Hash, null, [key], new Route.SlotKeyRoute(key, Route.SlotType.Primary));
+
+        Assert.Equal("value", result.ToString());
+    }
+
+    [Theory(DisableDiscoveryEnumeration = true)]
+    [MemberData(nameof(Config.TestClusterClients), MemberType = typeof(TestConfiguration))]
+    public async Task ScriptInvokeInternalAsync_ByAddressRoute_ExecutesScript(GlideClusterClient client)
+    {
+        using var script = new Script("return 'routed'");
+        var route = new Route.ByAddressRoute(TestConfiguration.CLUSTER_ADDRESS.Host, TestConfiguration.CLUSTER_ADDRESS.Port);
+
+        ValkeyResult result = await client.ScriptInvokeInternalAsync(script.Hash, null, null, route);
+
+        Assert.Equal("routed", result.ToString());
+    }
+
     // ===== Function Execution Tests =====
 
     [Theory(DisableDiscoveryEnumeration = true)]
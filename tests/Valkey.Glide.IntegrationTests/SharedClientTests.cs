@@ -96,4 +96,16 @@ public class SharedClientTests(TestConfiguration config)
 
         await Task.WhenAll(operations);
     }
+
+    [Theory(DisableDiscoveryEnumeration = true)]
+    [MemberData(nameof(Config.TestClients), MemberType = typeof(TestConfiguration))]
+    public void GetClientStatistics_ReportsConnectionMetrics(BaseClient client)
+    {
+        Dictionary<GlideString, object?> statistics = client.GetClientStatistics();
+
+        Assert.Equal(3, statistics.Count);
+        Assert.True((long)statistics["total_connections"]! > 0);
+        Assert.True((long)statistics["total_clients"]! > 0);
+        Assert.True((long)statistics["inflight_requests"]! >= 0);
+    }
 }
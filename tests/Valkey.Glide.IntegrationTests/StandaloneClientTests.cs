@@ -540,4 +540,23 @@ public class StandaloneClientTests(TestConfiguration config)
         await using var client = await GlideClient.CreateClient(config);
         await AssertConnected(client);
     }
+
+    [Fact]
+    public async Task Dispose_WithAutoPipeline_SendsQueuedCommands()
+    {
+        string prefix = Guid.NewGuid().ToString();
+        var config = TestConfiguration.DefaultClientConfig()
+            .WithAutoPipeline(100, TimeSpan.FromSeconds(10))
+            .Build();
+
+        GlideClient client = await GlideClient.CreateClient(config);
+        Task[] sets = [.. Enumerable.Range(0, 10).Select(i => client.SetAsync($"{prefix}{i}", i.ToString()))];
+        await client.DisposeAsync();
+
+        // The flush interval has not elapsed, so the commands are only sent by closing the client.
+        Assert.All(sets, set => Assert.True(set.IsCompletedSuccessfully));
+
+        await using var referenceClient = await GlideClient.CreateClient(TestConfiguration.DefaultClientConfig().Build());
+        Assert.Equal("9", await referenceClient.GetAsync($"{prefix}9"));
+    }
 }
@@ -131,4 +131,18 @@ public class CompressionConfigTests
         Assert.False(ffi.HasMaxDecompressedSize);
         Assert.True(ffi.Enabled);
     }
+
+    [Theory]
+    [InlineData(CompressionBackend.Zstd)]
+    [InlineData(CompressionBackend.Lz4)]
+    public void CompressionConfig_ToFfi_PassesBackend(CompressionBackend backend)
+    {
+        var config = new CompressionConfig(backend, CustomCompressionLevel, CustomMinCompressionSize);
+
+        var ffi = config.ToFfi();
+
+        Assert.Equal(backend, ffi.Backend);
+        Assert.True(ffi.HasCompressionLevel);
+        Assert.Equal(CustomCompressionLevel, ffi.CompressionLevel);
+    }
 }
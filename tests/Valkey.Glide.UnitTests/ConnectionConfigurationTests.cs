@@ -65,6 +65,17 @@ public class ConnectionConfigurationTests
         Assert.False(authenticationInfo.HasIamCredentials);
     }
 
+    [Fact]
+    public void WithAuthentication_EmptyPassword_HasPassword()
+    {
+        var builder = new StandaloneClientConfigurationBuilder()
+            .WithAuthentication(Username, "");
+
+        var authenticationInfo = builder.Build().Request.AuthenticationInfo!.Value;
+        Assert.Equal("", authenticationInfo.Password);
+        Assert.True(authenticationInfo.HasPassword);
+    }
+
     [Fact]
     public void WithAuthentication_PasswordOnly_NullThrows()
     {
@@ -571,6 +582,40 @@ public class ConnectionConfigurationTests
         _ = Assert.Throws<ArgumentException>(() => builder.WithTrustedCertificate(tempFile.Path));
     }
 
+    [Fact]
+    public void ClientCertificate_NotSet_IsEmpty()
+    {
+        var config = new StandaloneClientConfigurationBuilder().Build();
+        Assert.Null(config.Request.ClientCertificate);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Empty(ffi.ClientCertificate);
+        Assert.Empty(ffi.ClientKey);
+    }
+
+    [Fact]
+    public void WithClientCertificate_ToFfi_PassesCertificateAndKeyToFfiLayer()
+    {
+        var config = new StandaloneClientConfigurationBuilder()
+            .WithTls()
+            .WithClientCertificate(CertificateData1, CertificateData2)
+            .Build();
+        Assert.Equal((CertificateData1, CertificateData2), config.Request.ClientCertificate);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Equal(CertificateData1, ffi.ClientCertificate);
+        Assert.Equal(CertificateData2, ffi.ClientKey);
+    }
+
+    [Fact]
+    public void WithClientCertificate_InvalidData_Throws()
+    {
+        var builder = new StandaloneClientConfigurationBuilder();
+        _ = Assert.Throws<ArgumentNullException>(() => builder.WithClientCertificate(null!, CertificateData2));
+        _ = Assert.Throws<ArgumentException>(() => builder.WithClientCertificate(CertificateData1, []));
+        _ = Assert.Throws<ArgumentException>(() => builder.WithClientCertificate(new byte[CertificateMaxSize + 1], CertificateData2));
+    }
+
     #endregion
     #region Pub/Sub Reconciliation Interval Tests
 
@@ -607,6 +652,50 @@ public class ConnectionConfigurationTests
         _ = Assert.Throws<ArgumentException>(() => builder.WithPubSubReconciliationInterval(TimeSpan.Zero));
     }
 
+    [Fact]
+    public void PubSubReconciliationInterval_NotSet_IsNullInFfiLayer()
+    {
+        using FFI.ConnectionConfig ffi = new ClusterClientConfigurationBuilder().Build().Request.ToFfi();
+        Assert.Null(ffi.PubSubReconciliationIntervalMs);
+    }
+
+    [Fact]
+    public void WithPubSubReconciliationInterval_ToFfi_PassesIntervalToFfiLayer()
+    {
+        var config = new ClusterClientConfigurationBuilder()
+            .WithPubSubReconciliationInterval(TimeSpan.FromSeconds(30))
+            .Build();
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Equal(30_000u, ffi.PubSubReconciliationIntervalMs);
+    }
+
+    #endregion
+    #region Subscribe Confirmation Timeout Tests
+
+    [Fact]
+    public void SubscribeConfirmationTimeout_Default()
+    {
+        var builder = new StandaloneClientConfigurationBuilder();
+        Assert.Null(builder.Build().Request.SubscribeConfirmationTimeout);
+    }
+
+    [Fact]
+    public void SubscribeConfirmationTimeout_PositiveSucceeds()
+    {
+        var builder = new ClusterClientConfigurationBuilder()
+            .WithSubscribeConfirmationTimeout(TimeSpan.FromSeconds(5));
+
+        Assert.Equal(TimeSpan.FromSeconds(5), builder.Build().Request.SubscribeConfirmationTimeout);
+    }
+
+    [Fact]
+    public void SubscribeConfirmationTimeout_ZeroThrows()
+    {
+        var builder = new StandaloneClientConfigurationBuilder();
+        _ = Assert.Throws<ArgumentException>(() => builder.WithSubscribeConfirmationTimeout(TimeSpan.Zero));
+    }
+
     #endregion
     #region Connection Retry Strategy Tests
 
@@ -763,6 +852,323 @@ public class ConnectionConfigurationTests
     public void AddressResolver_Cluster_SetToNull_IsNull()
         => Assert.Null(new ClusterClientConfigurationBuilder { AddressResolver = null }.Build().Request.AddressResolver);
 
+    #endregion
+    #region Preserve Wire Types Tests
+
+    [Fact]
+    public void PreserveWireTypes_NotSet_IsFalse()
+    {
+        var config = new StandaloneClientConfigurationBuilder().Build();
+        Assert.False(config.Request.PreserveWireTypes);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.False(ffi.PreserveWireTypes);
+    }
+
+    [Fact]
+    public void WithPreserveWireTypes_ToFfi_PassesFlagToFfiLayer()
+    {
+        var config = new ClusterClientConfigurationBuilder().WithPreserveWireTypes(true).Build();
+        Assert.True(config.Request.PreserveWireTypes);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.True(ffi.PreserveWireTypes);
+    }
+
+    #endregion
+    #region Read Only Error Retry Tests
+
+    [Fact]
+    public void ReadOnlyErrorRetry_NotSet_IsNull()
+    {
+        var config = new ClusterClientConfigurationBuilder().Build();
+        Assert.Null(config.Request.ReadOnlyErrorRetry);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Null(ffi.ReadOnlyRetryConfig);
+    }
+
+    [Fact]
+    public void WithReadOnlyErrorRetry_ToFfi_PassesConfigToFfiLayer()
+    {
+        var config = new ClusterClientConfigurationBuilder()
+            .WithReadOnlyErrorRetry(3, TimeSpan.FromMilliseconds(100))
+            .Build();
+        Assert.Equal((3u, TimeSpan.FromMilliseconds(100)), config.Request.ReadOnlyErrorRetry);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Equal(3u, ffi.ReadOnlyRetryConfig!.Value.MaxRetries);
+        Assert.Equal(100u, ffi.ReadOnlyRetryConfig!.Value.DelayMs);
+    }
+
+    [Fact]
+    public void WithReadOnlyErrorRetry_NegativeDelay_Throws()
+        => Assert.Throws<ArgumentException>(() => new StandaloneClientConfigurationBuilder().WithReadOnlyErrorRetry(3, TimeSpan.FromMilliseconds(-1)));
+
+    #endregion
+    #region Inflight Requests Limit Tests
+
+    [Fact]
+    public void InflightRequestsLimit_NotSet_IsNull()
+    {
+        var config = new StandaloneClientConfigurationBuilder().Build();
+        Assert.Null(config.Request.InflightRequestsLimit);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Null(ffi.InflightRequestsLimit);
+    }
+
+    [Fact]
+    public void WithInflightRequestsLimit_ToFfi_PassesLimitToFfiLayer()
+    {
+        var config = new ClusterClientConfigurationBuilder()
+            .WithInflightRequestsLimit(250)
+            .Build();
+        Assert.Equal(250u, config.Request.InflightRequestsLimit);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Equal(250u, ffi.InflightRequestsLimit);
+    }
+
+    [Fact]
+    public void WithInflightRequestsLimit_Zero_Throws()
+        => Assert.Throws<ArgumentException>(() => new StandaloneClientConfigurationBuilder().WithInflightRequestsLimit(0));
+
+    #endregion
+    #region Periodic Checks Tests
+
+    [Fact]
+    public void PeriodicChecks_NotSet_IsNull()
+    {
+        var config = new ClusterClientConfigurationBuilder().Build();
+        Assert.Null(config.Request.PeriodicChecks);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Null(ffi.PeriodicChecksConfig);
+    }
+
+    [Theory]
+    [InlineData(PeriodicChecksMode.Enabled)]
+    [InlineData(PeriodicChecksMode.Disabled)]
+    public void WithPeriodicChecks_Mode_PassesModeToFfiLayer(PeriodicChecksMode mode)
+    {
+        var config = new ClusterClientConfigurationBuilder()
+            .WithPeriodicChecks(mode)
+            .Build();
+        Assert.Equal((mode, TimeSpan.Zero), config.Request.PeriodicChecks);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Equal(mode, ffi.PeriodicChecksConfig!.Value.Mode);
+        Assert.Equal(0u, ffi.PeriodicChecksConfig!.Value.IntervalSecs);
+    }
+
+    [Fact]
+    public void WithPeriodicChecks_Interval_PassesManualIntervalToFfiLayer()
+    {
+        var config = new ClusterClientConfigurationBuilder()
+            .WithPeriodicChecks(TimeSpan.FromSeconds(30))
+            .Build();
+        Assert.Equal((PeriodicChecksMode.ManualInterval, TimeSpan.FromSeconds(30)), config.Request.PeriodicChecks);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Equal(PeriodicChecksMode.ManualInterval, ffi.PeriodicChecksConfig!.Value.Mode);
+        Assert.Equal(30u, ffi.PeriodicChecksConfig!.Value.IntervalSecs);
+    }
+
+    [Fact]
+    public void WithPeriodicChecks_ManualIntervalWithoutInterval_Throws()
+    {
+        var builder = new ClusterClientConfigurationBuilder();
+        _ = Assert.Throws<ArgumentException>(() => builder.WithPeriodicChecks(PeriodicChecksMode.ManualInterval));
+        _ = Assert.Throws<ArgumentException>(() => builder.WithPeriodicChecks(TimeSpan.FromMilliseconds(500)));
+    }
+
+    #endregion
+    #region Max Response Bytes Tests
+
+    [Fact]
+    public void MaxResponseBytes_NotSet_IsNull()
+    {
+        var config = new StandaloneClientConfigurationBuilder().Build();
+        Assert.Null(config.Request.MaxResponseBytes);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Null(ffi.MaxResponseBytes);
+    }
+
+    [Fact]
+    public void WithMaxResponseBytes_ToFfi_PassesLimitToFfiLayer()
+    {
+        var config = new ClusterClientConfigurationBuilder()
+            .WithMaxResponseBytes(64 * 1024 * 1024)
+            .Build();
+        Assert.Equal(64ul * 1024 * 1024, config.Request.MaxResponseBytes);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Equal(64ul * 1024 * 1024, ffi.MaxResponseBytes);
+    }
+
+    [Fact]
+    public void WithMaxResponseBytes_Zero_Throws()
+        => Assert.Throws<ArgumentException>(() => new StandaloneClientConfigurationBuilder().WithMaxResponseBytes(0));
+
+    #endregion
+    #region Library Info Tests
+
+    [Fact]
+    public void LibInfo_NotSet_IsNull()
+    {
+        var config = new StandaloneClientConfigurationBuilder().Build();
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Null(ffi.LibName);
+        Assert.Null(ffi.LibVersion);
+    }
+
+    [Fact]
+    public void WithLibInfo_ToFfi_OverridesDefaultLibInfo()
+    {
+        var config = new ClusterClientConfigurationBuilder()
+            .WithLibName("MyWrapper")
+            .WithLibVersion("1.2.3")
+            .Build();
+        Assert.Equal("MyWrapper", config.Request.LibName);
+        Assert.Equal("1.2.3", config.Request.LibVersion);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Equal("MyWrapper", ffi.LibName);
+        Assert.Equal("1.2.3", ffi.LibVersion);
+    }
+
+    #endregion
+    #region TCP No Delay Tests
+
+    [Fact]
+    public void TcpNoDelay_NotSet_IsFalse()
+    {
+        var config = new StandaloneClientConfigurationBuilder().Build();
+        Assert.False(config.Request.TcpNoDelay);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.False(ffi.TcpNoDelay);
+    }
+
+    [Fact]
+    public void WithTcpNoDelay_ToFfi_PassesFlagToFfiLayer()
+    {
+        var config = new ClusterClientConfigurationBuilder()
+            .WithTcpNoDelay()
+            .Build();
+        Assert.True(config.Request.TcpNoDelay);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.True(ffi.TcpNoDelay);
+    }
+
+    #endregion
+    #region Command Arity Validation Tests
+
+    [Fact]
+    public void ValidateCommandArity_NotSet_IsFalse()
+    {
+        var config = new StandaloneClientConfigurationBuilder().Build();
+        Assert.False(config.Request.ValidateCommandArity);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.False(ffi.ValidateCommandArity);
+    }
+
+    [Fact]
+    public void WithCommandArityValidation_ToFfi_PassesFlagToFfiLayer()
+    {
+        var config = new ClusterClientConfigurationBuilder()
+            .WithCommandArityValidation()
+            .Build();
+        Assert.True(config.Request.ValidateCommandArity);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.True(ffi.ValidateCommandArity);
+    }
+
+    #endregion
+    #region Auto Pipeline Tests
+
+    [Fact]
+    public void AutoPipeline_NotSet_IsDisabled()
+    {
+        var config = new StandaloneClientConfigurationBuilder().Build();
+        Assert.Null(config.Request.AutoPipeline);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Null(ffi.AutoPipelineConfig);
+    }
+
+    [Fact]
+    public void WithAutoPipeline_ToFfi_PassesConfigToFfiLayer()
+    {
+        var config = new ClusterClientConfigurationBuilder()
+            .WithAutoPipeline(64, TimeSpan.FromMilliseconds(5))
+            .Build();
+        Assert.Equal((64u, TimeSpan.FromMilliseconds(5)), config.Request.AutoPipeline);
+
+        using FFI.ConnectionConfig ffi = config.Request.ToFfi();
+        Assert.Equal(64u, ffi.AutoPipelineConfig!.Value.MaxBatchSize);
+        Assert.Equal(5u, ffi.AutoPipelineConfig!.Value.FlushIntervalMs);
+    }
+
+    [Fact]
+    public void WithAutoPipeline_InvalidValues_Throw()
+    {
+        var builder = new StandaloneClientConfigurationBuilder();
+        _ = Assert.Throws<ArgumentException>(() => builder.WithAutoPipeline(0, TimeSpan.FromMilliseconds(5)));
+        _ = Assert.Throws<ArgumentException>(() => builder.WithAutoPipeline(64, TimeSpan.Zero));
+    }
+
+    #endregion
+    #region Operation Class Request Timeout Tests
+
+    [Fact]
+    public void OperationClassRequestTimeouts_NotSet_AreNull()
+    {
+        var config = new StandaloneClientConfigurationBuilder().Build();
+        Assert.Null(config.Request.ReadRequestTimeout);
+        Assert.Null(config.Request.WriteRequestTimeout);
+    }
+
+    [Fact]
+    public void WithOperationClassRequestTimeouts_SetsTimeouts()
+    {
+        var config = new ClusterClientConfigurationBuilder()
+            .WithRequestTimeout(TimeSpan.FromMilliseconds(500))
+            .WithReadRequestTimeout(TimeSpan.FromMilliseconds(100))
+            .WithWriteRequestTimeout(TimeSpan.FromSeconds(2))
+            .Build();
+
+        Assert.Equal(TimeSpan.FromMilliseconds(500), config.Request.RequestTimeout);
+        Assert.Equal(TimeSpan.FromMilliseconds(100), config.Request.ReadRequestTimeout);
+        Assert.Equal(TimeSpan.FromSeconds(2), config.Request.WriteRequestTimeout);
+    }
+
+    #endregion
+    #region Key Prefix Tests
+
+    [Fact]
+    public void KeyPrefix_NotSet_IsNull()
+    {
+        var config = new StandaloneClientConfigurationBuilder().Build();
+        Assert.Null(config.Request.KeyPrefix);
+    }
+
+    [Fact]
+    public void WithKeyPrefix_SetsKeyPrefix()
+    {
+        var config = new ClusterClientConfigurationBuilder()
+            .WithKeyPrefix("service:")
+            .Build();
+
+        Assert.Equal("service:", config.Request.KeyPrefix);
+    }
+
     #endregion
     #region Helpers
 
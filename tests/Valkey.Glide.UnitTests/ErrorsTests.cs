@@ -0,0 +1,27 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+using static Valkey.Glide.Errors;
+
+namespace Valkey.Glide.UnitTests;
+
+public class ErrorsTests
+{
+    [Fact]
+    public void Create_ReadOnlyError_IsReadOnlyException()
+    {
+        GlideException error = Create(RequestErrorType.Unspecified, "READONLY You can't write against a read only replica.");
+        _ = Assert.IsType<ReadOnlyException>(error);
+        _ = Assert.IsAssignableFrom<RequestException>(error);
+        Assert.Equal("READONLY You can't write against a read only replica.", error.Message);
+    }
+
+    [Theory]
+    [InlineData("ERR unknown command 'READONLY'")]
+    [InlineData("READONLYX not a READONLY error")]
+    public void Create_OtherError_IsRequestException(string message)
+        => Assert.IsType<RequestException>(Create(RequestErrorType.Unspecified, message));
+
+    [Fact]
+    public void Create_ReadOnlyMessageWithOtherType_KeepsType()
+        => Assert.IsType<TimeoutException>(Create(RequestErrorType.Timeout, "READONLY You can't write against a read only replica."));
+}
@@ -49,6 +49,68 @@ public class PubSubMessageQueueTests
         Assert.Equal(0, queue.Count);
     }
 
+    [Fact]
+    public void DrainMessages_EmptyQueue_ReturnsEmpty()
+    {
+        // Arrange
+        using var queue = new PubSubMessageQueue();
+
+        // Act
+        IReadOnlyList<PubSubMessage> messages = queue.DrainMessages();
+
+        // Assert
+        Assert.Empty(messages);
+    }
+
+    [Fact]
+    public void DrainMessages_WithMessages_ReturnsAllInOrder()
+    {
+        // Arrange
+        using var queue = new PubSubMessageQueue();
+        for (int i = 0; i < 3; i++)
+        {
+            queue.EnqueueMessage(PubSubMessage.FromChannel($"message-{i}", "test-channel"));
+        }
+
+        // Act
+        IReadOnlyList<PubSubMessage> messages = queue.DrainMessages();
+
+        // Assert
+        Assert.Equal(["message-0", "message-1", "message-2"], messages.Select(m => m.Message));
+        Assert.Equal(0, queue.Count);
+        Assert.False(queue.TryGetMessage(out _));
+    }
+
+    [Fact]
+    public void DrainMessages_WithMaxCount_LeavesRemainingMessages()
+    {
+        // Arrange
+        using var queue = new PubSubMessageQueue();
+        for (int i = 0; i < 3; i++)
+        {
+            queue.EnqueueMessage(PubSubMessage.FromChannel($"message-{i}", "test-channel"));
+        }
+
+        // Act
+        IReadOnlyList<PubSubMessage> messages = queue.DrainMessages(2);
+
+        // Assert
+        Assert.Equal(["message-0", "message-1"], messages.Select(m => m.Message));
+        Assert.Equal(1, queue.Count);
+        Assert.True(queue.TryGetMessage(out PubSubMessage? remaining));
+        Assert.Equal("message-2", remaining!.Message);
+    }
+
+    [Fact]
+    public void DrainMessages_NonPositiveMaxCount_ThrowsArgumentOutOfRangeException()
+    {
+        // Arrange
+        using var queue = new PubSubMessageQueue();
+
+        // Act & Assert
+        _ = Assert.Throws<ArgumentOutOfRangeException>(() => queue.DrainMessages(0));
+    }
+
     [Fact]
     public void EnqueueMessage_NullMessage_ThrowsArgumentNullException()
     {
@@ -0,0 +1,90 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+using System.Globalization;
+using System.Numerics;
+
+using Valkey.Glide.Internals;
+
+using static Valkey.Glide.Errors;
+
+namespace Valkey.Glide.UnitTests;
+
+public class ResponseHandlerTests
+{
+    [Theory]
+    [InlineData(1.5)]
+    [InlineData(-0.0)]
+    [InlineData(double.PositiveInfinity)]
+    [InlineData(double.NegativeInfinity)]
+    [InlineData(double.MaxValue)]
+    public void ToDouble_RoundTripsBits(double value)
+        => Assert.Equal(value, ResponseHandler.ToDouble((nuint)BitConverter.DoubleToInt64Bits(value)));
+
+    [Fact]
+    public void ToDouble_CanonicalNaN_IsNaN()
+    {
+        // Bits of Rust's `f64::NAN`, which Rust sends for every NaN.
+        nuint canonicalNaN = unchecked((nuint)0x7FF8_0000_0000_0000UL);
+
+        Assert.True(double.IsNaN(ResponseHandler.ToDouble(canonicalNaN)));
+    }
+
+    [Theory]
+    [InlineData("0")]
+    [InlineData("3492890328409238509324850943850943825024385")]
+    [InlineData("-3492890328409238509324850943850943825024385")]
+    public void ToBigInteger_ParsesDecimalDigits(string digits)
+        => Assert.Equal(BigInteger.Parse(digits, CultureInfo.InvariantCulture), ResponseHandler.ToBigInteger(new GlideString(digits)));
+
+    [Theory]
+    [InlineData("txt:Some text", "txt", "Some text")]
+    [InlineData("mkd:# Title", "mkd", "# Title")]
+    [InlineData("txt:", "txt", "")]
+    public void SplitVerbatimString_SeparatesFormatFromText(string verbatim, string format, string text)
+    {
+        (string actualFormat, GlideString actualText) = ResponseHandler.SplitVerbatimString(new GlideString(verbatim));
+
+        Assert.Equal(format, actualFormat);
+        Assert.Equal(new GlideString(text), actualText);
+    }
+
+    [Theory]
+    [InlineData("txt")]
+    [InlineData("text:abc")]
+    public void SplitVerbatimString_WithoutFormat_Throws(string verbatim)
+        => Assert.Throws<RequestException>(() => ResponseHandler.SplitVerbatimString(new GlideString(verbatim)));
+
+    [Fact]
+    public void ToMapEntries_PairsKeysWithValues()
+    {
+        object?[] values = [new GlideString("a"), 1L, new GlideString("b"), null];
+
+        var entries = ResponseHandler.ToMapEntries(values);
+
+        Assert.Equal(new (object?, object?)[] { (new GlideString("a"), 1L), (new GlideString("b"), null) }, entries);
+    }
+
+    [Fact]
+    public void ToMapEntries_NestedKeysAndValues_AreSingleEntries()
+    {
+        object?[] key = [new GlideString("k1"), new GlideString("k2")];
+        object?[] value = [1L, 2L, 3L];
+        object?[] values = [key, value, new GlideString("c"), 4L];
+
+        var entries = ResponseHandler.ToMapEntries(values);
+
+        Assert.Equal(2, entries.Length);
+        Assert.Same(key, entries[0].Key);
+        Assert.Same(value, entries[0].Value);
+        Assert.Equal(new GlideString("c"), entries[1].Key);
+        Assert.Equal(4L, entries[1].Value);
+    }
+
+    [Fact]
+    public void ToMapEntries_Empty_ReturnsNoEntries()
+        => Assert.Empty(ResponseHandler.ToMapEntries([]));
+
+    [Fact]
+    public void ToMapEntries_OddNumberOfValues_Throws()
+        => Assert.Throws<RequestException>(() => ResponseHandler.ToMapEntries([new GlideString("a")]));
+}